@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use crate::core::omnia_types::Type;
+use crate::core::omnia_types::Type::{BOOL, BYTE, CHAR, CHARARR, DECIMAL, INT, LONG, NEVER, NULL, OMNI, UBYTE, UINT, ULONG};
+
+/// Handle of a type in a [TypeInterner]. Equal types get the same handle, so handles are compared and hashed
+/// without walking the type
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TypeId(u32);
+
+/// Primitive types, interned up front so they get the first handles
+const PRIMITIVES: [Type; 13] = [DECIMAL, OMNI, CHAR, CHARARR, BOOL, BYTE, UBYTE, INT, UINT, LONG, ULONG, NULL, NEVER];
+
+/// Table of every distinct type of a program. Composite types such as arrays, references, functions, structs and
+/// generic instances are interned whole, so each of them has one [TypeId] too
+pub struct TypeInterner {
+    types: Vec<Type>,
+    ids: HashMap<Type, TypeId>
+}
+impl Default for TypeInterner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TypeInterner {
+    pub fn new() -> Self {
+        let mut interner = Self {
+            types: Vec::new(),
+            ids: HashMap::new()
+        };
+        for primitive in PRIMITIVES {
+            interner.intern(&primitive);
+        }
+        interner
+    }
+    pub fn intern(&mut self, r#type: &Type) -> TypeId {
+        if let Some(id) = self.ids.get(r#type) {
+            return *id
+        }
+        let id = TypeId(self.types.len() as u32);
+        self.types.push(r#type.clone());
+        self.ids.insert(r#type.clone(), id);
+        id
+    }
+    pub fn get(&self, id: TypeId) -> &Type {
+        &self.types[id.0 as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::omnia_types::interner::{TypeId, TypeInterner, PRIMITIVES};
+    use crate::core::omnia_types::Type;
+    use crate::core::omnia_types::Type::{INT, LONG};
+
+    #[test]
+    fn primitives_get_the_first_handles() {
+        let mut interner = TypeInterner::new();
+        assert_eq!(interner.intern(&PRIMITIVES[0]), TypeId(0));
+        assert_eq!(interner.intern(&LONG), TypeId(PRIMITIVES.iter().position(|x| x == &LONG).unwrap() as u32));
+    }
+
+    #[test]
+    fn equal_composite_types_share_a_handle() {
+        let mut interner = TypeInterner::new();
+        let pair = Type::CUSTOM(String::from("Pair"), vec![INT, LONG]);
+        let id = interner.intern(&pair);
+        assert_eq!(interner.intern(&Type::CUSTOM(String::from("Pair"), vec![INT, LONG])), id);
+        assert_ne!(interner.intern(&Type::CUSTOM(String::from("Pair"), vec![LONG, INT])), id);
+        assert_eq!(interner.get(id), &pair);
+    }
+}
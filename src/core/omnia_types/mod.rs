@@ -1,20 +1,19 @@
-#![feature(f16)]
-use std::alloc::{alloc, Layout};
-use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{DefaultHasher, Hash, Hasher};
-use std::io::Error;
-use std::sync::Arc;
 use downcast_rs::impl_downcast;
-use num_traits::{AsPrimitive, FromPrimitive};
-use crate::core::omnia_types::Type::{ANYNUM, BOOL, BYTE, CHAR, CHARARR, DECIMAL, INT, LONG, NULL, OMNI, OTHER, STRLIKE, UBYTE, UINT, ULONG};
+use num_traits::FromPrimitive;
+use crate::core::omnia_types::Type::{BOOL, BYTE, CHAR, CHARARR, CUSTOM, DECIMAL, FUNCTION, GENERIC, INT, LONG, NEVER, NULL, NULLABLE, OMNI, REFERENCE, RESULT, TUPLE, ARRAY, UBYTE, UINT, ULONG};
 use crate::core::utils::numeric_utils::omni::f128;
 use crate::lexer::token::TokenType;
 use crate::lexer::token::TokenType::DECIMALKW;
 use crate::parser::CompilerError;
 use crate::parser::CompilerError::TypeError;
 
-#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+pub mod interner;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, PartialEq, Debug, Eq, Hash)]
 pub enum Type {
     DECIMAL,
     OMNI,
@@ -30,30 +29,299 @@ pub enum Type {
     ULONG,
     NULL,
 
-    ANYNUM,
-    STRLIKE,
-    OTHER,
+    FUNCTION(Vec<Type>, Box<Type>),
+    GENERIC(String),
+    CUSTOM(String, Vec<Type>),
+    NULLABLE(Box<Type>),
+    /// Success type and error type, written `T ! E`
+    RESULT(Box<Type>, Box<Type>),
+    TUPLE(Vec<Type>),
+    ARRAY(Box<Type>, ArraySize),
+    /// Address of a value of the inner type, written `&T`
+    REFERENCE(Box<Type>),
+    /// Type of expressions that never produce a value, e.g. the missing side of `ok(x)` or `err(e)`
+    NEVER
+}
+
+/// What a cast between two types does to the value, see [Type::conversion]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Conversion {
+    /// Every value is kept, e.g. `int` to `long` or `char` to `int`
+    Lossless,
+    /// Some values don't fit, e.g. `long` to `int` or `decimal` to `int`, and the [CastMode] decides what happens to them
+    Narrowing,
+    /// The cast is not allowed
+    Invalid
+}
+
+/// How a cast treats a value its target type can't represent
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CastMode {
+    /// `x as T`: the value must fit. Constant evaluation fails on it and the program traps at runtime
+    Exact,
+    /// `x as? T`: `null` when the value doesn't fit, so the cast has type `T?`
+    Checked,
+    /// `x as saturating T`: the nearest value of `T`, and 0 for NaN
+    Saturating,
+    /// `x as wrapping T`: the low bits of the value, as many as `T` has
+    Wrapping
+}
+impl CastMode {
+    /// Converts an integer into the integer-like type `target`. `None` when an exact or checked cast doesn't fit
+    pub fn integer(&self, value: i128, target: &Type) -> Option<i128> {
+        let (min, max) = target.integer_range()?;
+        match self {
+            CastMode::Exact | CastMode::Checked => { Some(value).filter(|x| (min..=max).contains(x)) }
+            CastMode::Saturating => { Some(value.clamp(min, max)) }
+            CastMode::Wrapping => { Some((value - min).rem_euclid(max - min + 1) + min) }
+        }
+    }
+    /// Converts a decimal into the integer type `target`, dropping the fraction first
+    pub fn decimal(&self, value: f64, target: &Type) -> Option<i128> {
+        if value.is_nan() {
+            return match self {
+                CastMode::Exact | CastMode::Checked => { None }
+                CastMode::Saturating | CastMode::Wrapping => { Some(0) }
+            }
+        }
+        self.integer(value.trunc() as i128, target)
+    }
+}
+impl Display for CastMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CastMode::Exact => { write!(f, "as") }
+            CastMode::Checked => { write!(f, "as?") }
+            CastMode::Saturating => { write!(f, "as saturating") }
+            CastMode::Wrapping => { write!(f, "as wrapping") }
+        }
+    }
+}
 
+/// Length of an array type, either written out or named by a `static` constant until the semantic analyzer folds it.
+/// Arrays whose length is only known at runtime are unsized and used through a reference, e.g. `&char[][]`
+#[derive(Clone, PartialEq, Debug, Eq, Hash)]
+pub enum ArraySize {
+    Fixed(u64),
+    Constant(String),
+    Unsized
+}
+impl Display for ArraySize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArraySize::Fixed(n) => { write!(f, "{}", n) }
+            ArraySize::Constant(name) => { write!(f, "{}", name) }
+            ArraySize::Unsized => { Ok(()) }
+        }
+    }
 }
 
 impl Type {
     pub fn from_token_type(t: &TokenType) -> Result<Type, CompilerError> {
-        match t {
-            &DECIMALKW => Ok(DECIMAL),
-            &TokenType::OMNIKW => Ok(OMNI),
-            &TokenType::CHARKW => Ok(CHAR),
-            &TokenType::BOOLKW => Ok(BOOL),
-            &TokenType::BYTEKW => Ok(BYTE),
-            &TokenType::UBYTEKW => Ok(UBYTE),
-            &TokenType::INTKW => Ok(INT),
-            &TokenType::UINTKW => Ok(UINT),
-            &TokenType::LONGKW => Ok(LONG),
-            &TokenType::ULONGKW => Ok(ULONG),
-            &TokenType::NULLKW => Ok(NULL),
-            &TokenType::CHARARRKW => Ok(CHARARR),
+        match *t {
+            DECIMALKW => Ok(DECIMAL),
+            TokenType::OMNIKW => Ok(OMNI),
+            TokenType::CHARKW => Ok(CHAR),
+            TokenType::BOOLKW => Ok(BOOL),
+            TokenType::BYTEKW => Ok(BYTE),
+            TokenType::UBYTEKW => Ok(UBYTE),
+            TokenType::INTKW => Ok(INT),
+            TokenType::UINTKW => Ok(UINT),
+            TokenType::LONGKW => Ok(LONG),
+            TokenType::ULONGKW => Ok(ULONG),
+            TokenType::NULLKW => Ok(NULL),
+            TokenType::CHARARRKW => Ok(CHARARR),
             _ => Err(TypeError(String::from("Unexpected token type for type")))
         }
     }
+    /// Replaces type parameters with the types bound to them
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> Type {
+        match self {
+            GENERIC(name) => { bindings.get(name).cloned().unwrap_or(self.clone()) }
+            FUNCTION(params, returns) => { FUNCTION(params.iter().map(|x| x.substitute(bindings)).collect(), Box::from(returns.substitute(bindings))) }
+            CUSTOM(name, args) => { CUSTOM(name.clone(), args.iter().map(|x| x.substitute(bindings)).collect()) }
+            NULLABLE(inner) => { inner.substitute(bindings).nullable() }
+            RESULT(value, error) => { RESULT(Box::from(value.substitute(bindings)), Box::from(error.substitute(bindings))) }
+            TUPLE(items) => { TUPLE(items.iter().map(|x| x.substitute(bindings)).collect()) }
+            ARRAY(item, size) => { ARRAY(Box::from(item.substitute(bindings)), size.clone()) }
+            REFERENCE(inner) => { REFERENCE(Box::from(inner.substitute(bindings))) }
+            _ => { self.clone() }
+        }
+    }
+    /// Whether a type parameter appears in the type, which is then only concrete once it is substituted
+    pub fn is_generic(&self) -> bool {
+        match self {
+            GENERIC(_) => { true }
+            FUNCTION(params, returns) => { params.iter().any(Type::is_generic) || returns.is_generic() }
+            CUSTOM(_, items) | TUPLE(items) => { items.iter().any(Type::is_generic) }
+            NULLABLE(inner) | REFERENCE(inner) | ARRAY(inner, _) => { inner.is_generic() }
+            RESULT(value, error) => { value.is_generic() || error.is_generic() }
+            _ => { false }
+        }
+    }
+    /// Matches `actual` against `self`, binding type parameters found in `self`. Fails on conflicting bindings
+    pub fn bind(&self, actual: &Type, bindings: &mut HashMap<String, Type>) -> bool {
+        match (self, actual) {
+            (GENERIC(name), _) => {
+                match bindings.get(name) {
+                    Some(bound) => { bound == actual }
+                    None => { bindings.insert(name.clone(), actual.clone()); true }
+                }
+            }
+            (FUNCTION(params, returns), FUNCTION(a_params, a_returns)) => {
+                params.len() == a_params.len()
+                    && params.iter().zip(a_params).all(|(x, y)| x.bind(y, bindings))
+                    && returns.bind(a_returns, bindings)
+            }
+            (CUSTOM(name, args), CUSTOM(a_name, a_args)) => {
+                name == a_name && args.len() == a_args.len() && args.iter().zip(a_args).all(|(x, y)| x.bind(y, bindings))
+            }
+            (NULLABLE(inner), NULLABLE(a_inner)) | (REFERENCE(inner), REFERENCE(a_inner)) => { inner.bind(a_inner, bindings) }
+            (TUPLE(items), TUPLE(a_items)) => {
+                items.len() == a_items.len() && items.iter().zip(a_items).all(|(x, y)| x.bind(y, bindings))
+            }
+            (RESULT(value, error), RESULT(a_value, a_error)) => {
+                (a_value.as_ref() == &NEVER || value.bind(a_value, bindings)) && (a_error.as_ref() == &NEVER || error.bind(a_error, bindings))
+            }
+            _ => { self == actual }
+        }
+    }
+    /// Types a value of this type can be implicitly widened to in one step. Together they form the promotion lattice
+    ///
+    /// ```text
+    /// byte  ──→ int  ──→ long  ──→ decimal ──→ omni
+    ///         ↗        ↗         ↗
+    /// ubyte ──→ uint ──→ ulong
+    /// ```
+    ///
+    /// An unsigned value widens to the signed type of the next size, never to one of its own size, and no signed
+    /// value ever becomes unsigned implicitly. Every other numeric conversion may lose information and needs a cast
+    fn widens_to(&self) -> Vec<Type> {
+        match self {
+            BYTE => vec![INT],
+            UBYTE => vec![UINT, INT],
+            INT => vec![LONG],
+            UINT => vec![ULONG, LONG],
+            LONG => vec![DECIMAL],
+            ULONG => vec![DECIMAL],
+            DECIMAL => vec![OMNI],
+            _ => vec![]
+        }
+    }
+    pub fn is_integer(&self) -> bool {
+        matches!(self, BYTE | UBYTE | INT | UINT | LONG | ULONG)
+    }
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || matches!(self, DECIMAL | OMNI)
+    }
+    /// Whether converting a number of this type to `target` may lose information, so it has to be written as a cast
+    pub fn is_lossy_to(&self, target: &Type) -> bool {
+        self.non_null().is_numeric() && target.non_null().is_numeric() && !self.promotes_to(target)
+    }
+    /// Smallest and largest value of an integer-like type. `char` is a UTF-16 code unit and `bool` is 0 or 1
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        match self {
+            BYTE => { Some((i8::MIN as i128, i8::MAX as i128)) }
+            UBYTE => { Some((0, u8::MAX as i128)) }
+            INT => { Some((i32::MIN as i128, i32::MAX as i128)) }
+            UINT => { Some((0, u32::MAX as i128)) }
+            LONG => { Some((i64::MIN as i128, i64::MAX as i128)) }
+            ULONG => { Some((0, u64::MAX as i128)) }
+            CHAR => { Some((0, u16::MAX as i128)) }
+            BOOL => { Some((0, 1)) }
+            _ => { None }
+        }
+    }
+    /// Conversion matrix of casts. Numbers convert to each other, `char` and `bool` convert to numbers and integers
+    /// convert to `char`. Nothing converts to `bool`, from a possibly null value, or from `char[]` and composite types
+    ///
+    /// | from \ to        | integer        | decimal, omni  | char       | bool    |
+    /// |------------------|----------------|----------------|------------|---------|
+    /// | integer          | by range       | lossless¹      | by range   | invalid |
+    /// | decimal, omni    | narrowing      | by promotion   | invalid    | invalid |
+    /// | char             | by range       | lossless       | lossless   | invalid |
+    /// | bool             | lossless       | lossless       | invalid    | lossless|
+    ///
+    /// ¹ `long` and `ulong` to `decimal` are promotions even though large values are rounded
+    pub fn conversion(&self, target: &Type) -> Conversion {
+        if self == target {
+            return Conversion::Lossless
+        }
+        if let NULLABLE(inner) = target {
+            return if self.is_nullable() { Conversion::Invalid } else { self.conversion(inner) }
+        }
+        match (self.integer_range(), target.integer_range()) {
+            _ if target == &BOOL || self == &BOOL && target == &CHAR => { Conversion::Invalid }
+            (Some((min, max)), Some((t_min, t_max))) => {
+                if t_min <= min && max <= t_max { Conversion::Lossless } else { Conversion::Narrowing }
+            }
+            (Some(_), None) if matches!(target, DECIMAL | OMNI) => { Conversion::Lossless }
+            (None, Some(_)) if matches!(self, DECIMAL | OMNI) && target != &CHAR => { Conversion::Narrowing }
+            (None, None) if self.is_numeric() && target.is_numeric() => {
+                if self.promotes_to(target) { Conversion::Lossless } else { Conversion::Narrowing }
+            }
+            _ => { Conversion::Invalid }
+        }
+    }
+    /// A type that also admits `null`. Nullable types and `null` itself are left as they are
+    pub fn nullable(self) -> Type {
+        match self {
+            NULL | NULLABLE(_) => { self }
+            _ => { NULLABLE(Box::from(self)) }
+        }
+    }
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, NULL | NULLABLE(_))
+    }
+    /// The type a value has once it is known not to be `null`
+    pub fn non_null(&self) -> &Type {
+        match self {
+            NULLABLE(inner) => { inner }
+            _ => { self }
+        }
+    }
+    pub fn promotes_to(&self, target: &Type) -> bool {
+        if self == &NEVER {
+            return true
+        }
+        if let (RESULT(value, error), RESULT(t_value, t_error)) = (self, target) {
+            return value.promotes_to(t_value) && error.promotes_to(t_error)
+        }
+        if let (TUPLE(items), TUPLE(t_items)) = (self, target) {
+            return items.len() == t_items.len() && items.iter().zip(t_items).all(|(x, y)| x.promotes_to(y))
+        }
+        if let NULLABLE(inner) = target {
+            return self == &NULL || self.non_null().promotes_to(inner)
+        }
+        self == target || self.widens_to().iter().any(|x| x.promotes_to(target))
+    }
+    /// The narrowest type both `self` and `other` promote to, if any
+    pub fn common_with(&self, other: &Type) -> Option<Type> {
+        if let (RESULT(value, error), RESULT(o_value, o_error)) = (self, other) {
+            return Some(RESULT(Box::from(value.common_with(o_value)?), Box::from(error.common_with(o_error)?)))
+        }
+        if let (TUPLE(items), TUPLE(o_items)) = (self, other) {
+            if items.len() != o_items.len() {
+                return None
+            }
+            return items.iter().zip(o_items).map(|(x, y)| x.common_with(y)).collect::<Option<Vec<Type>>>().map(TUPLE)
+        }
+        if self.is_nullable() || other.is_nullable() {
+            return match (self, other) {
+                (NULL, NULL) => { Some(NULL) }
+                (NULL, _) => { Some(other.clone().nullable()) }
+                (_, NULL) => { Some(self.clone().nullable()) }
+                _ => { self.non_null().common_with(other.non_null()).map(Type::nullable) }
+            }
+        }
+        if other.promotes_to(self) {
+            return Some(self.clone())
+        }
+        if self.promotes_to(other) {
+            return Some(other.clone())
+        }
+        [INT, UINT, LONG, ULONG, DECIMAL, OMNI].into_iter().find(|x| self.promotes_to(x) && other.promotes_to(x))
+    }
 }
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -70,30 +338,56 @@ impl Display for Type {
             LONG => { write!(f, "long") }
             ULONG => { write!(f, "ulong") }
             NULL => { write!(f, "null") }
-            _ => { write!(f, "Deprecated") }
+            FUNCTION(params, returns) => {
+                write!(f, "(")?;
+                for (i, val) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", val)?;
+                }
+                write!(f, ") -> {}", returns)
+            }
+            GENERIC(name) => { write!(f, "{}", name) }
+            CUSTOM(name, args) => {
+                write!(f, "{}", name)?;
+                if !args.is_empty() {
+                    write!(f, "<")?;
+                    for (i, val) in args.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", val)?;
+                    }
+                    write!(f, ">")?;
+                }
+                Ok(())
+            }
+            RESULT(value, error) => { write!(f, "{} ! {}", value, error) }
+            ARRAY(item, size) => { write!(f, "{}[{}]", item, size) }
+            TUPLE(items) => {
+                write!(f, "(")?;
+                for (i, val) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", val)?;
+                }
+                write!(f, ")")
+            }
+            NEVER => { write!(f, "never") }
+            NULLABLE(inner) => {
+                match inner.as_ref() {
+                    FUNCTION(_, _) => { write!(f, "({})?", inner) }
+                    _ => { write!(f, "{}?", inner) }
+                }
+            }
+            REFERENCE(inner) => { write!(f, "&{}", inner) }
         }
     }
 }
 
-#[derive(PartialEq, Copy, Clone)]
-pub struct Pair<T, S> {
-    left: T,
-    right: S
-}
-impl Pair<Type, Type> {
-    pub fn new(left: Type, right: Type) -> Pair<Type, Type> {
-        Self {
-            left,
-            right
-        }
-    }
-    pub fn get_right(&self) -> &Type {
-        &self.right
-    }
-    pub fn get_left(&self) -> &Type {
-        &self.left
-    }
-}
+
 
 
 
@@ -101,7 +395,7 @@ pub trait OmniaValue: downcast_rs::Downcast {
     fn get_as_int32(&self) -> i32;
     fn get_as_string(&self) -> String;
     fn get_as_decimal(&self) -> f64;
-    fn get_type(&self) -> Pair<Type, Type>;
+    fn get_type(&self) -> Type;
 
 }
 impl_downcast!(OmniaValue);
@@ -146,8 +440,8 @@ impl OmniaValue for OmniaDecimal {
     fn get_as_decimal(&self) -> f64 {
         self.value
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, DECIMAL)
+    fn get_type(&self) -> Type {
+        DECIMAL
 
     }
 
@@ -168,6 +462,23 @@ impl OmniaOmni {
         T::from(self.value.clone())
     }
 }
+impl OmniaValue for OmniaOmni {
+
+    fn get_as_int32(&self) -> i32 {
+        self.value.get().to_i64().map_or(0, |x| x as i32)
+    }
+    fn get_as_string(&self) -> String {
+        self.get_value_as::<String>()
+    }
+    fn get_as_decimal(&self) -> f64 {
+        self.value.get().to_f64()
+    }
+    fn get_type(&self) -> Type {
+        OMNI
+
+    }
+
+}
 
 //SECTION::Floats end
 
@@ -209,8 +520,8 @@ impl OmniaValue for OmniaChar {
     fn get_as_decimal(&self) -> f64 {
         (self.value as u8) as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(STRLIKE, CHAR)
+    fn get_type(&self) -> Type {
+        CHAR
 
     }
 
@@ -256,8 +567,8 @@ impl OmniaValue for OmniaChararr {
         self.value.hash(&mut hasher);
         hasher.finish() as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(STRLIKE, CHARARR)
+    fn get_type(&self) -> Type {
+        CHARARR
 
     }
 
@@ -323,8 +634,8 @@ impl OmniaValue for OmniaBool {
     fn get_as_decimal(&self) -> f64 {
         (self.value as u8) as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(OTHER, BOOL)
+    fn get_type(&self) -> Type {
+        BOOL
 
     }
 
@@ -372,8 +683,8 @@ impl OmniaValue for OmniaByte {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, BYTE)
+    fn get_type(&self) -> Type {
+        BYTE
     }
 }
 #[derive(Clone)]
@@ -410,8 +721,8 @@ impl OmniaValue for OmniaUByte {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, UBYTE)
+    fn get_type(&self) -> Type {
+        UBYTE
     }
 }
 //SECTION::Byte end
@@ -451,8 +762,8 @@ impl OmniaValue for OmniaInt {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, INT)
+    fn get_type(&self) -> Type {
+        INT
     }
 }
 #[derive(Clone)]
@@ -488,8 +799,8 @@ impl OmniaValue for OmniaUInt {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, UINT)
+    fn get_type(&self) -> Type {
+        UINT
     }
 }
 //SECTION::Int end
@@ -552,8 +863,8 @@ impl OmniaValue for OmniaLong {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, LONG)
+    fn get_type(&self) -> Type {
+        LONG
     }
 }
 impl OmniaValue for OmniaULong {
@@ -566,8 +877,8 @@ impl OmniaValue for OmniaULong {
     fn get_as_decimal(&self) -> f64 {
         self.value as f64
     }
-    fn get_type(&self) -> Pair<Type, Type> {
-        Pair::new(ANYNUM, ULONG)
+    fn get_type(&self) -> Type {
+        ULONG
     }
 }
 //SECTION::Long end
@@ -0,0 +1,72 @@
+use std::fmt::{Display, Formatter};
+
+/// JSON value. Object keys keep their insertion order, so the same input always gives the same text
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+impl Json {
+    pub fn object() -> Self {
+        Json::Object(Vec::new())
+    }
+    pub fn string(value: impl ToString) -> Self {
+        Json::String(value.to_string())
+    }
+    /// Adds a key to an object, does nothing to other values
+    pub fn with(mut self, key: &str, value: Json) -> Self {
+        if let Json::Object(entries) = &mut self {
+            entries.push((String::from(key), value));
+        }
+        self
+    }
+}
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => { write!(f, "null") }
+            Json::Bool(v) => { write!(f, "{}", v) }
+            Json::Number(v) => { write!(f, "{}", v) }
+            Json::String(v) => { write_escaped(f, v) }
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, val) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", val)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, val)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ":{}", val)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+fn write_escaped(f: &mut Formatter<'_>, value: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => { write!(f, "\\\"")? }
+            '\\' => { write!(f, "\\\\")? }
+            '\n' => { write!(f, "\\n")? }
+            '\r' => { write!(f, "\\r")? }
+            '\t' => { write!(f, "\\t")? }
+            c if (c as u32) < 0x20 => { write!(f, "\\u{:04x}", c as u32)? }
+            c => { write!(f, "{}", c)? }
+        }
+    }
+    write!(f, "\"")
+}
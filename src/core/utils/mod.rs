@@ -1,2 +1,3 @@
 pub mod stringutils;
-pub mod numeric_utils;
\ No newline at end of file
+pub mod numeric_utils;
+pub mod json;
@@ -3,6 +3,7 @@
 pub mod omni {
     use num_bigfloat::BigFloat;
 
+    #[allow(non_camel_case_types)]
     #[derive(Clone)]
     pub struct f128 {
         _val: BigFloat
@@ -16,7 +17,7 @@ pub mod omni {
             }
         }
         pub fn get(&self) -> BigFloat {
-            self._val.clone()
+            self._val
         }
     }
 
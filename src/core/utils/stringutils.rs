@@ -3,6 +3,11 @@
 pub struct StringBuilder {
     buffer: Vec<char>
 }
+impl Default for StringBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl StringBuilder {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,439 @@
+//! # Emitting the compiler's view of a file
+//! ***
+//!
+//! `--emit=tokens,ast` writes the tokens and the AST of the input as JSON, for editor plugins and test snapshots.
+//! `--emit=types` writes the types inferred for the `mk` bindings once the input is analyzed, which editors show on hover.
+//! `--emit=layouts` writes the size, alignment and field offsets of the declared structs and enums, for FFI bindings.
+//! Every document carries [FORMAT_VERSION], which is raised whenever the shape of the output changes
+use crate::core::omnia_types::Type;
+use crate::core::utils::json::Json;
+use crate::generator::layout::Layouts;
+use crate::generator::semantics::SemanticsAnalyzer;
+use crate::lexer::token::{Span, Token};
+use crate::parser::ast::nodes::{ASTNode, AttributeNode, BlockStatementNode, Expression, FunctionCallNode, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, LambdaExpressionNode, LiteralExpression, MethodCallNode, Pattern, PatternBindings, Statement, UnaryExpressionNode, VariantPayload, VariantValues};
+use crate::parser::ast::visit::{walk_block, Visitor};
+
+pub const FORMAT_VERSION: i128 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Emit {
+    Tokens,
+    Ast,
+    Types,
+    Layouts
+}
+impl Emit {
+    /// Parses the comma separated list of `--emit=tokens,ast`
+    pub fn parse_list(list: &str) -> Result<Vec<Emit>, String> {
+        list.split(',').map(|x| match x.trim() {
+            "tokens" => { Ok(Emit::Tokens) }
+            "ast" => { Ok(Emit::Ast) }
+            "types" => { Ok(Emit::Types) }
+            "layouts" => { Ok(Emit::Layouts) }
+            other => { Err(format!("Unknown emit kind '{}', expected tokens, ast, types or layouts", other)) }
+        }).collect()
+    }
+    /// Extension of the file the output is written to, next to the input
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Emit::Tokens => { "tokens.json" }
+            Emit::Ast => { "ast.json" }
+            Emit::Types => { "types.json" }
+            Emit::Layouts => { "layouts.json" }
+        }
+    }
+}
+
+pub fn tokens(tokens: &[Token], source: &str) -> Json {
+    let tokens = tokens.iter().map(|x| Json::object()
+        .with("type", Json::string(format!("{:?}", x.t_type)))
+        .with("value", Json::string(&x.t_value))
+        .with("span", span(&x.get_span(), source))
+    ).collect();
+    Json::object()
+        .with("version", Json::Number(FORMAT_VERSION))
+        .with("tokens", Json::Array(tokens))
+}
+
+/// The AST of a program. Statements held by blocks carry the span they were parsed from
+pub fn ast(root: &BlockStatementNode, source: &str) -> Json {
+    Json::object()
+        .with("version", Json::Number(FORMAT_VERSION))
+        .with("ast", block(root, source))
+}
+
+/// Types of the `mk` bindings of an analyzed program, the ones annotated on their initializers, see
+/// [get_type_of][SemanticsAnalyzer::get_type_of]. Each binding carries the span of the statement declaring it
+pub fn types(analyzer: &SemanticsAnalyzer, source: &str) -> Json {
+    let mut bindings = Bindings { analyzer, source, found: Vec::new() };
+    bindings.visit_block(analyzer.get_input());
+    Json::object()
+        .with("version", Json::Number(FORMAT_VERSION))
+        .with("bindings", Json::Array(bindings.found))
+}
+
+/// Layouts of the top-level structs and enums of an analyzed program. Generic ones are left out, they are only laid out
+/// for each instance, and so are the ones which can't be laid out, which are errors of the analysis
+pub fn layouts(root: &BlockStatementNode, layouts: &Layouts) -> Json {
+    let mut structs: Vec<Json> = Vec::new();
+    let mut enums: Vec<Json> = Vec::new();
+    for node in root.get_body() {
+        match node {
+            ASTNode::Statement(Statement::StructDeclaration(s)) if s.get_generics().is_empty() => {
+                if let Ok(layout) = layouts.struct_layout(s.get_name(), &Vec::new()) {
+                    structs.push(Json::object()
+                        .with("name", Json::string(s.get_name()))
+                        .with("size", Json::Number(layout.size as i128))
+                        .with("align", Json::Number(layout.align as i128))
+                        .with("fields", offsets(&layout.fields)));
+                }
+            }
+            ASTNode::Statement(Statement::EnumDeclaration(e)) if e.get_generics().is_empty() => {
+                if let Ok(layout) = layouts.enum_layout(e.get_name(), &Vec::new()) {
+                    let variants = layout.variants.iter().map(|x| Json::object()
+                        .with("name", Json::string(&x.name))
+                        .with("tag", Json::Number(x.tag as i128))
+                        .with("fields", offsets(&x.fields))
+                    ).collect();
+                    enums.push(Json::object()
+                        .with("name", Json::string(e.get_name()))
+                        .with("size", Json::Number(layout.size as i128))
+                        .with("align", Json::Number(layout.align as i128))
+                        .with("tag_size", Json::Number(layout.tag_size as i128))
+                        .with("variants", Json::Array(variants)));
+                }
+            }
+            _ => {}
+        }
+    }
+    Json::object()
+        .with("version", Json::Number(FORMAT_VERSION))
+        .with("structs", Json::Array(structs))
+        .with("enums", Json::Array(enums))
+}
+
+fn offsets(fields: &[(String, u64)]) -> Json {
+    Json::Array(fields.iter().map(|(name, offset)| Json::object().with("name", Json::string(name)).with("offset", Json::Number(*offset as i128))).collect())
+}
+
+struct Bindings<'a> {
+    analyzer: &'a SemanticsAnalyzer,
+    source: &'a str,
+    found: Vec<Json>
+}
+impl Bindings<'_> {
+    fn type_of(&self, expression: &Expression) -> Option<Type> {
+        self.analyzer.get_type_of(expression).map(|x| self.analyzer.get_interner().get(x).clone())
+    }
+}
+impl Visitor for Bindings<'_> {
+    fn visit_block(&mut self, block: &BlockStatementNode) {
+        for (i, node) in block.get_body().iter().enumerate() {
+            let names: Vec<(&String, Option<Type>)> = match node {
+                ASTNode::Statement(Statement::VariableCreation(v)) if v.get_type().is_none() => { vec![(v.get_name(), self.type_of(v.get_value()))] }
+                ASTNode::Statement(Statement::Destructuring(d)) => {
+                    let types = match self.type_of(d.get_value()) {
+                        Some(Type::TUPLE(types)) => { types }
+                        _ => { Vec::new() }
+                    };
+                    d.get_names().iter().enumerate().map(|(i, x)| (x, types.get(i).cloned())).collect()
+                }
+                _ => { Vec::new() }
+            };
+            for (name, inferred) in names {
+                let (Some(t), false) = (inferred, name == "_") else {
+                    continue
+                };
+                let binding = Json::object().with("name", Json::string(name)).with("type", r#type(&t));
+                self.found.push(match block.get_span(i) {
+                    Some(s) => { binding.with("span", span(&s, self.source)) }
+                    None => { binding }
+                });
+            }
+        }
+        walk_block(self, block)
+    }
+}
+
+fn span(span: &Span, source: &str) -> Json {
+    let (line, column) = span.line_column(source);
+    Json::object()
+        .with("start", Json::Number(span.start as i128))
+        .with("end", Json::Number(span.end as i128))
+        .with("line", Json::Number(line as i128))
+        .with("column", Json::Number(column as i128))
+}
+
+fn kind(name: &str) -> Json {
+    Json::object().with("kind", Json::string(name))
+}
+
+fn r#type(t: &Type) -> Json {
+    Json::string(t)
+}
+
+fn list<T>(values: &[T], f: impl Fn(&T) -> Json) -> Json {
+    Json::Array(values.iter().map(f).collect())
+}
+
+fn block(b: &BlockStatementNode, source: &str) -> Json {
+    let body = b.get_body().iter().enumerate().map(|(i, x)| {
+        let json = node(x, source);
+        match b.get_span(i) {
+            Some(s) => { json.with("span", span(&s, source)) }
+            None => { json }
+        }
+    }).collect();
+    kind("Block").with("body", Json::Array(body))
+}
+
+fn node(n: &ASTNode, source: &str) -> Json {
+    match n {
+        ASTNode::Statement(s) => { statement(s, source) }
+        ASTNode::Expression(e) => { expression(e, source) }
+    }
+}
+
+fn statement(s: &Statement, source: &str) -> Json {
+    match s {
+        Statement::If(i) => { if_node(i, source) }
+        Statement::Block(b) => { block(b, source) }
+        Statement::Plug(p) => { kind("Plug").with("lib", Json::string(p.get_lib())) }
+        Statement::FunctionDeclaration(f) => { function_declaration(f, source) }
+        Statement::FunctionCall(c) => { function_call(c, source) }
+        Statement::Assignment(a) => {
+            kind("Assignment")
+                .with("target", Json::string(a.get_cont()))
+                .with("op", Json::string(a.get_op()))
+                .with("value", expression(a.get_value(), source))
+        }
+        Statement::VariableCreation(v) => {
+            kind("VariableCreation")
+                .with("name", Json::string(v.get_name()))
+                .with("mutable", Json::Bool(v.is_mutable()))
+                .with("type", v.get_type().map(r#type).unwrap_or(Json::Null))
+                .with("value", expression(v.get_value(), source))
+        }
+        Statement::Return(r) => { kind("Return").with("value", node(r.get_value(), source)).with("tail", Json::Bool(r.is_tail())) }
+        Statement::LambdaDeclaration(l) => {
+            kind("LambdaDeclaration")
+                .with("name", Json::string(l.get_name()))
+                .with("mutable", Json::Bool(l.is_mutable()))
+                .with("lambda", lambda(l.get_lambda(), source))
+        }
+        Statement::StructDeclaration(s) => {
+            kind("StructDeclaration")
+                .with("name", Json::string(s.get_name()))
+                .with("attributes", list(s.get_attributes(), attribute))
+                .with("generics", list(s.get_generics(), generic))
+                .with("fields", list(s.get_fields(), |x| Json::object().with("name", Json::string(x.get_name())).with("type", r#type(x.get_type()))))
+                .with("visible", Json::Bool(s.is_visible()))
+        }
+        Statement::EnumDeclaration(e) => {
+            let variants = list(e.get_variants(), |x| {
+                let payload = match x.get_payload() {
+                    VariantPayload::Unit => { Json::Null }
+                    VariantPayload::Tuple(types) => { list(types, r#type) }
+                    VariantPayload::Struct(fields) => { list(fields, |x| Json::object().with("name", Json::string(x.get_name())).with("type", r#type(x.get_type()))) }
+                };
+                Json::object().with("name", Json::string(x.get_name())).with("payload", payload)
+            });
+            kind("EnumDeclaration")
+                .with("name", Json::string(e.get_name()))
+                .with("generics", list(e.get_generics(), generic))
+                .with("variants", variants)
+                .with("visible", Json::Bool(e.is_visible()))
+        }
+        Statement::Switch(s) => {
+            kind("Switch")
+                .with("value", expression(s.get_value(), source))
+                .with("arms", list(s.get_arms(), |x| Json::object().with("pattern", pattern(x.get_pattern(), source)).with("body", statement(x.get_body(), source))))
+        }
+        Statement::InterfaceDeclaration(i) => {
+            let methods = list(i.get_methods(), |x| Json::object()
+                .with("name", Json::string(x.get_name()))
+                .with("args", list(x.get_args(), |x| expression(x, source)))
+                .with("returns", r#type(x.get_return_type()))
+            );
+            kind("InterfaceDeclaration")
+                .with("name", Json::string(i.get_name()))
+                .with("methods", methods)
+                .with("visible", Json::Bool(i.is_visible()))
+        }
+        Statement::Extension(e) => {
+            kind("Extension")
+                .with("interface", e.get_interface().as_ref().map(Json::string).unwrap_or(Json::Null))
+                .with("target", r#type(e.get_target()))
+                .with("methods", list(e.get_methods(), |x| Json::object().with("override", Json::Bool(x.is_override())).with("function", function_declaration(x.get_function(), source))))
+        }
+        Statement::MethodCall(m) => { method_call(m, source) }
+        Statement::Propagate(p) => { kind("Propagate").with("value", expression(p.get_value(), source)) }
+        Statement::Unary(u) => { unary(u, source) }
+        Statement::Declaration(d) => {
+            kind("Declaration")
+                .with("names", list(d.get_names(), |x| Json::string(x)))
+                .with("mutable", Json::Bool(d.is_mutable()))
+                .with("type", r#type(d.get_type()))
+        }
+        Statement::Destructuring(d) => {
+            kind("Destructuring")
+                .with("names", list(d.get_names(), |x| Json::string(x)))
+                .with("value", expression(d.get_value(), source))
+        }
+        Statement::Static(s) => {
+            kind("Static")
+                .with("name", Json::string(s.get_name()))
+                .with("type", r#type(s.get_type()))
+                .with("value", expression(s.get_value(), source))
+        }
+    }
+}
+
+fn expression(e: &Expression, source: &str) -> Json {
+    match e {
+        Expression::Literal(l) => {
+            let value = match l.as_ref() {
+                LiteralExpression::Omni(v) => { Json::string(v.get_value().get_value_as::<String>()) }
+                other => { other.get_value().map(|x| Json::string(x.get_as_string())).unwrap_or(Json::Null) }
+            };
+            kind("Literal").with("type", r#type(&l.get_type())).with("value", value)
+        }
+        Expression::Binary(b) => { binary("Binary", b.get_op(), b.get_left(), b.get_right(), source) }
+        Expression::Comparative(c) => { binary("Comparative", c.get_op(), c.get_left(), c.get_right(), source) }
+        Expression::Logical(l) => { binary("Logical", l.get_op(), l.get_left(), l.get_right(), source) }
+        Expression::Bitwise(b) => { binary("Bitwise", b.get_op(), b.get_left(), b.get_right(), source) }
+        Expression::Unary(u) => { unary(u, source) }
+        Expression::FunctionCall(c) => { function_call(c, source) }
+        Expression::VariableAccess(v) => { kind("VariableAccess").with("name", Json::string(v.get_name())) }
+        Expression::Argument(a) => {
+            kind("Argument")
+                .with("name", Json::string(a.get_name()))
+                .with("mutable", Json::Bool(a.is_mutable()))
+                .with("type", r#type(a.get_type()))
+        }
+        Expression::Lambda(l) => { lambda(l, source) }
+        Expression::Ternary(t) => {
+            kind("Ternary")
+                .with("cond", expression(t.get_cond(), source))
+                .with("then", expression(t.get_then(), source))
+                .with("else", expression(t.get_else(), source))
+        }
+        Expression::If(i) => { if_node(i, source) }
+        Expression::Variant(v) => {
+            let values = match v.get_values() {
+                VariantValues::Unit => { Json::Null }
+                VariantValues::Tuple(values) => { list(values, |x| expression(x, source)) }
+                VariantValues::Struct(values) => { list(values, |(name, x)| Json::object().with("name", Json::string(name)).with("value", expression(x, source))) }
+            };
+            kind("Variant")
+                .with("enum", Json::string(v.get_enum()))
+                .with("variant", Json::string(v.get_variant()))
+                .with("values", values)
+        }
+        Expression::MethodCall(m) => { method_call(m, source) }
+        Expression::FieldAccess(f) => {
+            kind("FieldAccess")
+                .with("value", expression(f.get_value(), source))
+                .with("field", Json::string(f.get_field()))
+        }
+        Expression::ResultValue(r) => {
+            kind("Result")
+                .with("ok", Json::Bool(r.is_ok()))
+                .with("value", expression(r.get_value(), source))
+        }
+        Expression::Propagate(p) => { kind("Propagate").with("value", expression(p.get_value(), source)) }
+        Expression::Tuple(t) => { kind("Tuple").with("values", list(t.get_values(), |x| expression(x, source))) }
+        Expression::Cast(c) => {
+            kind("Cast")
+                .with("value", expression(c.get_value(), source))
+                .with("mode", Json::string(c.get_mode().to_string()))
+                .with("type", r#type(c.get_target()))
+        }
+        Expression::LayoutQuery(q) => { kind("LayoutQuery").with("query", Json::string(q.get_query().to_string())).with("type", r#type(q.get_target())) }
+    }
+}
+
+fn binary(name: &str, op: &impl ToString, left: &Expression, right: &Expression, source: &str) -> Json {
+    kind(name)
+        .with("op", Json::string(op.to_string()))
+        .with("left", expression(left, source))
+        .with("right", expression(right, source))
+}
+
+fn unary(u: &UnaryExpressionNode, source: &str) -> Json {
+    kind("Unary")
+        .with("op", Json::string(u.get_op()))
+        .with("value", expression(u.get_value(), source))
+}
+
+fn if_node(i: &IfStatementNode, source: &str) -> Json {
+    kind("If")
+        .with("cond", expression(i.get_cond(), source))
+        .with("then", statement(i.get_then(), source))
+        .with("else", i.get_else().as_ref().map(|x| statement(x, source)).unwrap_or(Json::Null))
+}
+
+fn function_declaration(f: &FunctionDeclarationStatementNode, source: &str) -> Json {
+    kind("FunctionDeclaration")
+        .with("name", Json::string(f.get_name()))
+        .with("attributes", list(f.get_attributes(), attribute))
+        .with("generics", list(f.get_generics(), generic))
+        .with("args", list(f.get_args(), |x| expression(x, source)))
+        .with("returns", r#type(f.get_return_type()))
+        .with("body", statement(f.get_body(), source))
+        .with("visible", Json::Bool(*f.is_visible()))
+        .with("static", Json::Bool(f.is_static()))
+}
+
+fn function_call(c: &FunctionCallNode, source: &str) -> Json {
+    kind("FunctionCall")
+        .with("name", Json::string(c.get_name()))
+        .with("args", c.get_args().as_ref().map(|x| list(x, |x| node(x, source))).unwrap_or(Json::Array(Vec::new())))
+}
+
+fn method_call(m: &MethodCallNode, source: &str) -> Json {
+    kind("MethodCall")
+        .with("receiver", expression(m.get_receiver(), source))
+        .with("name", Json::string(m.get_name()))
+        .with("args", list(m.get_args(), |x| node(x, source)))
+}
+
+fn lambda(l: &LambdaExpressionNode, source: &str) -> Json {
+    kind("Lambda")
+        .with("id", Json::Number(l.get_id() as i128))
+        .with("args", list(l.get_args(), |x| expression(x, source)))
+        .with("returns", r#type(l.get_return_type()))
+        .with("body", statement(l.get_body(), source))
+}
+
+fn attribute(a: &AttributeNode) -> Json {
+    Json::object()
+        .with("name", Json::string(a.get_name()))
+        .with("argument", a.get_argument().map(Json::string).unwrap_or(Json::Null))
+}
+
+fn generic(g: &GenericParameterNode) -> Json {
+    Json::object()
+        .with("name", Json::string(g.get_name()))
+        .with("bounds", list(g.get_bounds(), r#type))
+}
+
+fn pattern(p: &Pattern, source: &str) -> Json {
+    match p {
+        Pattern::Wildcard => { kind("Wildcard") }
+        Pattern::Literal(e) => { kind("Literal").with("value", expression(e, source)) }
+        Pattern::Ok(name) => { kind("Ok").with("binding", Json::string(name)) }
+        Pattern::Err(name) => { kind("Err").with("binding", Json::string(name)) }
+        Pattern::Variant(r#enum, variant, bindings) => {
+            let bindings = match bindings {
+                PatternBindings::Unit => { Json::Null }
+                PatternBindings::Tuple(names) => { list(names, |x| Json::string(x)) }
+                PatternBindings::Struct(fields) => { list(fields, |(field, name)| Json::object().with("field", Json::string(field)).with("binding", Json::string(name))) }
+            };
+            kind("Variant")
+                .with("enum", Json::string(r#enum))
+                .with("variant", Json::string(variant))
+                .with("bindings", bindings)
+        }
+    }
+}
@@ -0,0 +1,716 @@
+//! # Omnia formatter
+//! ***
+//!
+//! `Omnia fmt [--check] <file>` reprints a file from its AST in the canonical style: four space indentation, opening
+//! braces on the line of their statement, spaces around `->` and `>>`, and each run of adjacent `plug` lines sorted.
+//! A blank line between two statements is kept as one blank line.
+//!
+//! Comments are not part of the AST, so they are put back once the code is printed, between the same two tokens they
+//! were written between: a comment inside a struct stays with its field, and `add(1, /* two */ 2)` keeps its comment
+//! in front of the argument. A comment that starts its line keeps a line of its own. When the tokens around a comment
+//! are no longer next to each other, as in a run of `plug` lines that gets sorted, the file is left as it is.
+//!
+//! Every result is formatted a second time and compared with the first, so output that would change again on the
+//! next run is reported instead of written
+use std::fs::{read_to_string, write};
+use std::panic;
+use crate::core::omnia_types::{OmniaValue, Type};
+use crate::lexer::Lexer;
+use crate::lexer::token::{Comment, Span, Token, TokenType};
+use crate::parser::ast::nodes::{ASTNode, ArgumentExpressionNode, AttributeNode, BlockStatementNode, Expression, FunctionCallNode, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, LambdaExpressionNode, LiteralExpression, MethodCallNode, Pattern, PatternBindings, Statement, UnaryExpressionNode, UnaryOperation, VariantPayload, VariantValues};
+use crate::parser::Parser;
+
+const INDENT: &str = "    ";
+/// How many tokens the printed code may add or leave out in a row, such as redundant parentheses, before the tokens
+/// of the source and of the printed code are no longer matched
+const RESYNC: usize = 16;
+
+/// Runs `fmt` with the arguments following it. Returns the exit code: 1 when `--check` finds a file that is not
+/// formatted, 2 when a file can't be formatted: it doesn't parse, a comment would move or the formatter is not
+/// idempotent on it
+pub fn run(args: &[String]) -> std::io::Result<i32> {
+    let check = args.iter().any(|x| x == "--check");
+    let mut code = 0;
+    for path in args.iter().filter(|x| !x.starts_with("--")) {
+        let source = read_to_string(path)?;
+        let formatted = match format(&source) {
+            Ok(formatted) => { formatted }
+            Err(e) => {
+                eprintln!("{}: {}, the file was left as is", path, e);
+                code = 2;
+                continue
+            }
+        };
+        if format(&formatted).as_ref() != Ok(&formatted) {
+            eprintln!("{}: formatting is not idempotent, the file was left as is", path);
+            code = 2;
+            continue
+        }
+        if formatted == source {
+            continue
+        }
+        if check {
+            eprintln!("{}: not formatted", path);
+            code = code.max(1);
+        } else {
+            write(path, formatted)?;
+        }
+    }
+    Ok(code)
+}
+
+/// Formats a whole program. Fails when it doesn't parse, or when one of its comments can't be put back where it was
+pub fn format(source: &str) -> Result<String, String> {
+    let (tokens, comments, root) = parse(source)?;
+    let mut printer = Printer {
+        source,
+        out: String::new(),
+        indent: 0,
+        sorted: Vec::new()
+    };
+    printer.body(&root);
+    printer.out.push('\n');
+    let formatted = place_comments(&printer, &tokens, &comments)?;
+    let (_, kept, _) = parse(&formatted)?;
+    if kept.iter().map(|x| &x.text).ne(comments.iter().map(|x| &x.text)) {
+        return Err(String::from("the comments would change"))
+    }
+    Ok(formatted)
+}
+
+/// Tokens, comments and AST of `source`. The lexer and the parser report errors by panicking, so the panic is caught
+/// and its message returned instead
+fn parse(source: &str) -> Result<(Vec<Token>, Vec<Comment>, BlockStatementNode), String> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(|| {
+        let mut lexer = Lexer::new(String::from(source));
+        let tokens: Vec<Token> = lexer.tokenize();
+        let root = Parser::new(tokens.clone()).parse();
+        (tokens.into_iter().filter(|x| x.t_type != TokenType::EOF).collect(), lexer.get_comments().clone(), root)
+    });
+    panic::set_hook(hook);
+    result.map_err(|e| {
+        let message = e.downcast_ref::<String>().cloned().or_else(|| e.downcast_ref::<&str>().map(|x| x.to_string()));
+        format!("cannot be parsed: {}", message.unwrap_or_default())
+    })
+}
+
+/// Puts the comments of the source back into the code printed from its AST. Each comment goes between the printed
+/// counterparts of the nearest source tokens that are printed on either side of it
+fn place_comments(printer: &Printer, tokens: &[Token], comments: &[Comment]) -> Result<String, String> {
+    let out = printer.out.as_str();
+    if comments.is_empty() {
+        return Ok(String::from(out))
+    }
+    let printed: Vec<Token> = Lexer::new(String::from(out)).tokenize().into_iter().filter(|x| x.t_type != TokenType::EOF).collect();
+    let matched = align(tokens, &printed);
+    let chars: Vec<char> = out.chars().collect();
+    let source: Vec<char> = printer.source.chars().collect();
+    // comments sharing a gap between two printed tokens, as (token before, token after, comments)
+    let mut gaps: Vec<(Option<usize>, Option<usize>, Vec<&Comment>)> = Vec::new();
+    for comment in comments {
+        let following = tokens.iter().position(|x| x.t_pos >= comment.span.end).unwrap_or(tokens.len());
+        let before = matched[..following].iter().rev().find_map(|x| *x);
+        let after = matched[following..].iter().find_map(|x| *x);
+        let adjacent = match (before, after) {
+            (Some(b), Some(a)) => { a == b + 1 }
+            (None, Some(a)) => { a == 0 }
+            (Some(b), None) => { b + 1 == printed.len() }
+            (None, None) => { printed.is_empty() }
+        };
+        let sorted = printer.sorted.iter().any(|(start, end)| comment.span.start > *start
+            && (comment.span.start < *end || !source[*end..comment.span.start].contains(&'\n')));
+        if !adjacent || sorted {
+            let line = source[..comment.span.start].iter().filter(|x| **x == '\n').count() + 1;
+            return Err(format!("the comment `{}` on line {} would move", comment.text, line))
+        }
+        match gaps.last_mut() {
+            Some((b, a, group)) if *b == before && *a == after => { group.push(comment) }
+            _ => { gaps.push((before, after, vec![comment])) }
+        }
+    }
+    let mut result = String::new();
+    let mut copied = 0;
+    for (before, after, group) in gaps {
+        let start = before.map(|x| printed[x].t_end).unwrap_or(0);
+        let end = after.map(|x| printed[x].t_pos).unwrap_or(chars.len());
+        result.extend(&chars[copied..start]);
+        let own_lines: Vec<bool> = group.iter().map(|x| starts_line(&source, x.span.start)).collect();
+        result.push_str(&gap(&chars, start, end, after.map(|x| &printed[x]), &group, &own_lines));
+        copied = end;
+    }
+    result.extend(&chars[copied..]);
+    Ok(result)
+}
+
+/// Printed text of the gap `[start, end)` between two tokens of `out` with `comments` put into it. A comment that
+/// started its line in the source gets a line of its own, the others stay on the line of what precedes them
+fn gap(out: &[char], start: usize, end: usize, after: Option<&Token>, comments: &[&Comment], own_lines: &[bool]) -> String {
+    let original: String = out[start..end].iter().collect();
+    let mut same_line = String::new();  // comments following the token before the gap
+    let mut lines: Vec<String> = Vec::new();
+    let top = start == 0;  // nothing is printed before the gap, so there is no line to stay on
+    for (comment, own_line) in comments.iter().zip(own_lines) {
+        if *own_line || top && lines.is_empty() {
+            lines.push(comment.text.clone())
+        } else if let Some(line) = lines.last_mut() {
+            line.push_str(&format!(" {}", comment.text))
+        } else {
+            same_line.push_str(&format!(" {}", comment.text))
+        }
+    }
+    let closing = after.is_some_and(|x| x.t_type == TokenType::RBRACE);
+    match original.rfind('\n') {
+        Some(i) if !top || after.is_none() => {  // the token after the gap starts a line
+            let (head, indent) = original.split_at(i + 1);
+            let head = if top { "" } else { head };
+            let inner = if closing { format!("{}{}", indent, INDENT) } else { String::from(indent) };
+            let lines: String = lines.iter().map(|x| format!("{}{}\n", inner, x)).collect();
+            format!("{}{}{}{}", same_line, head, lines, indent)
+        }
+        _ => {
+            let line_start = out[..start].iter().rposition(|x| *x == '\n').map(|x| x + 1).unwrap_or(0);
+            let indent: String = out[line_start..].iter().take_while(|x| **x == ' ').collect();
+            let ends_line = !lines.is_empty() || comments.last().is_some_and(|x| x.is_line());
+            if !ends_line {
+                return format!("{}{}", same_line, if original.is_empty() { " " } else { &original })
+            }
+            let inner = if top { indent.clone() } else { format!("{}{}", indent, INDENT) };
+            let lines: String = lines.iter().map(|x| format!("\n{}{}", inner, x)).collect();
+            let next = if closing || top { indent } else { format!("{}{}", indent, INDENT) };
+            let text = format!("{}{}\n{}", same_line, lines, next);
+            if top { text.trim_start_matches('\n').to_string() } else { text }
+        }
+    }
+}
+
+/// Whether only whitespace precedes `offset` on its line of `source`
+fn starts_line(source: &[char], offset: usize) -> bool {
+    source[..offset].iter().rev().take_while(|x| **x != '\n').all(|x| x.is_whitespace())
+}
+
+/// Printed token each source token is matched with. The printer keeps the tokens of the source in order, except that
+/// it drops redundant parentheses and trailing commas and sorts `plug` lines, so after a mismatch the nearest pair of
+/// equal tokens within [RESYNC] of it is matched next
+fn align(tokens: &[Token], printed: &[Token]) -> Vec<Option<usize>> {
+    let same = |a: &Token, b: &Token| a.t_type == b.t_type && (a.t_type == TokenType::DECIMAL || a.t_value == b.t_value);
+    let mut matched = vec![None; tokens.len()];
+    let (mut i, mut k) = (0, 0);
+    while i < tokens.len() && k < printed.len() {
+        if same(&tokens[i], &printed[k]) {
+            matched[i] = Some(k);
+            i += 1;
+            k += 1;
+            continue
+        }
+        let next = (1..=RESYNC).flat_map(|d| (0..=d).map(move |a| (a, d - a)))
+            .find(|(a, b)| i + a < tokens.len() && k + b < printed.len() && same(&tokens[i + a], &printed[k + b]));
+        match next {
+            Some((a, b)) => {
+                i += a;
+                k += b;
+            }
+            None => { break }
+        }
+    }
+    matched
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    out: String,
+    indent: usize,
+    /// Source ranges of the runs of `plug` lines which were reordered. The comments among them can't follow their line
+    sorted: Vec<(usize, usize)>
+}
+impl Printer<'_> {
+    fn write(&mut self, text: &str) {
+        self.out.push_str(text)
+    }
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.indent {
+            self.out.push_str(INDENT)
+        }
+    }
+    /// Source text between two character offsets
+    fn between(&self, start: usize, end: usize) -> String {
+        self.source.chars().skip(start).take(end.saturating_sub(start)).collect()
+    }
+
+    /// Statements of a block, one per line
+    fn body(&mut self, block: &BlockStatementNode) {
+        let order = plug_order(block);
+        let moved: Vec<Span> = order.iter().enumerate().filter(|(k, i)| k != *i).filter_map(|(_, i)| block.get_span(*i)).collect();
+        if let (Some(start), Some(end)) = (moved.iter().map(|x| x.start).min(), moved.iter().map(|x| x.end).max()) {
+            self.sorted.push((start, end))
+        }
+        for (k, i) in order.into_iter().enumerate() {
+            if k > 0 {
+                if self.blank_line_before(block, k) {
+                    self.out.push('\n');
+                }
+                self.newline()
+            }
+            match &block.get_body()[i] {
+                ASTNode::Statement(s) => { self.statement(s) }
+                ASTNode::Expression(e) => { self.expression(e, 0) }
+            }
+        }
+    }
+    /// Whether the source has an empty line between the statements at positions `k - 1` and `k`. Sorting `plug` lines
+    /// only moves statements inside a run, so the gaps are kept by position
+    fn blank_line_before(&self, block: &BlockStatementNode, k: usize) -> bool {
+        match (block.get_span(k - 1), block.get_span(k)) {
+            (Some(previous), Some(next)) => {
+                let gap = self.between(previous.end, next.start);
+                let lines: Vec<&str> = gap.split('\n').collect();
+                lines.len() > 2 && lines[1..lines.len() - 1].iter().any(|x| x.trim().is_empty())
+            }
+            _ => { false }
+        }
+    }
+    fn block(&mut self, block: &BlockStatementNode) {
+        if block.get_body().is_empty() {
+            return self.write("{}")
+        }
+        self.write("{");
+        self.indent += 1;
+        self.newline();
+        self.body(block);
+        self.indent -= 1;
+        self.newline();
+        self.write("}")
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::If(i) => { self.r#if(i) }
+            Statement::Block(b) => { self.block(b) }
+            Statement::Plug(p) => { self.write(&format!("plug {};", p.get_lib())) }
+            Statement::FunctionDeclaration(f) => {
+                self.attributes(f.get_attributes());
+                if *f.is_visible() {
+                    self.write("visible ")
+                }
+                if f.is_static() {
+                    self.write("static ")
+                }
+                self.write("func ");
+                self.function(f, 0, None)
+            }
+            Statement::FunctionCall(c) => {
+                self.function_call(c);
+                self.write(";")
+            }
+            Statement::Assignment(a) => {
+                self.write(&format!("{} {} ", a.get_cont(), a.get_op()));
+                self.expression(a.get_value(), 0);
+                self.write(";")
+            }
+            Statement::VariableCreation(v) => {
+                let name = if v.is_mutable() { format!("mut {}", v.get_name()) } else { v.get_name().clone() };
+                match v.get_type() {
+                    Some(t) => { self.write(&format!("{} {} = ", t, name)) }
+                    None => { self.write(&format!("mk {} = ", name)) }
+                }
+                self.expression(v.get_value(), 0);
+                self.write(";")
+            }
+            Statement::Return(r) => {  // a tail value is printed bare, as the value of its block
+                if !r.is_tail() {
+                    self.write("return ");
+                }
+                match r.get_value() {
+                    ASTNode::Expression(e) => { self.expression(e, 0) }
+                    ASTNode::Statement(s) => { self.statement(s) }
+                }
+                if !r.is_tail() {
+                    self.write(";")
+                }
+            }
+            Statement::LambdaDeclaration(l) => {
+                self.write(&format!("mk {}{} = ", if l.is_mutable() { "mut " } else { "" }, l.get_name()));
+                self.lambda(l.get_lambda());
+                self.write(";")
+            }
+            Statement::StructDeclaration(s) => {
+                self.attributes(s.get_attributes());
+                if s.is_visible() {
+                    self.write("visible ")
+                }
+                self.write(&format!("struct {}{}", s.get_name(), generics(s.get_generics())));
+                let fields: Vec<String> = s.get_fields().iter().map(|x| format!("{}: {}", x.get_name(), x.get_type())).collect();
+                self.members(&fields, ",")
+            }
+            Statement::EnumDeclaration(e) => {
+                if e.is_visible() {
+                    self.write("visible ")
+                }
+                self.write(&format!("enum {}{}", e.get_name(), generics(e.get_generics())));
+                let variants: Vec<String> = e.get_variants().iter().map(|x| match x.get_payload() {
+                    VariantPayload::Unit => { x.get_name().clone() }
+                    VariantPayload::Tuple(types) => { format!("{}({})", x.get_name(), join(types)) }
+                    VariantPayload::Struct(fields) => {
+                        let fields: Vec<String> = fields.iter().map(|x| format!("{}: {}", x.get_name(), x.get_type())).collect();
+                        format!("{} {{ {} }}", x.get_name(), fields.join(", "))
+                    }
+                }).collect();
+                self.members(&variants, ",")
+            }
+            Statement::Switch(s) => {
+                self.write("switch ");
+                self.expression(s.get_value(), 0);
+                self.write(" {");
+                self.indent += 1;
+                for arm in s.get_arms() {
+                    self.newline();
+                    self.pattern(arm.get_pattern());
+                    self.write(" -> ");
+                    self.statement(arm.get_body());
+                }
+                self.indent -= 1;
+                self.newline();
+                self.write("}")
+            }
+            Statement::InterfaceDeclaration(i) => {
+                if i.is_visible() {
+                    self.write("visible ")
+                }
+                self.write(&format!("interface {}", i.get_name()));
+                let methods: Vec<String> = i.get_methods().iter().map(|x| format!("func {}({}){};", x.get_name(), arguments(x.get_args(), 0), returns(x.get_return_type()))).collect();
+                self.members(&methods, "")
+            }
+            Statement::Extension(e) => {
+                match e.get_interface() {
+                    None => {  // ext func area() for Point -> decimal { ... }
+                        self.write("ext func ");
+                        if let Some(method) = e.get_methods().first() {
+                            self.function(method.get_function(), 1, Some(e.get_target()))
+                        }
+                    }
+                    Some(interface) => {
+                        self.write(&format!("ext {} for {} {{", interface, e.get_target()));
+                        self.indent += 1;
+                        for method in e.get_methods() {
+                            self.newline();
+                            if method.is_override() {
+                                self.write("override ")
+                            }
+                            self.write("func ");
+                            self.function(method.get_function(), 1, None)
+                        }
+                        self.indent -= 1;
+                        self.newline();
+                        self.write("}")
+                    }
+                }
+            }
+            Statement::MethodCall(m) => {
+                self.method_call(m);
+                self.write(";")
+            }
+            Statement::Propagate(p) => {
+                self.expression(p.get_value(), 8);
+                self.write("!;")
+            }
+            Statement::Unary(u) => {
+                self.unary(u);
+                self.write(";")
+            }
+            Statement::Declaration(d) => {
+                let mutable = if d.is_mutable() { "mut " } else { "" };
+                self.write(&format!("{} {}{};", d.get_type(), mutable, d.get_names().join(", ")))
+            }
+            Statement::Destructuring(d) => {
+                self.write(&format!("mk ({}) = ", d.get_names().join(", ")));
+                self.expression(d.get_value(), 0);
+                self.write(";")
+            }
+            Statement::Static(s) => {
+                self.write(&format!("static {} {} = ", s.get_type(), s.get_name()));
+                self.expression(s.get_value(), 0);
+                self.write(";")
+            }
+        }
+    }
+    fn attributes(&mut self, attributes: &[AttributeNode]) {
+        if attributes.is_empty() {
+            return
+        }
+        let attributes: Vec<String> = attributes.iter().map(|x| x.to_string()).collect();
+        self.write(&attributes.join(" "));
+        self.newline()
+    }
+    /// `name<T>(args) -> type body` of a function, leaving out the first `skip` arguments, which are receivers
+    fn function(&mut self, f: &FunctionDeclarationStatementNode, skip: usize, target: Option<&Type>) {
+        self.write(&format!("{}{}({})", f.get_name(), generics(f.get_generics()), arguments(f.get_args(), skip)));
+        if let Some(target) = target {
+            self.write(&format!(" for {}", target))
+        }
+        self.write(&format!("{} ", returns(f.get_return_type())));
+        self.statement(f.get_body())
+    }
+    /// Members of a struct, enum or interface, one per line
+    fn members(&mut self, members: &[String], separator: &str) {
+        if members.is_empty() {
+            return self.write(" {}")
+        }
+        self.write(" {");
+        self.indent += 1;
+        for (i, member) in members.iter().enumerate() {
+            self.newline();
+            self.write(member);
+            if i + 1 < members.len() {
+                self.write(separator)
+            }
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write("}")
+    }
+    fn r#if(&mut self, i: &IfStatementNode) {
+        self.write("if ");
+        self.expression(i.get_cond(), 0);
+        self.write(" ");
+        self.statement(i.get_then());
+        if let Some(e) = i.get_else() {
+            self.write(" else ");
+            self.statement(e)
+        }
+    }
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard => { self.write("_") }
+            Pattern::Literal(e) => { self.expression(e, 0) }
+            Pattern::Ok(name) => { self.write(&format!("ok({})", name)) }
+            Pattern::Err(name) => { self.write(&format!("err({})", name)) }
+            Pattern::Variant(r#enum, variant, bindings) => {
+                self.write(&format!("{}::{}", r#enum, variant));
+                match bindings {
+                    PatternBindings::Unit => {}
+                    PatternBindings::Tuple(names) => { self.write(&format!("({})", names.join(", "))) }
+                    PatternBindings::Struct(fields) => {
+                        let fields: Vec<String> = fields.iter().map(|(field, name)| if field == name { field.clone() } else { format!("{}: {}", field, name) }).collect();
+                        self.write(&format!(" {{ {} }}", fields.join(", ")))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prints an expression, in parentheses when it binds looser than `level`
+    fn expression(&mut self, expression: &Expression, level: u8) {
+        let parenthesized = precedence(expression) < level;
+        if parenthesized {
+            self.write("(")
+        }
+        match expression {
+            Expression::Literal(l) => { self.write(&literal(l)) }
+            Expression::Binary(b) => { self.binary(b.get_left(), &b.get_op().to_string(), b.get_right(), precedence(expression)) }
+            Expression::Comparative(c) => { self.binary(c.get_left(), &c.get_op().to_string(), c.get_right(), 3) }
+            Expression::Logical(l) => { self.binary(l.get_left(), &l.get_op().to_string(), l.get_right(), 2) }
+            Expression::Bitwise(b) => { self.binary(b.get_left(), &b.get_op().to_string(), b.get_right(), 1) }
+            Expression::Unary(u) => { self.unary(u) }
+            Expression::FunctionCall(c) => { self.function_call(c) }
+            Expression::VariableAccess(v) => { self.write(v.get_name()) }
+            Expression::Argument(a) => { self.write(&argument(a)) }
+            Expression::Lambda(l) => { self.lambda(l) }
+            Expression::Ternary(t) => {
+                self.expression(t.get_cond(), 1);
+                self.write(" ? ");
+                self.expression(t.get_then(), 0);
+                self.write(" : ");
+                self.expression(t.get_else(), 0)
+            }
+            Expression::If(i) => { self.r#if(i) }
+            Expression::Variant(v) => {
+                self.write(&format!("{}::{}", v.get_enum(), v.get_variant()));
+                match v.get_values() {
+                    VariantValues::Unit => {}
+                    VariantValues::Tuple(values) => {
+                        self.write("(");
+                        self.list(values);
+                        self.write(")")
+                    }
+                    VariantValues::Struct(values) => {
+                        self.write(" { ");
+                        for (i, (field, value)) in values.iter().enumerate() {
+                            if i > 0 {
+                                self.write(", ")
+                            }
+                            self.write(&format!("{}: ", field));
+                            self.expression(value, 0)
+                        }
+                        self.write(" }")
+                    }
+                }
+            }
+            Expression::MethodCall(m) => { self.method_call(m) }
+            Expression::FieldAccess(f) => {
+                self.expression(f.get_value(), 8);
+                self.write(&format!(".{}", f.get_field()))
+            }
+            Expression::ResultValue(r) => {
+                self.write(if r.is_ok() { "ok(" } else { "err(" });
+                self.expression(r.get_value(), 0);
+                self.write(")")
+            }
+            Expression::Propagate(p) => {
+                self.expression(p.get_value(), 8);
+                self.write("!")
+            }
+            Expression::Tuple(t) => {
+                self.write("(");
+                self.list(t.get_values());
+                self.write(")")
+            }
+            Expression::Cast(c) => {
+                self.expression(c.get_value(), 8);
+                self.write(&format!(" {} {}", c.get_mode(), c.get_target()))
+            }
+            Expression::LayoutQuery(q) => { self.write(&format!("{}({})", q.get_query(), q.get_target())) }
+        }
+        if parenthesized {
+            self.write(")")
+        }
+    }
+    /// Operators don't chain, so both operands must bind tighter than the operator itself
+    fn binary(&mut self, left: &Expression, op: &str, right: &Expression, level: u8) {
+        self.expression(left, level + 1);
+        self.write(&format!(" {} ", op));
+        self.expression(right, level + 1)
+    }
+    fn unary(&mut self, unary: &UnaryExpressionNode) {
+        match unary.get_op() {
+            UnaryOperation::Inc | UnaryOperation::Dec => {
+                self.expression(unary.get_value(), 8);
+                self.write(&unary.get_op().to_string())
+            }
+            UnaryOperation::Neg | UnaryOperation::Not | UnaryOperation::Ref => {
+                self.write(&unary.get_op().to_string());
+                self.expression(unary.get_value(), 8)
+            }
+        }
+    }
+    fn list(&mut self, values: &[Expression]) {
+        for (i, val) in values.iter().enumerate() {
+            if i > 0 {
+                self.write(", ")
+            }
+            self.expression(val, 0)
+        }
+    }
+    fn nodes(&mut self, values: &[ASTNode]) {
+        for (i, val) in values.iter().enumerate() {
+            if i > 0 {
+                self.write(", ")
+            }
+            match val {
+                ASTNode::Expression(e) => { self.expression(e, 0) }
+                ASTNode::Statement(s) => { self.statement(s) }
+            }
+        }
+    }
+    fn function_call(&mut self, call: &FunctionCallNode) {
+        self.write(&format!("{}(", call.get_name()));
+        if let Some(args) = call.get_args() {
+            self.nodes(args)
+        }
+        self.write(")")
+    }
+    fn method_call(&mut self, call: &MethodCallNode) {
+        self.expression(call.get_receiver(), 8);
+        self.write(&format!(".{}(", call.get_name()));
+        self.nodes(call.get_args());
+        self.write(")")
+    }
+    fn lambda(&mut self, lambda: &LambdaExpressionNode) {
+        self.write(&format!("({}) -> {} >> ", arguments(lambda.get_args(), 0), lambda.get_return_type()));
+        self.statement(lambda.get_body())
+    }
+}
+
+/// Indices of the statements of a block in print order, with every run of adjacent `plug` statements sorted
+fn plug_order(block: &BlockStatementNode) -> Vec<usize> {
+    let body = block.get_body();
+    let lib = |i: usize| match &body[i] {
+        ASTNode::Statement(Statement::Plug(p)) => { Some(p.get_lib().clone()) }
+        _ => { None }
+    };
+    let mut order: Vec<usize> = (0..body.len()).collect();
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end < order.len() && lib(end).is_some() {
+            end += 1;
+        }
+        order[start..end].sort_by_key(|x| lib(*x));
+        start = end.max(start + 1);
+    }
+    order
+}
+
+/// How tightly an expression binds, following the levels of the parser from `ternary` to `primary`
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Ternary(_) => { 0 }
+        Expression::Bitwise(_) => { 1 }
+        Expression::Logical(_) => { 2 }
+        Expression::Comparative(_) => { 3 }
+        Expression::Binary(b) => {
+            match b.get_op().to_string().as_str() {
+                "+" | "-" => { 4 }
+                "^" => { 6 }
+                _ => { 5 }
+            }
+        }
+        Expression::Unary(_) | Expression::Cast(_) => { 7 }
+        Expression::MethodCall(_) | Expression::FieldAccess(_) | Expression::Propagate(_) => { 8 }
+        _ => { 9 }
+    }
+}
+
+fn literal(literal: &LiteralExpression) -> String {
+    match literal {
+        LiteralExpression::Decimal(v) => { format!("{:?}", v.get_value().get_as_decimal()) }
+        LiteralExpression::Omni(v) => { v.get_value().get_value_as::<String>() }
+        LiteralExpression::Char(v) => { format!("'{}'", v.get_value().get_as_string()) }
+        LiteralExpression::Chararr(v) => { format!("\"{}\"", v.get_value().get_as_string()) }
+        LiteralExpression::Null => { String::from("null") }
+        other => { other.get_value().map(|x| x.get_as_string()).unwrap_or_default() }
+    }
+}
+
+fn generics(generics: &[GenericParameterNode]) -> String {
+    if generics.is_empty() {
+        return String::new()
+    }
+    let generics: Vec<String> = generics.iter().map(|x| x.to_string()).collect();
+    format!("<{}>", generics.join(", "))
+}
+
+fn arguments(args: &[Expression], skip: usize) -> String {
+    let args: Vec<String> = args.iter().skip(skip).filter_map(|x| match x {
+        Expression::Argument(a) => { Some(argument(a)) }
+        _ => { None }
+    }).collect();
+    args.join(", ")
+}
+
+fn argument(a: &ArgumentExpressionNode) -> String {
+    format!("{}{}: {}", if a.is_mutable() { "mut " } else { "" }, a.get_name(), a.get_type())
+}
+
+fn returns(r#type: &Type) -> String {
+    match r#type {
+        Type::NULL => { String::new() }
+        _ => { format!(" -> {}", r#type) }
+    }
+}
+
+fn join(types: &[Type]) -> String {
+    let types: Vec<String> = types.iter().map(|x| x.to_string()).collect();
+    types.join(", ")
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use num_bigfloat::BigFloat;
+use crate::core::omnia_types::{OmniaBool, OmniaByte, OmniaChar, OmniaChararr, OmniaDecimal, OmniaInt, OmniaLong, OmniaOmni, OmniaUByte, OmniaUInt, OmniaULong, OmniaValue, CastMode, Conversion, Type};
+use crate::core::utils::numeric_utils::omni::f128;
+use crate::generator::semantics::utils::SemanticsError;
+use crate::generator::semantics::utils::SemanticsError::{ConstantEvaluation, UndefinedSymbol};
+use crate::generator::layout::Layouts;
+use crate::generator::size_of;
+use crate::parser::ast::nodes::{BinaryOperation, BitwiseOperation, ConditionalOperation, Expression, LayoutQuery, LogicalOperation, UnaryOperation};
+
+/// Evaluates constant expressions at compile time. Integer arithmetic is checked against the range of the common
+/// type of its operands, so `static byte B = 100 + 100;` is an error instead of a wrapped value
+pub struct ConstEvaluator<'a> {
+    constants: &'a HashMap<String, Box<dyn OmniaValue>>,
+    layouts: Option<&'a Layouts>
+}
+impl<'a> ConstEvaluator<'a> {
+    pub fn new(constants: &'a HashMap<String, Box<dyn OmniaValue>>) -> Self {
+        Self {
+            constants,
+            layouts: None
+        }
+    }
+    /// Makes `sizeof` and `alignof` of the declared types constant
+    pub fn with_layouts(mut self, layouts: &'a Layouts) -> Self {
+        self.layouts = Some(layouts);
+        self
+    }
+    pub fn evaluate(&self, expression: &Expression) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+        match expression {
+            Expression::Literal(l) => {
+                let value = l.get_value().ok_or(ConstantEvaluation(format!("{} is not supported in constant expressions", l)))?;
+                convert(value, &type_of(value))
+            }
+            Expression::VariableAccess(v) => {
+                let value = self.constants.get(v.get_name()).ok_or(UndefinedSymbol(format!("'{}' is not a static constant", v.get_name())))?;
+                convert(value.as_ref(), &type_of(value.as_ref()))
+            }
+            Expression::Binary(b) => { arithmetic(b.get_op(), self.evaluate(b.get_left())?.as_ref(), self.evaluate(b.get_right())?.as_ref()) }
+            Expression::Bitwise(b) => { bitwise(b.get_op(), self.evaluate(b.get_left())?.as_ref(), self.evaluate(b.get_right())?.as_ref()) }
+            Expression::Comparative(c) => { comparison(c.get_op(), self.evaluate(c.get_left())?.as_ref(), self.evaluate(c.get_right())?.as_ref()) }
+            Expression::Logical(l) => {
+                let left = as_bool(self.evaluate(l.get_left())?.as_ref())?;
+                let value = match l.get_op() {
+                    LogicalOperation::And => { left && as_bool(self.evaluate(l.get_right())?.as_ref())? }
+                    LogicalOperation::Or => { left || as_bool(self.evaluate(l.get_right())?.as_ref())? }
+                };
+                Ok(Box::new(OmniaBool::new(value)))
+            }
+            Expression::Unary(u) => {
+                let value = self.evaluate(u.get_value())?;
+                match u.get_op() {
+                    UnaryOperation::Not => { Ok(Box::new(OmniaBool::new(!as_bool(value.as_ref())?))) }
+                    UnaryOperation::Neg if type_of(value.as_ref()) == Type::DECIMAL => { Ok(Box::new(OmniaDecimal::new(-value.get_as_decimal()))) }
+                    UnaryOperation::Neg if type_of(value.as_ref()) == Type::OMNI => { Ok(make_omni(-as_omni(value.as_ref())?)) }
+                    UnaryOperation::Neg => { make_integer(&type_of(value.as_ref()), -as_integer(value.as_ref())?) }
+                    UnaryOperation::Inc | UnaryOperation::Dec => { Err(ConstantEvaluation(format!("{} modifies a variable and is not constant", expression))) }
+                    UnaryOperation::Ref => { Err(ConstantEvaluation(format!("{} takes the address of a variable and is not constant", expression))) }
+                }
+            }
+            Expression::Ternary(t) => {
+                if as_bool(self.evaluate(t.get_cond())?.as_ref())? { self.evaluate(t.get_then()) } else { self.evaluate(t.get_else()) }
+            }
+            Expression::Cast(c) => { cast(self.evaluate(c.get_value())?.as_ref(), c.get_target(), c.get_mode()) }
+            Expression::LayoutQuery(q) => {
+                let layouts = self.layouts.ok_or(ConstantEvaluation(format!("{} is not a constant expression", expression)))?;
+                let answer = match q.get_query() {
+                    LayoutQuery::Size => { layouts.size_of(q.get_target()) }
+                    LayoutQuery::Align => { layouts.align_of(q.get_target()) }
+                };
+                Ok(Box::new(OmniaULong::new(answer.map_err(|e| ConstantEvaluation(format!("Cannot take `{}` of {}: {}", q.get_query(), q.get_target(), e)))?)))
+            }
+            _ => { Err(ConstantEvaluation(format!("{} is not a constant expression", expression))) }
+        }
+    }
+}
+
+/// Converts a constant to another type, e.g. the `int` literal in `static long L = 1;`. Fails if the value does not fit
+pub fn convert(value: &dyn OmniaValue, target: &Type) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    match target {
+        Type::DECIMAL => { Ok(Box::new(OmniaDecimal::new(value.get_as_decimal()))) }
+        Type::OMNI => { Ok(make_omni(as_omni(value)?)) }
+        Type::BOOL => { Ok(Box::new(OmniaBool::new(as_bool(value)?))) }
+        Type::CHAR => {
+            let value = value.downcast_ref::<OmniaChar>().ok_or(ConstantEvaluation(format!("Cannot convert {} to char", value.get_as_string())))?;
+            Ok(Box::new(OmniaChar::new(value.get_value_as::<char>())))
+        }
+        Type::CHARARR => { Ok(Box::new(OmniaChararr::new(value.get_as_string()))) }
+        _ => { make_integer(target, as_integer(value)?) }
+    }
+}
+
+/// Casts a constant the way the chosen mode does at runtime. A checked cast that doesn't fit would be `null`, which
+/// is not a constant, so it fails like an exact one
+pub fn cast(value: &dyn OmniaValue, target: &Type, mode: CastMode) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    let source = type_of(value);
+    if source.conversion(target) == Conversion::Invalid {
+        return Err(ConstantEvaluation(format!("Cannot cast {} to {}", source, target)))
+    }
+    if target == &Type::DECIMAL || target == &Type::OMNI {
+        return convert(value, target)
+    }
+    let integer = match source {
+        Type::DECIMAL | Type::OMNI => { mode.decimal(value.get_as_decimal(), target) }
+        Type::CHAR => { mode.integer(value.get_as_string().chars().next().unwrap_or('\0') as i128, target) }
+        Type::BOOL => { mode.integer(as_bool(value)? as i128, target) }
+        _ => { mode.integer(as_integer(value)?, target) }
+    };
+    let integer = integer.ok_or(ConstantEvaluation(format!("Constant {} doesn't fit {} in `{}` cast", value.get_as_string(), target, mode)))?;
+    match target {
+        Type::CHAR => {
+            let code = char::from_u32(integer as u32).ok_or(ConstantEvaluation(format!("{} is not a valid char", integer)))?;
+            Ok(Box::new(OmniaChar::new(code)))
+        }
+        Type::BOOL => { Ok(Box::new(OmniaBool::new(integer != 0))) }
+        _ => { make_integer(target, integer) }
+    }
+}
+
+/// Integer value of a constant, for array sizes and the like
+pub fn as_integer(value: &dyn OmniaValue) -> Result<i128, SemanticsError> {
+    let integer = match type_of(value) {
+        Type::BYTE => { value.downcast_ref::<OmniaByte>().map(|x| x.get_value_as::<i128>()) }
+        Type::UBYTE => { value.downcast_ref::<OmniaUByte>().map(|x| x.get_value_as::<i128>()) }
+        Type::INT => { value.downcast_ref::<OmniaInt>().map(|x| x.get_value_as::<i128>()) }
+        Type::UINT => { value.downcast_ref::<OmniaUInt>().map(|x| x.get_value_as::<i128>()) }
+        Type::LONG => { value.downcast_ref::<OmniaLong>().map(|x| x.get_value_as::<i128>()) }
+        Type::ULONG => { value.downcast_ref::<OmniaULong>().map(|x| x.get_value_as::<i128>()) }
+        _ => { None }
+    };
+    integer.ok_or(ConstantEvaluation(format!("Expected an integer constant, got {} of type {}", value.get_as_string(), type_of(value))))
+}
+
+pub fn type_of(value: &dyn OmniaValue) -> Type {
+    value.get_type()
+}
+
+fn as_bool(value: &dyn OmniaValue) -> Result<bool, SemanticsError> {
+    value.downcast_ref::<OmniaBool>().map(|x| x.get_value_as::<bool>())
+        .ok_or(ConstantEvaluation(format!("Expected a bool constant, got {} of type {}", value.get_as_string(), type_of(value))))
+}
+
+/// Value of a numeric constant as an `omni`, which holds every integer and decimal exactly
+fn as_omni(value: &dyn OmniaValue) -> Result<BigFloat, SemanticsError> {
+    match type_of(value) {
+        Type::OMNI => {
+            value.downcast_ref::<OmniaOmni>().map(|x| x.get_value_as::<f128>().get())
+                .ok_or(ConstantEvaluation(format!("Expected an omni constant, got {}", value.get_as_string())))
+        }
+        Type::DECIMAL => { Ok(BigFloat::from_f64(value.get_as_decimal())) }
+        _ => { Ok(BigFloat::from_i128(as_integer(value)?)) }
+    }
+}
+
+fn make_omni(value: BigFloat) -> Box<dyn OmniaValue> {
+    Box::new(OmniaOmni::new(f128::new(value)))
+}
+
+fn make_integer(r#type: &Type, value: i128) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    let made: Option<Box<dyn OmniaValue>> = match r#type {
+        Type::OMNI => { Some(make_omni(BigFloat::from_i128(value))) }
+        Type::BYTE => { i8::try_from(value).ok().map(|x| Box::new(OmniaByte::new(x)) as Box<dyn OmniaValue>) }
+        Type::UBYTE => { u8::try_from(value).ok().map(|x| Box::new(OmniaUByte::new(x)) as Box<dyn OmniaValue>) }
+        Type::INT => { i32::try_from(value).ok().map(|x| Box::new(OmniaInt::new(x)) as Box<dyn OmniaValue>) }
+        Type::UINT => { u32::try_from(value).ok().map(|x| Box::new(OmniaUInt::new(x)) as Box<dyn OmniaValue>) }
+        Type::LONG => { i64::try_from(value).ok().map(|x| Box::new(OmniaLong::new(x)) as Box<dyn OmniaValue>) }
+        Type::ULONG => { u64::try_from(value).ok().map(|x| Box::new(OmniaULong::new(x)) as Box<dyn OmniaValue>) }
+        _ => { return Err(ConstantEvaluation(format!("{} is not an integer type", r#type))) }
+    };
+    made.ok_or(ConstantEvaluation(format!("Constant {} overflows {}", value, r#type)))
+}
+
+/// Common type of two operands, which must be numeric or both bool
+fn common_type(left: &dyn OmniaValue, right: &dyn OmniaValue) -> Result<Type, SemanticsError> {
+    let (left, right) = (type_of(left), type_of(right));
+    match left.common_with(&right) {
+        Some(common) if common != Type::CHAR && common != Type::CHARARR => { Ok(common) }
+        _ => { Err(ConstantEvaluation(format!("Cannot combine constants of types {} and {}", left, right))) }
+    }
+}
+
+fn arithmetic(op: &BinaryOperation, left: &dyn OmniaValue, right: &dyn OmniaValue) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    let common = common_type(left, right)?;
+    if common == Type::OMNI {
+        let (l, r) = (as_omni(left)?, as_omni(right)?);
+        let value = match op {
+            BinaryOperation::Div | BinaryOperation::Rem if r.is_zero() => { return Err(ConstantEvaluation(String::from("Division by zero in constant expression"))) }
+            BinaryOperation::Add => { l.add(&r) }
+            BinaryOperation::Sub => { l.sub(&r) }
+            BinaryOperation::Mul => { l.mul(&r) }
+            BinaryOperation::Div => { l.div(&r) }
+            BinaryOperation::Rem => { l.rem(&r) }
+            BinaryOperation::Power => { l.pow(&r) }
+        };
+        return Ok(make_omni(value))
+    }
+    if common == Type::DECIMAL {
+        let (l, r) = (left.get_as_decimal(), right.get_as_decimal());
+        let value = match op {
+            BinaryOperation::Div | BinaryOperation::Rem if r == 0.0 => { return Err(ConstantEvaluation(String::from("Division by zero in constant expression"))) }
+            BinaryOperation::Add => { l + r }
+            BinaryOperation::Sub => { l - r }
+            BinaryOperation::Mul => { l * r }
+            BinaryOperation::Div => { l / r }
+            BinaryOperation::Rem => { l % r }
+            BinaryOperation::Power => { l.powf(r) }
+        };
+        return Ok(Box::new(OmniaDecimal::new(value)))
+    }
+    let (l, r) = (as_integer(left)?, as_integer(right)?);
+    let value = match op {
+        BinaryOperation::Div | BinaryOperation::Rem if r == 0 => { return Err(ConstantEvaluation(String::from("Division by zero in constant expression"))) }
+        BinaryOperation::Add => { l.checked_add(r) }
+        BinaryOperation::Sub => { l.checked_sub(r) }
+        BinaryOperation::Mul => { l.checked_mul(r) }
+        BinaryOperation::Div => { l.checked_div(r) }
+        BinaryOperation::Rem => { l.checked_rem(r) }
+        BinaryOperation::Power => { u32::try_from(r).ok().and_then(|x| l.checked_pow(x)) }
+    };
+    make_integer(&common, value.ok_or(ConstantEvaluation(format!("Constant {} {} {} overflows {}", l, op, r, common)))?)
+}
+
+fn bitwise(op: &BitwiseOperation, left: &dyn OmniaValue, right: &dyn OmniaValue) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    let (l, r) = (as_integer(left)?, as_integer(right)?);
+    match op {
+        BitwiseOperation::ShiftL | BitwiseOperation::ShiftR => {
+            let r#type = type_of(left);
+            let bits = size_of(&r#type) * 8;
+            if r < 0 || r as u64 >= bits {
+                return Err(ConstantEvaluation(format!("Cannot shift a {} by {} bits", r#type, r)))
+            }
+            let value = if matches!(op, BitwiseOperation::ShiftL) { l << r } else { l >> r };
+            make_integer(&r#type, value)
+        }
+        BitwiseOperation::BitwiseAnd => { make_integer(&common_type(left, right)?, l & r) }
+        BitwiseOperation::BitwiseOr => { make_integer(&common_type(left, right)?, l | r) }
+        BitwiseOperation::BitwiseXor => { make_integer(&common_type(left, right)?, l ^ r) }
+    }
+}
+
+fn comparison(op: &ConditionalOperation, left: &dyn OmniaValue, right: &dyn OmniaValue) -> Result<Box<dyn OmniaValue>, SemanticsError> {
+    if matches!(op, ConditionalOperation::StrictEq) && type_of(left) != type_of(right) {
+        return Err(ConstantEvaluation(format!("`=!` compares without promotion, but operands are {} and {}", type_of(left), type_of(right))))
+    }
+    let ordering = match common_type(left, right)? {
+        Type::DECIMAL => { left.get_as_decimal().partial_cmp(&right.get_as_decimal()) }
+        Type::OMNI => { as_omni(left)?.partial_cmp(&as_omni(right)?) }
+        Type::BOOL => { Some(as_bool(left)?.cmp(&as_bool(right)?)) }
+        _ => { Some(as_integer(left)?.cmp(&as_integer(right)?)) }
+    };
+    let ordering = ordering.ok_or(ConstantEvaluation(String::from("Cannot compare NaN constants")))?;
+    let value = match op {
+        ConditionalOperation::Greater => { ordering.is_gt() }
+        ConditionalOperation::Less => { ordering.is_lt() }
+        ConditionalOperation::GreaterEq => { ordering.is_ge() }
+        ConditionalOperation::LessEq => { ordering.is_le() }
+        ConditionalOperation::Eq | ConditionalOperation::StrictEq => { ordering.is_eq() }
+        ConditionalOperation::NotEq => { ordering.is_ne() }
+    };
+    Ok(Box::new(OmniaBool::new(value)))
+}
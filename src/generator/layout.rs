@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+use crate::core::omnia_types::{ArraySize, Type};
+use crate::generator::semantics::{EnumInfo, StructInfo};
+use crate::generator::size_of;
+
+/// How the fields of a struct are placed, chosen with `@layout("C")` or `@layout("packed")`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Repr {
+    /// Fields are reordered by decreasing alignment, which keeps the padding between them small
+    Default,
+    /// Fields stay in declaration order, each aligned the way a C compiler does, for structs passed over FFI
+    C,
+    /// Fields stay in declaration order without any padding and the struct is aligned to 1 byte
+    Packed
+}
+impl Repr {
+    /// Layout named by the argument of `@layout`
+    pub fn from_name(name: &str) -> Option<Repr> {
+        match name {
+            "C" => { Some(Repr::C) }
+            "packed" => { Some(Repr::Packed) }
+            _ => { None }
+        }
+    }
+}
+
+/// Payload offsets of a single enum variant
+pub struct VariantLayout {
+    pub name: String,
+    pub tag: u64,
+    pub fields: Vec<(String, u64)>
+}
+
+/// An enum value is a tag selecting the variant, followed by the variant's payload.
+/// Every value of the enum takes the tag size plus the size of its largest payload, padded to the enum alignment
+pub struct EnumLayout {
+    pub tag_size: u64,
+    pub size: u64,
+    pub align: u64,
+    pub variants: Vec<VariantLayout>
+}
+
+/// Size, alignment and field offsets of a struct. Offsets are listed in declaration order whatever the [Repr]
+pub struct StructLayout {
+    pub size: u64,
+    pub align: u64,
+    pub fields: Vec<(String, u64)>
+}
+
+/// Layouts of the types of a program. Structs and enums are registered as they are declared and laid out on demand,
+/// so a field may refer to a type declared later. A struct containing itself, even as a nullable field, has no finite
+/// size and is reported as an error
+pub struct Layouts {
+    structs: HashMap<String, (StructInfo, Repr)>,
+    enums: HashMap<String, EnumInfo>,
+    interfaces: HashSet<String>
+}
+impl Default for Layouts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Layouts {
+    pub fn new() -> Self {
+        Self {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            interfaces: HashSet::new()
+        }
+    }
+    pub fn add_struct(&mut self, info: StructInfo, repr: Repr) {
+        self.structs.insert(info.name.clone(), (info, repr));
+    }
+    pub fn add_enum(&mut self, info: EnumInfo) {
+        self.enums.insert(info.name.clone(), info);
+    }
+    /// Interface references are a data pointer and a table pointer
+    pub fn add_interface(&mut self, name: String) {
+        self.interfaces.insert(name);
+    }
+    /// Whether the struct or enum `name` is registered
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.structs.contains_key(name) || self.enums.contains_key(name)
+    }
+    /// Size in bytes of a value of type `t`, the answer of `sizeof(t)`
+    pub fn size_of(&self, t: &Type) -> Result<u64, String> {
+        self.layout(t, &mut Vec::new()).map(|(size, _)| size)
+    }
+    /// Alignment in bytes of a value of type `t`, the answer of `alignof(t)`
+    pub fn align_of(&self, t: &Type) -> Result<u64, String> {
+        self.layout(t, &mut Vec::new()).map(|(_, align)| align)
+    }
+    /// Layout of the struct `name`, with its type parameters bound to `args`
+    pub fn struct_layout(&self, name: &str, args: &[Type]) -> Result<StructLayout, String> {
+        self.struct_layout_in(name, args, &mut Vec::new())
+    }
+    /// Layout of the enum `name`, with its type parameters bound to `args`
+    pub fn enum_layout(&self, name: &str, args: &[Type]) -> Result<EnumLayout, String> {
+        self.enum_layout_in(name, args, &mut Vec::new())
+    }
+    /// Size and alignment of `t`. `path` holds the structs and enums being laid out, to catch the ones containing
+    /// themselves
+    fn layout(&self, t: &Type, path: &mut Vec<String>) -> Result<(u64, u64), String> {
+        match t {
+            Type::CUSTOM(name, args) if self.structs.contains_key(name) => {
+                let layout = self.struct_layout_in(name, args, path)?;
+                Ok((layout.size, layout.align))
+            }
+            Type::CUSTOM(name, args) if self.enums.contains_key(name) => {
+                let layout = self.enum_layout_in(name, args, path)?;
+                Ok((layout.size, layout.align))
+            }
+            Type::CUSTOM(name, _) if self.interfaces.contains(name) => { Ok((16, 8)) }
+            Type::CUSTOM(name, _) => { Err(format!("type '{}' is not defined", name)) }
+            Type::GENERIC(name) => { Err(format!("the size of type parameter '{}' is only known for each instance", name)) }
+            Type::ARRAY(_, ArraySize::Constant(name)) => { Err(format!("array size '{}' is not resolved", name)) }
+            Type::ARRAY(_, ArraySize::Unsized) => { Err(format!("the length of {} is only known at runtime, use it through a reference", t)) }
+            Type::ARRAY(item, ArraySize::Fixed(n)) => {
+                let (size, align) = self.layout(item, path)?;
+                Ok((size * n, align))
+            }
+            Type::TUPLE(items) => {  // elements in order, each aligned on its own, like the fields of a C struct
+                let items = items.iter().map(|x| self.layout(x, path)).collect::<Result<Vec<(u64, u64)>, String>>()?;
+                let (_, size, align) = place(&items, Repr::C);
+                Ok((size, align))
+            }
+            Type::NULLABLE(inner) => {  // the value followed by a flag telling whether it is present
+                let (size, align) = self.layout(inner, path)?;
+                Ok(((size + 1).next_multiple_of(align), align))
+            }
+            Type::RESULT(value, error) => {  // a one byte tag followed by the success or the error value
+                let (value_size, value_align) = self.layout(value, path)?;
+                let (error_size, error_align) = self.layout(error, path)?;
+                let align = value_align.max(error_align);
+                Ok(((1u64.next_multiple_of(align) + value_size.max(error_size)).next_multiple_of(align), align))
+            }
+            Type::NEVER => { Ok((0, 1)) }
+            _ => { Ok((size_of(t), align_of(t))) }
+        }
+    }
+    fn struct_layout_in(&self, name: &str, args: &[Type], path: &mut Vec<String>) -> Result<StructLayout, String> {
+        let (info, repr) = self.structs.get(name).ok_or(format!("struct '{}' is not defined", name))?;
+        let bindings: HashMap<String, Type> = info.generics.iter().cloned().zip(args.iter().cloned()).collect();
+        let fields = self.enter(name, path, |layouts, path| {
+            info.fields.iter().map(|(_, t)| layouts.layout(&t.substitute(&bindings), path)).collect::<Result<Vec<(u64, u64)>, String>>()
+        })?;
+        let (offsets, size, align) = place(&fields, *repr);
+        Ok(StructLayout {
+            size,
+            align,
+            fields: info.fields.iter().map(|(x, _)| x.clone()).zip(offsets).collect()
+        })
+    }
+    fn enum_layout_in(&self, name: &str, args: &[Type], path: &mut Vec<String>) -> Result<EnumLayout, String> {
+        let info = self.enums.get(name).ok_or(format!("enum '{}' is not defined", name))?;
+        let bindings: HashMap<String, Type> = info.generics.iter().cloned().zip(args.iter().cloned()).collect();
+        let tag_size = match info.variants.len() {
+            0..=0xFF => 1,
+            0x100..=0xFFFF => 2,
+            _ => 4
+        };
+        let payloads = self.enter(name, path, |layouts, path| {
+            info.variants.iter().map(|v| v.fields.iter().map(|(_, t)| layouts.layout(&t.substitute(&bindings), path)).collect::<Result<Vec<(u64, u64)>, String>>())
+                .collect::<Result<Vec<Vec<(u64, u64)>>, String>>()
+        })?;
+        let align = payloads.iter().flatten().map(|(_, align)| *align).fold(tag_size, u64::max);
+        let mut size = tag_size;
+        let mut variants: Vec<VariantLayout> = Vec::new();
+        for (tag, (variant, fields)) in info.variants.iter().zip(payloads).enumerate() {
+            let (offsets, payload, _) = place(&[(tag_size, tag_size)].into_iter().chain(fields).collect::<Vec<(u64, u64)>>(), Repr::C);
+            size = size.max(payload);
+            variants.push(VariantLayout {
+                name: variant.name.clone(),
+                tag: tag as u64,
+                fields: variant.fields.iter().map(|(x, _)| x.clone()).zip(offsets.into_iter().skip(1)).collect()
+            });
+        }
+        Ok(EnumLayout {
+            tag_size,
+            size: size.next_multiple_of(align),
+            align,
+            variants
+        })
+    }
+    /// Lays out the members of the struct or enum `name`, failing if it is already being laid out
+    fn enter<T>(&self, name: &str, path: &mut Vec<String>, members: impl FnOnce(&Self, &mut Vec<String>) -> Result<T, String>) -> Result<T, String> {
+        if let Some(start) = path.iter().position(|x| x == name) {
+            return Err(format!("'{}' contains itself through {} -> {} and has no finite size", name, path[start..].join(" -> "), name))
+        }
+        path.push(name.to_string());
+        let result = members(self, path);
+        path.pop();
+        result
+    }
+}
+
+/// Places members of the given sizes and alignments, returning their offsets in the given order, the total size and
+/// the alignment
+fn place(members: &[(u64, u64)], repr: Repr) -> (Vec<u64>, u64, u64) {
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    if repr == Repr::Default {
+        order.sort_by_key(|&i| std::cmp::Reverse(members[i].1));
+    }
+    let mut offsets = vec![0u64; members.len()];
+    let mut offset = 0u64;
+    let mut align = 1u64;
+    for i in order {
+        let (size, member_align) = members[i];
+        let member_align = if repr == Repr::Packed { 1 } else { member_align };
+        offset = offset.next_multiple_of(member_align);
+        offsets[i] = offset;
+        offset += size;
+        align = align.max(member_align);
+    }
+    (offsets, offset.next_multiple_of(align), align)
+}
+
+/// Alignment in bytes of a value of type `t`. Scalars are aligned to their size, up to 16 bytes for `omni`.
+/// Like [size_of], it doesn't know about tuples, structs and enums
+pub fn align_of(t: &Type) -> u64 {
+    match t {
+        Type::FUNCTION(_, _) => { 8 }
+        Type::ARRAY(item, _) => { align_of(item) }
+        _ => { size_of(t).min(16) }
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::lexer::token::Span;
+
+/// Named check whose findings are reported at a configurable [Level] instead of always failing the compilation
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Lint {
+    /// Local variable or parameter that is never read
+    UnusedVariable,
+    /// `plug` item that is never called
+    UnusedPlug,
+    /// Binding that hides a variable of an enclosing scope
+    Shadowing,
+    /// Function that is neither `visible` nor used
+    UnusedFunction,
+    /// Statement after one that always returns
+    Unreachable,
+    /// Use of a function or struct marked `@deprecated`
+    Deprecated
+}
+/// Project lint config, read from the working directory if it exists. See [LintLevels::parse]
+pub const LINT_CONFIG: &str = "omnia.lints";
+
+pub const LINTS: [Lint; 6] = [Lint::UnusedVariable, Lint::UnusedPlug, Lint::Shadowing, Lint::UnusedFunction, Lint::Unreachable, Lint::Deprecated];
+impl Lint {
+    /// Name used in `@allow`, `@warn` and `@deny` and in the project config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => { "unused_variable" }
+            Lint::UnusedPlug => { "unused_plug" }
+            Lint::Shadowing => { "shadowing" }
+            Lint::UnusedFunction => { "unused_function" }
+            Lint::Unreachable => { "unreachable" }
+            Lint::Deprecated => { "deprecated" }
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Lint> {
+        LINTS.into_iter().find(|x| x.name() == name)
+    }
+}
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// What happens to the findings of a lint
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Level {
+    /// Not reported
+    Allow,
+    /// Reported without failing the compilation
+    Warn,
+    /// Reported as an error
+    Deny
+}
+impl Level {
+    /// Level of the attribute or config value of the same name
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name {
+            "allow" => { Some(Level::Allow) }
+            "warn" => { Some(Level::Warn) }
+            "deny" => { Some(Level::Deny) }
+            _ => { None }
+        }
+    }
+}
+
+/// Levels of the lints, `warn` for the ones not set
+#[derive(Clone)]
+pub struct LintLevels {
+    levels: HashMap<Lint, Level>
+}
+impl Default for LintLevels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl LintLevels {
+    pub fn new() -> Self {
+        Self {
+            levels: HashMap::new()
+        }
+    }
+    /// Reads a project config of `lint = level` lines, e.g. `shadowing = deny`. `#` starts a comment
+    pub fn parse(config: &str) -> Result<Self, String> {
+        let mut levels = Self::new();
+        for (i, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue
+            }
+            let (lint, level) = line.split_once('=').ok_or(format!("Line {} of the lint config is not `lint = level`", i + 1))?;
+            let lint = Lint::from_name(lint.trim()).ok_or(format!("Unknown lint '{}' on line {} of the lint config", lint.trim(), i + 1))?;
+            let level = Level::from_name(level.trim()).ok_or(format!("Unknown level '{}' on line {} of the lint config, expected allow, warn or deny", level.trim(), i + 1))?;
+            levels.set(lint, level);
+        }
+        Ok(levels)
+    }
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels.insert(lint, level);
+    }
+    pub fn get(&self, lint: Lint) -> Level {
+        self.levels.get(&lint).copied().unwrap_or(Level::Warn)
+    }
+}
+
+/// Finding of a lint at the level it is reported with
+pub struct Diagnostic {
+    lint: Lint,
+    level: Level,
+    message: String,
+    span: Option<Span>
+}
+impl Diagnostic {
+    pub fn new(lint: Lint, level: Level, message: String, span: Option<Span>) -> Self {
+        Self {
+            lint,
+            level,
+            message,
+            span
+        }
+    }
+    /// The diagnostic followed by the source it refers to
+    pub fn render(&self, source: &str) -> String {
+        match &self.span {
+            Some(span) => { format!("{}\n  at {}", self, span.excerpt(source)) }
+            None => { self.to_string() }
+        }
+    }
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.level {
+            Level::Deny => { write!(f, "Lint error: {} [{}]", self.message, self.lint) }
+            _ => { write!(f, "Warning: {} [{}]", self.message, self.lint) }
+        }
+    }
+}
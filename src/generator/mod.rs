@@ -1,9 +1,12 @@
-mod utils;
-mod semantics;
+pub mod utils;
+pub mod semantics;
+pub mod layout;
+mod consteval;
+pub mod testrunner;
+pub mod lints;
+pub mod program;
 
-use std::any::Any;
-use std::collections::{HashMap, VecDeque};
-use crate::core::omnia_types::Type;
+use crate::core::omnia_types::{ArraySize, Type};
 use crate::core::omnia_types::Type::{BOOL, BYTE, CHAR, DECIMAL, INT, LONG, NULL, OMNI, UBYTE, UINT, ULONG};
 
 pub const TYPES_SIZES: &[(Type, u64); 11] = &[
@@ -20,3 +23,13 @@ pub const TYPES_SIZES: &[(Type, u64); 11] = &[
     (OMNI, 16)
 ];
 
+/// Size in bytes of a value of a scalar type `t`. Function values are a code pointer plus an environment pointer.
+/// Tuples, structs and enums are laid out by [layout::Layouts], which knows the declarations of their members
+pub fn size_of(t: &Type) -> u64 {
+    match t {
+        Type::FUNCTION(_, _) => 16,
+        Type::ARRAY(item, ArraySize::Fixed(n)) => size_of(item) * n,
+        _ => TYPES_SIZES.iter().find(|(x, _)| x == t).map(|(_, size)| *size).unwrap_or(8)
+    }
+}
+
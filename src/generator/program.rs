@@ -0,0 +1,145 @@
+use std::path::Path;
+use crate::core::omnia_types::{ArraySize, Type};
+use crate::generator::semantics::utils::SemanticsError;
+use crate::generator::semantics::utils::SemanticsError::{EntryPoint, Redefinition};
+use crate::lexer::token::Span;
+use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, FunctionDeclarationStatementNode, Statement};
+
+/// Name of the function a program starts from
+pub const ENTRY_POINT: &str = "main";
+
+/// Source file of a program, named after its file stem, e.g. `geometry` for `src/geometry.oa`
+pub struct Module {
+    name: String,
+    source: String,
+    root: BlockStatementNode
+}
+impl Module {
+    pub fn new(path: &str, source: String, root: BlockStatementNode) -> Self {
+        Self {
+            name: Path::new(path).file_stem().map_or(path.to_string(), |x| x.to_string_lossy().to_string()),
+            source,
+            root
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_source(&self) -> &String {
+        &self.source
+    }
+    pub fn get_root(&self) -> &BlockStatementNode {
+        &self.root
+    }
+    /// The parsed module, for the semantic analyzer which takes it over
+    pub fn into_root(self) -> BlockStatementNode {
+        self.root
+    }
+}
+
+/// Top-level declaration of a module
+struct Definition<'a> {
+    module: &'a Module,
+    kind: &'static str,
+    name: String,
+    span: Option<Span>,
+    function: Option<&'a FunctionDeclarationStatementNode>
+}
+impl Definition<'_> {
+    /// Where the declaration is, e.g. `module 'geometry' at 3:1: func area() -> decimal {`
+    fn location(&self) -> String {
+        match &self.span {
+            Some(span) => { format!("module '{}' at {}", self.module.name, span.excerpt(&self.module.source)) }
+            None => { format!("module '{}'", self.module.name) }
+        }
+    }
+    /// Functions of the same name only conflict when they have the same parameter types, like in an overload set
+    fn conflicts(&self, other: &Definition) -> bool {
+        self.name == other.name && match (self.function, other.function) {
+            (Some(function), Some(other)) => { function.get_parameter_types() == other.get_parameter_types() }
+            _ => { true }
+        }
+    }
+}
+
+/// Checks what only the whole program tells, once every module is parsed: it has exactly one entry point with one
+/// of the allowed signatures, and no top-level symbol is defined by two modules. Symbols defined twice in the same
+/// module are left to the semantic analysis of that module
+pub fn check_program(modules: &[Module]) -> Result<(), Vec<SemanticsError>> {
+    let definitions: Vec<Definition> = modules.iter().flat_map(definitions).collect();
+    let mut errors: Vec<SemanticsError> = Vec::new();
+    for (i, definition) in definitions.iter().enumerate() {
+        if definition.name == ENTRY_POINT && definition.function.is_some() {
+            continue
+        }
+        let first = definitions[..i].iter().find(|x| !std::ptr::eq(x.module, definition.module) && x.conflicts(definition));
+        if let Some(first) = first {
+            errors.push(Redefinition(format!("{} '{}' of {} is already defined by {}", definition.kind, definition.name, definition.location(), first.location())));
+        }
+    }
+    let entries: Vec<&Definition> = definitions.iter().filter(|x| x.name == ENTRY_POINT && x.function.is_some()).collect();
+    match entries[..] {
+        [] => { errors.push(EntryPoint(format!("The program has no entry point, declare `func {}()` in one of its modules", ENTRY_POINT))) }
+        [entry] => {
+            if let Err(e) = check_entry_point(entry) {
+                errors.push(e);
+            }
+        }
+        _ => {
+            let locations: Vec<String> = entries.iter().map(|x| x.location()).collect();
+            errors.push(EntryPoint(format!("The program has {} entry points, but it must have exactly one: {}", entries.len(), locations.join("; "))));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The entry point takes nothing or the command line arguments as `args: &char[][]`, and returns nothing or an `int`
+/// exit code. It may be declared `static` and name its parameter type first with the `string` alias of `char[]`, so
+/// `func main()`, `func main(args: &char[][]) -> int` and `static func main(&string[] args)` are all accepted
+fn check_entry_point(entry: &Definition) -> Result<(), SemanticsError> {
+    let function = entry.function.unwrap();
+    let arguments = Type::REFERENCE(Box::from(Type::ARRAY(Box::from(Type::CHARARR), ArraySize::Unsized)));
+    let parameters = function.get_parameter_types();
+    let accepted = function.get_generics().is_empty()
+        && (parameters.is_empty() || parameters == vec![arguments.clone()])
+        && matches!(function.get_return_type(), Type::NULL | Type::INT);
+    if !accepted {
+        let returns = match function.get_return_type() {
+            Type::NULL => { String::new() }
+            other => { format!(" -> {}", other) }
+        };
+        return Err(EntryPoint(format!("'{}' of {} must be declared as `func {}()` or `func {}(args: {})`, also written `static func {}(&string[] args)`, optionally returning an `int` exit code, got {}({}){}",
+            ENTRY_POINT, entry.location(), ENTRY_POINT, ENTRY_POINT, arguments, ENTRY_POINT, ENTRY_POINT,
+            parameters.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "), returns)))
+    }
+    if let Some(attribute) = function.get_attributes().iter().find(|x| x.get_name() == "test" || x.get_name() == "extern") {
+        return Err(EntryPoint(format!("'{}' of {} can't be marked '{}'", ENTRY_POINT, entry.location(), attribute)))
+    }
+    Ok(())
+}
+
+fn definitions(module: &Module) -> Vec<Definition<'_>> {
+    let mut definitions: Vec<Definition> = Vec::new();
+    for (i, node) in module.root.get_body().iter().enumerate() {
+        let (kind, name, function) = match node {
+            ASTNode::Statement(Statement::FunctionDeclaration(f)) => { ("Function", f.get_name(), Some(f.as_ref())) }
+            ASTNode::Statement(Statement::StructDeclaration(s)) => { ("Struct", s.get_name(), None) }
+            ASTNode::Statement(Statement::EnumDeclaration(e)) => { ("Enum", e.get_name(), None) }
+            ASTNode::Statement(Statement::InterfaceDeclaration(interface)) => { ("Interface", interface.get_name(), None) }
+            ASTNode::Statement(Statement::Static(s)) => { ("Constant", s.get_name(), None) }
+            _ => { continue }
+        };
+        definitions.push(Definition {
+            module,
+            kind,
+            name: name.clone(),
+            span: module.root.get_span(i),
+            function
+        });
+    }
+    definitions
+}
@@ -1,69 +1,201 @@
 use std;
-use std::collections::{HashMap, VecDeque};
+use std::cell::{Ref, RefCell};
+use std::mem;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use crate::core::omnia_types::{ArraySize, CastMode, Conversion, OmniaValue, Type};
+use crate::core::omnia_types::interner::{TypeId, TypeInterner};
+use crate::generator::consteval;
+use crate::generator::consteval::ConstEvaluator;
 use crate::generator::semantics::utils::SemanticsError;
-use crate::generator::semantics::utils::SemanticsError::NotYetImplemented;
-use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, Expression, Statement};
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum DataType {
-    Byte,
-    UByte,
-    Int,
-    UInt,
-    Long,
-    ULong,
-    Decimal,
-    Omni,
-    Char,
-    Chararr,
-    Null,
-    Bool
-}
+use crate::generator::semantics::utils::SemanticsError::{ConstantEvaluation, Immutable, InvalidAttribute, Layout, MissingReturn, Redefinition, TypeMismatch, UndefinedSymbol};
+use crate::generator::layout::{Layouts, Repr};
+use crate::generator::lints::{Diagnostic, Level, Lint, LintLevels, LINTS};
+use crate::lexer::token::Span;
+use crate::parser::ast::visit::{walk_expression, walk_statement, Visitor};
+use crate::parser::ast::nodes::{ASTNode, AssignmentOperator, AttributeNode, BitwiseOperation, BlockStatementNode, CastExpressionNode, ConditionalOperation, DestructuringStatementNode, Expression, ExtensionStatementNode, FunctionCallNode, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, LambdaExpressionNode, LayoutQuery, LayoutQueryExpressionNode, LiteralExpression, LogicalOperation, MethodCallNode, Pattern, PatternBindings, PropagateExpressionNode, Statement, StaticDeclarationStatementNode, SwitchStatementNode, UnaryExpressionNode, UnaryOperation, VariableCreationStatementNode, VariantPayload, VariantExpressionNode, VariantValues};
 
 #[derive(Debug, Clone)]
 pub struct VariableInfo {
     pub name: String,
-    pub r#type: DataType,
-    pub mutable: bool
+    pub r#type: Type,
+    pub mutable: bool,
+    pub inferred: bool,
+    /// Span of the statement that declared the variable, for diagnostics
+    pub declared: Option<Span>,
+    /// Index of the local checked by the unused variable lint, `None` for statics
+    pub local: Option<usize>,
+    /// Declared type of a variable whose reads a null check narrowed to `r#type`. Assignments still accept it
+    pub narrowed_from: Option<Type>
+}
+
+/// Local variable or parameter, with the level of the unused variable lint where it is declared
+struct Local {
+    name: String,
+    parameter: bool,
+    level: Level,
+    span: Option<Span>
 }
 
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
     pub name: String,
-    pub returns: DataType,
-    pub parameters: Vec<(String, DataType)>
+    pub returns: Type,
+    pub parameters: Vec<(String, Type)>,
+    pub generics: Vec<String>,
+    pub bounds: Vec<(String, String)>
+}
+impl FunctionInfo {
+    /// Binds type parameters from the argument types of a call, so a generic function is checked once
+    /// and every call only checks the bindings. A type parameter taken by several arguments is bound to their
+    /// common type, e.g. `T` is decimal in `max(1, 2.5)`
+    pub fn bind(&self, args: &Vec<Type>) -> Result<HashMap<String, Type>, SemanticsError> {
+        if args.len() != self.parameters.len() {
+            return Err(TypeMismatch(format!("Function '{}' takes {} arguments, got {}", self.name, self.parameters.len(), args.len())))
+        }
+        let mut bindings: HashMap<String, Type> = HashMap::new();
+        for ((name, param), arg) in self.parameters.iter().zip(args) {
+            let accepted = match param {
+                Type::GENERIC(generic) => {
+                    match bindings.get(generic).and_then(|x| x.common_with(arg)) {
+                        Some(common) => { bindings.insert(generic.clone(), common); true }
+                        None => { param.bind(arg, &mut bindings) }
+                    }
+                }
+                _ => { arg.promotes_to(param) }
+            };
+            if !accepted {
+                return Err(TypeMismatch(suggest_cast(format!("Argument '{}' of '{}' expects {}, got {}", name, self.name, param.substitute(&bindings), arg), arg, param)))
+            }
+        }
+        Ok(bindings)
+    }
+    pub fn get_parameter_types(&self) -> Vec<Type> {
+        self.parameters.iter().map(|(_, t)| t.clone()).collect()
+    }
+}
+impl Display for FunctionInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", signature(&self.name, &self.get_parameter_types()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantInfo {
+    pub name: String,
+    pub fields: Vec<(String, Type)>,
+    pub named: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumInfo {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub variants: Vec<VariantInfo>
+}
+impl EnumInfo {
+    pub fn get_variant(&self, name: &str) -> Result<&VariantInfo, SemanticsError> {
+        self.variants.iter().find(|x| x.name == name).ok_or(UndefinedSymbol(format!("Enum '{}' has no variant '{}'", self.name, name)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StructInfo {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub fields: Vec<(String, Type)>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodInfo {
+    pub name: String,
+    pub parameters: Vec<Type>,
+    pub returns: Type
+}
+impl MethodInfo {
+    /// Signature of a method, without its `self` parameter when `has_receiver` is set
+    pub fn from_args(name: &str, args: &[Expression], returns: &Type, has_receiver: bool) -> Self {
+        let parameters = args.iter().skip(if has_receiver { 1 } else { 0 }).filter_map(|x| match x {
+            Expression::Argument(a) => Some(a.get_type().clone()),
+            _ => None
+        }).collect();
+        Self {
+            name: name.to_string(),
+            parameters,
+            returns: returns.clone()
+        }
+    }
+}
+impl Display for MethodInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.name, Type::FUNCTION(self.parameters.clone(), Box::from(self.returns.clone())))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub methods: Vec<MethodInfo>
 }
 
 #[derive(Debug, Clone)]
 pub enum Symbol {
     Variable(VariableInfo),
-    Function(FunctionInfo),
+    /// Overload set of the functions sharing a name, which differ in their parameter types
+    Function(Vec<FunctionInfo>),
+    Enum(EnumInfo),
+    Struct(StructInfo),
+    Interface(InterfaceInfo),
     Custom(String)
 }
 pub struct Scope {
     symbols: HashMap<String, Symbol>
 }
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Scope {
     pub fn new() -> Self {
         Self {
             symbols: HashMap::new()
         }
     }
+    /// Adds a symbol, or the functions of `symbol` to the overload set of the same name
     pub fn insert(&mut self, name: String, symbol: Symbol) -> Result<(), String> {
-        if self.symbols.contains_key(&name) {
-            Err(format!("Symbol '{}' already defined in this scope", name))
-        } else {
-            self.symbols.insert(name, symbol);
-            Ok(())
+        match (self.symbols.get_mut(&name), symbol) {
+            (Some(Symbol::Function(overloads)), Symbol::Function(functions)) => {
+                for function in functions {
+                    if overloads.iter().any(|x| x.get_parameter_types() == function.get_parameter_types()) {
+                        return Err(format!("Function '{}' is already defined in this scope with the same parameter types", function))
+                    }
+                    overloads.push(function);
+                }
+                Ok(())
+            }
+            (Some(_), _) => { Err(format!("Symbol '{}' already defined in this scope", name)) }
+            (None, symbol) => {
+                self.symbols.insert(name, symbol);
+                Ok(())
+            }
         }
     }
     pub fn get(&self, name: &str) -> Option<&Symbol> {
         self.symbols.get(name)
     }
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        self.symbols.get_mut(name)
+    }
 }
 pub struct SymbolTable {
     scopes: VecDeque<Scope>
 }
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl SymbolTable {
     pub fn new() -> Self {
         Self {
@@ -83,6 +215,10 @@ impl SymbolTable {
             Err(String::from("No available scopes for inserting"))
         }
     }
+    /// Number of open scopes, the global one included
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
     pub fn get_symbol(&self, name: &str) -> Option<&Symbol> {
         for scope in &self.scopes {
             if let Some(symbol) = scope.get(name) {
@@ -91,51 +227,1769 @@ impl SymbolTable {
         }
         None
     }
+    pub fn get_symbol_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        self.scopes.iter_mut().find_map(|x| x.get_mut(name))
+    }
+    /// Symbol of an enclosing scope that a new symbol named `name` in the innermost scope would hide
+    pub fn get_shadowed(&self, name: &str) -> Option<&Symbol> {
+        if self.scopes.front().is_none_or(|x| x.get(name).is_some()) {
+            return None
+        }
+        self.scopes.iter().skip(1).find_map(|x| x.get(name))
+    }
 }
 
 pub struct SemanticsAnalyzer {
     table: SymbolTable,
     input: BlockStatementNode,
-
+    /// Variables captured by each lambda, keyed by [lambda id][LambdaExpressionNode::get_id]
+    captures: HashMap<usize, Vec<VariableInfo>>,
+    returns: Vec<Type>,
+    constants: HashMap<String, Box<dyn OmniaValue>>,
+    methods: HashMap<String, Vec<MethodInfo>>,
+    implementations: HashMap<String, Vec<String>>,
+    bounds: HashMap<String, Vec<String>>,
+    /// Functions and structs marked `@deprecated`, with the note to show where they are used
+    deprecated: HashMap<String, Option<String>>,
+    errors: Vec<SemanticsError>,
+    warnings: Vec<Diagnostic>,
+    /// Lint levels of the project, overridden by `@allow`, `@warn` and `@deny` of the functions being analyzed
+    lints: Vec<LintLevels>,
+    locals: Vec<Local>,
+    used: RefCell<HashSet<usize>>,
+    /// Names of the functions and plugged items which are used
+    called: RefCell<HashSet<String>>,
+    /// Deprecated functions and structs used by the statement being analyzed, reported once it is done
+    uses: RefCell<Vec<String>>,
+    plugs: Vec<(String, Option<Span>)>,
+    /// Roots of the plugged paths, e.g. `std`, whose items can be named as `std::item`
+    libraries: HashSet<String>,
+    /// Parameter types of the overload each call of an overload set resolves to, keyed by the address of the call
+    overloads: RefCell<HashMap<usize, Vec<Type>>>,
+    layouts: Layouts,
+    /// Span of the statement being analyzed
+    span: Option<Span>,
+    /// Depth of the branches of `if` expressions being analyzed, whose trailing values are not returned
+    values: usize,
+    types: RefCell<HashMap<usize, TypeId>>,
+    interner: RefCell<TypeInterner>
 }
 impl SemanticsAnalyzer {
     pub fn new(input: BlockStatementNode) -> Self {
         Self {
             table: SymbolTable::new(),
-            input
+            input,
+            captures: HashMap::new(),
+            returns: Vec::new(),
+            constants: HashMap::new(),
+            methods: HashMap::new(),
+            implementations: HashMap::new(),
+            bounds: HashMap::new(),
+            deprecated: HashMap::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            lints: vec![LintLevels::new()],
+            locals: Vec::new(),
+            used: RefCell::new(HashSet::new()),
+            called: RefCell::new(HashSet::new()),
+            uses: RefCell::new(Vec::new()),
+            plugs: Vec::new(),
+            libraries: HashSet::new(),
+            overloads: RefCell::new(HashMap::new()),
+            layouts: Layouts::new(),
+            span: None,
+            values: 0,
+            types: RefCell::new(HashMap::new()),
+            interner: RefCell::new(TypeInterner::new())
         }
     }
-    pub fn start(&mut self) -> Result<(), SemanticsError> {
-        self.analyze();
-
-
-        Ok(())
+    /// Type the checker inferred for an expression of the analyzed program, see [get_input][Self::get_input]
+    pub fn get_type_of(&self, expression: &Expression) -> Option<TypeId> {
+        self.types.borrow().get(&expression_key(expression)).copied()
     }
-    fn analyze(&mut self) -> Result<(), SemanticsError> {
-        for x in self.input.get_body() {
-            return match x {
-                ASTNode::Statement(st) => { self.analyze_statement(st) }
-                ASTNode::Expression(ex) => { self.analyze_expression(ex) }
+    /// Types the annotations refer to
+    pub fn get_interner(&self) -> Ref<'_, TypeInterner> {
+        self.interner.borrow()
+    }
+    /// The analyzed program. Type annotations refer to its expressions, so it must not be modified while they are used
+    pub fn get_input(&self) -> &BlockStatementNode {
+        &self.input
+    }
+    /// Sizes, alignments and field offsets of the declared types
+    pub fn get_layouts(&self) -> &Layouts {
+        &self.layouts
+    }
+    /// Folded values of `static` constants
+    pub fn get_constants(&self) -> &HashMap<String, Box<dyn OmniaValue>> {
+        &self.constants
+    }
+    /// Sets the lint levels of the project
+    pub fn with_lints(mut self, levels: LintLevels) -> Self {
+        self.lints = vec![levels];
+        self
+    }
+    /// Findings of lints set to `warn`, which don't stop the compilation. Denied ones are among the errors
+    pub fn get_warnings(&self) -> &Vec<Diagnostic> {
+        &self.warnings
+    }
+    /// Parameter types of the overload a call of an overloaded function resolves to
+    pub fn get_overload_of_call(&self, call: &FunctionCallNode) -> Option<Vec<Type>> {
+        self.overloads.borrow().get(&node_key(call)).cloned()
+    }
+    /// Analyzes the whole program in the global scope. Every error is collected rather than stopping at the first
+    pub fn start(&mut self) -> Result<(), Vec<SemanticsError>> {
+        let input = std::mem::take(&mut self.input);
+        self.table.push();
+        self.analyze_body(&input);
+        self.table.pop();
+        self.report_unused(&input);
+        self.check_layouts(&input);
+        self.input = input;
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(mem::take(&mut self.errors))
+        }
+    }
+    /// Lays out every struct and enum which is not generic, once all the types they may refer to are declared
+    fn check_layouts(&mut self, input: &BlockStatementNode) {
+        for node in input.get_body() {
+            let (name, r#type) = match node {
+                ASTNode::Statement(Statement::StructDeclaration(s)) if s.get_generics().is_empty() => { (s.get_name(), "struct") }
+                ASTNode::Statement(Statement::EnumDeclaration(e)) if e.get_generics().is_empty() => { (e.get_name(), "enum") }
+                _ => { continue }
+            };
+            if !self.layouts.is_declared(name) {  // the declaration itself failed
+                continue
+            }
+            if let Err(e) = self.layouts.size_of(&Type::CUSTOM(name.clone(), Vec::new())) {
+                self.errors.push(Layout(format!("Cannot lay out {} '{}': {}", r#type, name, e)));
             }
         }
-        Ok(())
     }
     fn analyze_statement(&mut self, statement: &Statement) -> Result<(), SemanticsError> {
+        let mut nested = Nested { analyzer: self, result: Ok(()) };
+        walk_statement(&mut nested, statement);
+        nested.result?;
         match statement {
             Statement::FunctionDeclaration(f) => {
-                self.table.push();
-                self.table.insert_symbol(f.get_name().clone(), Symbol::Function(FunctionInfo { name: f.get_name().clone(), returns: f.get_return_type(), parameters: f.get_args() }))
+                check_attributes(f.get_attributes(), AttributeTarget::Function, f.get_name())?;
+                if f.is_static() && self.table.depth() > 1 {
+                    return Err(TypeMismatch(format!("`static` function '{}' must be declared at the top level", f.get_name())))
+                }
+                if f.get_attribute("test").is_some() && (!f.get_args().is_empty() || !f.get_generics().is_empty() || !matches!(f.get_return_type(), Type::BOOL | Type::NULL)) {
+                    return Err(InvalidAttribute(format!("Test '{}' must take no parameters and return bool or nothing", f.get_name())))
+                }
+                self.analyze_function(f)
+            }
+            Statement::EnumDeclaration(e) => {
+                let mut variants: Vec<VariantInfo> = Vec::new();
+                for variant in e.get_variants() {
+                    if variants.iter().any(|x| &x.name == variant.get_name()) {
+                        return Err(Redefinition(format!("Variant '{}' is declared twice in enum '{}'", variant.get_name(), e.get_name())))
+                    }
+                    variants.push(VariantInfo {
+                        name: variant.get_name().clone(),
+                        fields: variant.get_payload_fields(),
+                        named: matches!(variant.get_payload(), VariantPayload::Struct(_))
+                    });
+                }
+                let info = EnumInfo { name: e.get_name().clone(), generics: e.get_generics().iter().map(|x| x.get_name().clone()).collect(), variants };
+                self.table.insert_symbol(e.get_name().clone(), Symbol::Enum(info.clone())).map_err(Redefinition)?;
+                self.layouts.add_enum(info);
+                Ok(())
+            }
+            Statement::Block(b) => { self.analyze_block(b) }
+            Statement::If(i) => {
+                self.require_bool(i.get_cond())?;
+                let (then, r#else) = self.narrowing(i.get_cond());
+                self.analyze_narrowed(i.get_then(), then)?;
+                match i.get_else() {
+                    Some(e) => { self.analyze_narrowed(e, r#else) }
+                    None => { Ok(()) }
+                }
+            }
+            Statement::Switch(s) => { self.analyze_switch(s) }
+            Statement::VariableCreation(v) => { self.analyze_variable_creation(v) }
+            Statement::Destructuring(d) => { self.analyze_destructuring(d) }
+            Statement::Declaration(d) => {
+                let r#type = self.resolve_sizes(d.get_type())?;
+                self.mark_deprecated_type(&r#type);
+                for name in d.get_names() {
+                    let info = self.local(name, r#type.clone(), d.is_mutable(), false, false);
+                    self.table.insert_symbol(name.clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+                }
+                Ok(())
+            }
+            Statement::Static(s) => { self.analyze_static(s) }
+            Statement::StructDeclaration(s) => {
+                check_attributes(s.get_attributes(), AttributeTarget::Struct, s.get_name())?;
+                let repr = match s.get_attribute("layout").and_then(|x| x.get_argument()) {
+                    Some(name) => {
+                        Repr::from_name(name).ok_or(InvalidAttribute(format!("Unknown layout \"{}\" of '{}', expected \"C\" or \"packed\"", name, s.get_name())))?
+                    }
+                    None => { Repr::Default }
+                };
+                let mut fields: Vec<(String, Type)> = Vec::new();
+                for field in s.get_fields() {
+                    fields.push((field.get_name().clone(), self.resolve_sizes(field.get_type())?));
+                }
+                let info = StructInfo {
+                    name: s.get_name().clone(),
+                    generics: s.get_generics().iter().map(|x| x.get_name().clone()).collect(),
+                    fields
+                };
+                self.table.insert_symbol(s.get_name().clone(), Symbol::Struct(info.clone())).map_err(Redefinition)?;
+                self.layouts.add_struct(info, repr);
+                Ok(())
+            }
+            Statement::InterfaceDeclaration(i) => {
+                let mut methods: Vec<MethodInfo> = Vec::new();
+                for method in i.get_methods() {
+                    if methods.iter().any(|x| &x.name == method.get_name()) {
+                        return Err(Redefinition(format!("Method '{}' is declared twice in interface '{}'", method.get_name(), i.get_name())))
+                    }
+                    methods.push(MethodInfo::from_args(method.get_name(), method.get_args(), method.get_return_type(), false));
+                }
+                self.table.insert_symbol(i.get_name().clone(), Symbol::Interface(InterfaceInfo { name: i.get_name().clone(), methods })).map_err(Redefinition)?;
+                self.layouts.add_interface(i.get_name().clone());
+                Ok(())
+            }
+            Statement::Extension(e) => { self.analyze_extension(e) }
+            Statement::FunctionCall(c) => {
+                let returns = self.type_of_call(c)?;
+                self.require_used(returns, c.get_name())
+            }
+            Statement::MethodCall(c) => {
+                let returns = self.type_of_method_call(c)?;
+                self.require_used(returns, c.get_name())
+            }
+            Statement::Propagate(p) => { self.type_of_propagation(p).map(|_| ()) }
+            Statement::Unary(u) => { self.type_of_unary(u).map(|_| ()) }
+            Statement::Plug(p) => {
+                self.libraries.insert(library(p.get_lib()).to_string());
+                for name in p.get_items() {
+                    self.plugs.push((name.to_string(), self.span));
+                    self.table.insert_symbol(name.to_string(), Symbol::Custom(p.get_lib().clone())).map_err(Redefinition)?;
+                }
+                Ok(())
+            }
+            Statement::Assignment(a) => {
+                let target = match self.table.get_symbol(a.get_cont()) {
+                    Some(Symbol::Variable(info)) if !info.mutable => { return Err(immutable(info, &format!("Cannot assign to immutable '{}'", info.name))) }
+                    Some(Symbol::Variable(info)) if matches!(a.get_op(), AssignmentOperator::ASSIGN) => { info.narrowed_from.clone().unwrap_or(info.r#type.clone()) }
+                    Some(Symbol::Variable(info)) => {
+                        self.mark_used(info);
+                        info.r#type.clone()
+                    }
+                    Some(_) => { return Err(TypeMismatch(format!("'{}' is not a variable and can't be assigned to", a.get_cont()))) }
+                    None => { return Err(UndefinedSymbol(format!("Variable '{}' is not defined", a.get_cont()))) }
+                };
+                let value = self.type_of(a.get_value())?;
+                if !matches!(a.get_op(), AssignmentOperator::ASSIGN) && target.is_nullable() {
+                    return Err(TypeMismatch(format!("'{}' of type {} may be null, check it with `if {} != null` before using it", a.get_cont(), target, a.get_cont())))
+                }
+                if !self.converts(a.get_value(), &value, &target) {
+                    return Err(self.explain(TypeMismatch(suggest_cast(format!("Cannot assign a value of type {} to '{}' of type {}", value, a.get_cont(), target), &value, &target)), &[a.get_value()]))
+                }
+                if value.is_nullable() {  // the null check no longer holds, e.g. `x = null;` in `if x != null { ... }`
+                    if let Some(Symbol::Variable(info)) = self.table.get_symbol_mut(a.get_cont()) {
+                        if let Some(declared) = info.narrowed_from.take() {
+                            info.r#type = declared;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Statement::Return(r) if r.is_tail() && !self.tail_returns() => {  // a branch value, or dropped in a function without a value
+                match r.get_value() {
+                    ASTNode::Expression(e) => {
+                        let value = self.type_of(e)?;
+                        match e {
+                            Expression::FunctionCall(c) => { self.require_used(value, c.get_name()) }
+                            Expression::MethodCall(c) => { self.require_used(value, c.get_name()) }
+                            _ => { Ok(()) }
+                        }
+                    }
+                    ASTNode::Statement(s) => { self.analyze_statement(s) }
+                }
+            }
+            Statement::Return(r) => {
+                let (value, literal) = match r.get_value() {
+                    ASTNode::Expression(e) => { (self.type_of(e)?, Some(e)) }
+                    ASTNode::Statement(s) => {
+                        self.analyze_statement(s)?;
+                        (self.type_of_value(s)?, None)
+                    }
+                };
+                match self.returns.last() {
+                    Some(expected) if !literal.map_or(value.promotes_to(expected), |e| self.converts(e, &value, expected)) => {
+                        Err(TypeMismatch(suggest_cast(format!("Cannot return a value of type {} from a function returning {}", value, expected), &value, expected)))
+                    }
+                    Some(_) => { Ok(()) }
+                    None => { Err(TypeMismatch(String::from("`return` can only be used inside of a function"))) }
+                }
+            }
+            Statement::LambdaDeclaration(l) => {  // the body is analyzed with the other nested lambdas
+                let info = self.local(l.get_name(), l.get_lambda().get_type(), l.is_mutable(), true, false);
+                self.table.insert_symbol(l.get_name().clone(), Symbol::Variable(info)).map_err(Redefinition)
+            }
+        }
+    }
+    /// Declares the functions of a block first, so they can be called before they appear, and notes which of its functions and structs
+    /// are deprecated, then analyzes each statement.
+    /// An error is recorded and analysis carries on with the next statement.
+    /// The span of the enclosing statement is restored afterwards, so nested bodies don't move it
+    fn analyze_body(&mut self, block: &BlockStatementNode) {
+        let span = self.span;
+        for node in block.get_body() {
+            match node {
+                ASTNode::Statement(Statement::FunctionDeclaration(f)) => {
+                    if let Err(e) = self.declare_function(f) {
+                        self.errors.push(e);
+                    }
+                }
+                ASTNode::Statement(Statement::StructDeclaration(s)) => {
+                    if let Some(attribute) = s.get_attribute("deprecated") {
+                        self.deprecated.insert(s.get_name().clone(), attribute.get_argument().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut scopes = 0;
+        let mut unreachable = false;
+        for (i, node) in block.get_body().iter().enumerate() {
+            self.span = block.get_span(i).or(self.span);
+            let result = match node {
+                ASTNode::Statement(st) => { self.analyze_statement(st) }
+                ASTNode::Expression(ex) => { self.analyze_expression(ex) }
+            };
+            self.report_deprecated();
+            if let Err(e) = result {
+                self.errors.push(e);
+                continue
+            }
+            if !unreachable && i + 1 < block.get_body().len() && matches!(node, ASTNode::Statement(s) if self.exits(s)) {
+                let message = String::from("this statement can never run, the one before it always returns");
+                self.report(Lint::Unreachable, message, block.get_span(i + 1));
+                unreachable = true;
+            }
+            if let ASTNode::Statement(Statement::If(i)) = node {
+                let (_, rest) = self.narrowing(i.get_cond());
+                if i.get_else().is_none() && self.exits(i.get_then()) && !rest.is_empty() {
+                    self.table.push();
+                    scopes += 1;
+                    if let Err(e) = self.declare(rest) {
+                        self.errors.push(e);
+                    }
+                }
+            }
+        }
+        for _ in 0..scopes {
+            self.table.pop();
+        }
+        self.span = span;
+    }
+    fn declare_function(&mut self, function: &FunctionDeclarationStatementNode) -> Result<(), SemanticsError> {
+        let mut parameters: Vec<(String, Type)> = Vec::new();
+        for arg in function.get_args() {
+            if let Expression::Argument(a) = arg {
+                parameters.push((a.get_name().clone(), a.get_type().clone()));
+            }
+        }
+        let info = FunctionInfo {
+            name: function.get_name().clone(),
+            returns: function.get_return_type().clone(),
+            parameters,
+            generics: function.get_generics().iter().map(|x| x.get_name().clone()).collect(),
+            bounds: generic_bounds(function.get_generics())
+        };
+        self.table.insert_symbol(function.get_name().clone(), Symbol::Function(vec![info])).map_err(Redefinition)?;
+        if let Some(attribute) = function.get_attribute("deprecated") {
+            self.deprecated.insert(function.get_name().clone(), attribute.get_argument().cloned());
+        }
+        Ok(())
+    }
+    /// Analyzes a function body with its parameters declared and the bounds of its type parameters in effect
+    fn analyze_function(&mut self, function: &FunctionDeclarationStatementNode) -> Result<(), SemanticsError> {
+        let levels = self.attribute_levels(function.get_attributes())?;
+        let bounds = generic_bounds(function.get_generics());
+        for (param, interface) in &bounds {
+            self.bounds.entry(param.clone()).or_default().push(interface.clone());
+        }
+        self.lints.push(levels);
+        self.table.push();
+        self.mark_deprecated_type(function.get_return_type());
+        self.returns.push(function.get_return_type().clone());
+        let values = mem::take(&mut self.values);
+        let result = self.declare_arguments(function.get_args()).and_then(|_| self.analyze_statement(function.get_body()))
+            .and_then(|_| self.require_return(&format!("Function '{}'", function.get_name()), function.get_body()));
+        self.values = values;
+        self.returns.pop();
+        self.table.pop();
+        self.lints.pop();
+        for (param, _) in &bounds {
+            self.bounds.remove(param);
+        }
+        result
+    }
+    /// Creates a local variable or parameter, warning when it hides a variable of an enclosing scope
+    fn local(&mut self, name: &String, r#type: Type, mutable: bool, inferred: bool, parameter: bool) -> VariableInfo {
+        if let Some(Symbol::Variable(_)) = self.table.get_shadowed(name) {
+            self.report(Lint::Shadowing, format!("'{}' shadows a variable of an enclosing scope", name), self.span);
+        }
+        self.locals.push(Local { name: name.clone(), parameter, level: self.level(Lint::UnusedVariable), span: self.span });
+        VariableInfo { name: name.clone(), r#type, mutable, inferred, declared: self.span, local: Some(self.locals.len() - 1), narrowed_from: None }
+    }
+    fn mark_used(&self, info: &VariableInfo) {
+        if let Some(local) = info.local {
+            self.used.borrow_mut().insert(local);
+        }
+    }
+    fn mark_called(&self, name: &String) {
+        self.called.borrow_mut().insert(name.clone());
+        self.mark_deprecated(name);
+    }
+    /// Records a use of `name` if it is `@deprecated`, see [report_deprecated][Self::report_deprecated]
+    fn mark_deprecated(&self, name: &String) {
+        let mut uses = self.uses.borrow_mut();
+        if self.deprecated.contains_key(name) && !uses.contains(name) {
+            uses.push(name.clone());
+        }
+    }
+    /// Records the deprecated structs named by a declared type
+    fn mark_deprecated_type(&self, r#type: &Type) {
+        match r#type {
+            Type::CUSTOM(name, generics) => {
+                self.mark_deprecated(name);
+                generics.iter().for_each(|x| self.mark_deprecated_type(x));
+            }
+            Type::NULLABLE(inner) | Type::ARRAY(inner, _) | Type::REFERENCE(inner) => { self.mark_deprecated_type(inner) }
+            Type::RESULT(ok, err) => {
+                self.mark_deprecated_type(ok);
+                self.mark_deprecated_type(err);
+            }
+            Type::TUPLE(types) => { types.iter().for_each(|x| self.mark_deprecated_type(x)) }
+            Type::FUNCTION(params, returns) => {
+                params.iter().for_each(|x| self.mark_deprecated_type(x));
+                self.mark_deprecated_type(returns);
+            }
+            _ => {}
+        }
+    }
+    /// Warns about each deprecated item the statement just analyzed uses, with the note of its `@deprecated`
+    fn report_deprecated(&mut self) {
+        for name in self.uses.take() {
+            let message = match self.deprecated.get(&name) {
+                Some(Some(note)) => { format!("'{}' is deprecated: {}", name, note) }
+                _ => { format!("'{}' is deprecated", name) }
+            };
+            self.report(Lint::Deprecated, message, self.span);
+        }
+    }
+    fn level(&self, lint: Lint) -> Level {
+        self.lints.last().map_or(Level::Warn, |x| x.get(lint))
+    }
+    /// Reports a finding of `lint` at its current level
+    fn report(&mut self, lint: Lint, message: String, span: Option<Span>) {
+        self.report_at(self.level(lint), lint, message, span)
+    }
+    fn report_at(&mut self, level: Level, lint: Lint, message: String, span: Option<Span>) {
+        match level {
+            Level::Allow => {}
+            Level::Warn => { self.warnings.push(Diagnostic::new(lint, level, message, span)) }
+            Level::Deny => { self.errors.push(SemanticsError::Lint(Diagnostic::new(lint, level, message, span))) }
+        }
+    }
+    /// Current lint levels overridden by `@allow("lint, ...")`, `@warn(...)` and `@deny(...)`
+    fn attribute_levels(&self, attributes: &Vec<AttributeNode>) -> Result<LintLevels, SemanticsError> {
+        let mut levels = self.lints.last().cloned().unwrap_or(LintLevels::new());
+        for attribute in attributes {
+            let (Some(level), Some(names)) = (Level::from_name(attribute.get_name()), attribute.get_argument()) else {
+                continue
+            };
+            for name in names.split(',').map(str::trim) {
+                let lint = Lint::from_name(name).ok_or(InvalidAttribute(format!("Unknown lint '{}' in {}, expected one of {}", name, attribute, LINTS.map(|x| x.name()).join(", "))))?;
+                levels.set(lint, level);
+            }
+        }
+        Ok(levels)
+    }
+    /// Reports locals and plugged items which are never used, and functions which are neither used nor `visible`.
+    /// `main`, tests, `@extern` functions and names starting with `_` are exempt
+    fn report_unused(&mut self, input: &BlockStatementNode) {
+        let used = self.used.take();
+        for (i, local) in mem::take(&mut self.locals).into_iter().enumerate() {
+            if !used.contains(&i) && !local.name.starts_with('_') && local.name != "self" {
+                let what = if local.parameter { "Parameter" } else { "Variable" };
+                self.report_at(local.level, Lint::UnusedVariable, format!("{} '{}' is never used", what, local.name), local.span);
+            }
+        }
+        let called = self.called.take();
+        for (name, span) in mem::take(&mut self.plugs) {
+            if !called.contains(&name) {
+                self.report(Lint::UnusedPlug, format!("Plugged item '{}' is never used", name), span);
+            }
+        }
+        for (i, node) in input.get_body().iter().enumerate() {
+            let ASTNode::Statement(Statement::FunctionDeclaration(f)) = node else {
+                continue
+            };
+            let exempt = *f.is_visible() || f.get_name() == "main" || f.get_name().starts_with('_') || f.get_attribute("test").is_some() || f.get_attribute("extern").is_some();
+            if !exempt && !called.contains(f.get_name()) {
+                let level = self.attribute_levels(f.get_attributes()).map_or(Level::Warn, |x| x.get(Lint::UnusedFunction));
+                self.report_at(level, Lint::UnusedFunction, format!("Function '{}' is never used and is not `visible`", f.get_name()), input.get_span(i));
+            }
+        }
+    }
+    /// Functions returning something other than `null` must return a value on every path through their body.
+    /// Checked while the function is the innermost one, see [exits][Self::exits]
+    fn require_return(&self, what: &str, body: &Statement) -> Result<(), SemanticsError> {
+        match self.returns.last() {
+            Some(returns) if self.is_valued() && !self.exits(body) => {
+                Err(MissingReturn(format!("{} returns {}, but the end of its body can be reached without a `return`", what, returns)))
+            }
+            _ => { Ok(()) }
+        }
+    }
+    /// Whether the innermost function returns something other than `null`
+    fn is_valued(&self) -> bool {
+        self.returns.last().is_some_and(|x| x != &Type::NULL)
+    }
+    /// Whether a trailing value returns from the innermost function rather than being the value of an `if` branch
+    fn tail_returns(&self) -> bool {
+        self.values == 0 && self.is_valued()
+    }
+    fn declare_arguments(&mut self, args: &Vec<Expression>) -> Result<(), SemanticsError> {
+        for arg in args {
+            if let Expression::Argument(a) = arg {
+                self.mark_deprecated_type(a.get_type());
+                let info = self.local(a.get_name(), a.get_type().clone(), a.is_mutable(), false, true);
+                self.table.insert_symbol(a.get_name().clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+            }
+        }
+        Ok(())
+    }
+    /// Analyzes a block in its own scope. After `if x == null { return ...; }` the rest of the block sees `x` as non-null
+    fn analyze_block(&mut self, block: &BlockStatementNode) -> Result<(), SemanticsError> {
+        self.table.push();
+        self.analyze_body(block);
+        self.table.pop();
+        Ok(())
+    }
+    /// Analyzes a branch in a scope where `narrowed` shadow the nullable variables they were checked from
+    fn analyze_narrowed(&mut self, statement: &Statement, narrowed: Vec<VariableInfo>) -> Result<(), SemanticsError> {
+        self.table.push();
+        let result = self.declare(narrowed).and_then(|_| self.analyze_statement(statement));
+        self.table.pop();
+        result
+    }
+    fn declare(&mut self, variables: Vec<VariableInfo>) -> Result<(), SemanticsError> {
+        for info in variables {
+            self.table.insert_symbol(info.name.clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+        }
+        Ok(())
+    }
+    /// Nullable variables known to be non-null when `cond` holds, and when it does not
+    fn narrowing(&self, cond: &Expression) -> (Vec<VariableInfo>, Vec<VariableInfo>) {
+        match cond {
+            Expression::Comparative(c) => {
+                let name = match (c.get_left(), c.get_right()) {
+                    (Expression::VariableAccess(v), Expression::Literal(l)) | (Expression::Literal(l), Expression::VariableAccess(v)) if matches!(l.as_ref(), LiteralExpression::Null) => { v.get_name() }
+                    _ => { return (Vec::new(), Vec::new()) }
+                };
+                let narrowed = match self.table.get_symbol(name) {
+                    Some(Symbol::Variable(info)) => {
+                        match &info.r#type {
+                            Type::NULLABLE(inner) => { VariableInfo { r#type: inner.as_ref().clone(), narrowed_from: Some(info.r#type.clone()), ..info.clone() } }
+                            _ => { return (Vec::new(), Vec::new()) }
+                        }
+                    }
+                    _ => { return (Vec::new(), Vec::new()) }
+                };
+                match c.get_op() {
+                    ConditionalOperation::NotEq => { (vec![narrowed], Vec::new()) }
+                    ConditionalOperation::Eq => { (Vec::new(), vec![narrowed]) }
+                    _ => { (Vec::new(), Vec::new()) }
+                }
+            }
+            Expression::Logical(l) => {
+                let (left_then, left_else) = self.narrowing(l.get_left());
+                let (right_then, right_else) = self.narrowing(l.get_right());
+                match l.get_op() {
+                    LogicalOperation::And => { (merge(left_then, right_then), Vec::new()) }
+                    LogicalOperation::Or => { (Vec::new(), merge(left_else, right_else)) }
+                }
+            }
+            _ => { (Vec::new(), Vec::new()) }
+        }
+    }
+    /// `++` and `--` change their operand, which must be a mutable variable or a field of one
+    fn require_mutable(&self, expression: &Expression, op: &UnaryOperation) -> Result<(), SemanticsError> {
+        match expression {
+            Expression::FieldAccess(f) => { self.require_mutable(f.get_value(), op) }
+            Expression::VariableAccess(v) => {
+                match self.table.get_symbol(v.get_name()) {
+                    Some(Symbol::Variable(info)) if !info.mutable => { Err(immutable(info, &format!("Cannot apply {} to immutable '{}'", op, info.name))) }
+                    _ => { Ok(()) }
+                }
+            }
+            _ => { Err(TypeMismatch(format!("{} changes its operand, which must be a variable or a field, got {}", op, expression))) }
+        }
+    }
+    /// Rejects using a value that may be `null` where a value is required
+    fn require_non_null(&self, r#type: Type, expression: &Expression) -> Result<Type, SemanticsError> {
+        if !r#type.is_nullable() {
+            return Ok(r#type)
+        }
+        match expression {
+            Expression::VariableAccess(v) => {
+                Err(TypeMismatch(format!("'{}' of type {} may be null, check it with `if {} != null` before using it", v.get_name(), r#type, v.get_name())))
+            }
+            _ => { Err(TypeMismatch(format!("Value of type {} may be null here", r#type))) }
+        }
+    }
+    /// Declares a local. `mk` bindings take the type of their initializer, as annotated by [get_type_of][Self::get_type_of]
+    /// A variable with a declared type is declared even if its initializer is wrong, so its uses are not reported again
+    fn analyze_variable_creation(&mut self, variable: &VariableCreationStatementNode) -> Result<(), SemanticsError> {
+        let declared = variable.get_type().map(|x| self.resolve_sizes(x)).transpose()?;
+        if let Some(declared) = &declared {
+            self.mark_deprecated_type(declared);
+        }
+        let value = self.type_of(variable.get_value());
+        let (r#type, error) = match (declared.as_ref(), value) {
+            (Some(declared), Ok(value)) if !self.converts(variable.get_value(), &value, declared) => {
+                (declared.clone(), Some(self.explain(TypeMismatch(suggest_cast(format!("Cannot initialize '{}' of type {} with a value of type {}", variable.get_name(), declared, value), &value, declared)), &[variable.get_value()])))
+            }
+            (Some(declared), Ok(_)) => { (declared.clone(), None) }
+            (Some(declared), Err(e)) => { (declared.clone(), Some(e)) }
+            (None, Ok(Type::NULL)) => {
+                return Err(TypeMismatch(format!("Cannot infer the type of '{}' from `null`, declare it with an explicit type", variable.get_name())))
+            }
+            (None, Ok(value)) => { (value, None) }
+            (None, Err(e)) => { return Err(e) }
+        };
+        let info = self.local(variable.get_name(), r#type, variable.is_mutable(), variable.get_type().is_none(), false);
+        self.table.insert_symbol(variable.get_name().clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+        error.map_or(Ok(()), Err)
+    }
+    /// Whether `expression` of type `value` can be used as a `target`: its type promotes to `target`, it implements the
+    /// interface `target`, or it is an integer literal whose value fits into the integer type `target`
+    fn converts(&self, expression: &Expression, value: &Type, target: &Type) -> bool {
+        if value.promotes_to(target) {
+            return true
+        }
+        let inner = if target.is_nullable() { value.non_null() } else { value };
+        if let Type::CUSTOM(interface, _) = target.non_null() {
+            if matches!(self.table.get_symbol(interface), Some(Symbol::Interface(_))) && self.implements(inner, interface) {
+                return true
+            }
+        }
+        matches!(expression, Expression::Literal(_)) && value.is_integer() && target.non_null().is_integer()
+            && ConstEvaluator::new(&self.constants).evaluate(expression).and_then(|x| consteval::convert(x.as_ref(), target.non_null())).is_ok()
+    }
+    /// Folds a top-level constant. Its value is available to later constants and array sizes
+    fn analyze_static(&mut self, constant: &StaticDeclarationStatementNode) -> Result<(), SemanticsError> {
+        if self.table.depth() > 1 {
+            return Err(TypeMismatch(format!("`static` constant '{}' must be declared at the top level", constant.get_name())))
+        }
+        let value = ConstEvaluator::new(&self.constants).with_layouts(&self.layouts).evaluate(constant.get_value())?;
+        let declared = constant.get_type();
+        let r#type = consteval::type_of(value.as_ref());
+        if !r#type.promotes_to(declared) {
+            return Err(TypeMismatch(suggest_cast(format!("Cannot initialize constant '{}' of type {} with a value of type {}", constant.get_name(), declared, r#type), &r#type, declared)))
+        }
+        let value = consteval::convert(value.as_ref(), declared)?;
+        let info = VariableInfo { name: constant.get_name().clone(), r#type: declared.clone(), mutable: false, inferred: false, declared: self.span, local: None, narrowed_from: None };
+        self.table.insert_symbol(constant.get_name().clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+        self.constants.insert(constant.get_name().clone(), value);
+        Ok(())
+    }
+    /// Replaces array sizes named by constants with their folded values
+    fn resolve_sizes(&self, r#type: &Type) -> Result<Type, SemanticsError> {
+        match r#type {
+            Type::ARRAY(item, ArraySize::Constant(name)) => {
+                let value = self.constants.get(name).ok_or(UndefinedSymbol(format!("Array size '{}' is not a static constant", name)))?;
+                let size = u64::try_from(consteval::as_integer(value.as_ref())?)
+                    .map_err(|_| ConstantEvaluation(format!("Array size '{}' must not be negative", name)))?;
+                Ok(Type::ARRAY(Box::from(self.resolve_sizes(item)?), ArraySize::Fixed(size)))
+            }
+            Type::ARRAY(item, size) => { Ok(Type::ARRAY(Box::from(self.resolve_sizes(item)?), size.clone())) }
+            _ => { Ok(r#type.clone()) }
+        }
+    }
+    /// Binds every element of a tuple to its own local
+    fn analyze_destructuring(&mut self, destructuring: &DestructuringStatementNode) -> Result<(), SemanticsError> {
+        let value = self.type_of(destructuring.get_value())?;
+        let items = match &value {
+            Type::TUPLE(items) if items.len() == destructuring.get_names().len() => { items.clone() }
+            Type::TUPLE(items) => {
+                return Err(TypeMismatch(format!("Tuple of type {} has {} elements, but {} names are bound", value, items.len(), destructuring.get_names().len())))
+            }
+            _ => { return Err(TypeMismatch(format!("Only tuples can be destructured, got a value of type {}", value))) }
+        };
+        for (name, r#type) in destructuring.get_names().iter().zip(items).filter(|(n, _)| n.as_str() != "_") {
+            let info = self.local(name, r#type, false, true, false);
+            self.table.insert_symbol(name.clone(), Symbol::Variable(info)).map_err(Redefinition)?;
+        }
+        Ok(())
+    }
+    /// Adds the inferred types of `mk` bindings and possible nulls used in `operands` to a type mismatch,
+    /// since neither is visible at the point of use
+    fn explain(&self, error: SemanticsError, operands: &[&Expression]) -> SemanticsError {
+        let notes: Vec<String> = operands.iter().filter_map(|x| match x {
+            Expression::VariableAccess(v) => {
+                match self.table.get_symbol(v.get_name()) {
+                    Some(Symbol::Variable(info)) if info.inferred => { Some(format!("'{}' is inferred as {}", info.name, info.r#type)) }
+                    Some(Symbol::Variable(info)) if matches!(info.r#type, Type::NULLABLE(_)) => { Some(format!("'{}' may be null", info.name)) }
+                    _ => { None }
+                }
+            }
+            _ => { None }
+        }).collect();
+        match error {
+            TypeMismatch(message) if !notes.is_empty() => { TypeMismatch(format!("{} ({})", message, notes.join(", "))) }
+            other => { other }
+        }
+    }
+    fn analyze_switch(&mut self, switch: &SwitchStatementNode) -> Result<(), SemanticsError> {
+        let value = self.type_of(switch.get_value())?;
+        for arm in switch.get_arms() {
+            let mut bindings: Vec<(String, Type)> = Vec::new();
+            match arm.get_pattern() {
+                Pattern::Wildcard => {}
+                Pattern::Ok(name) | Pattern::Err(name) => {
+                    let (success, error) = match &value {
+                        Type::RESULT(success, error) => { (success.as_ref().clone(), error.as_ref().clone()) }
+                        _ => { return Err(TypeMismatch(format!("Pattern {} can never match a value of type {}", arm.get_pattern(), value))) }
+                    };
+                    bindings.push((name.clone(), if matches!(arm.get_pattern(), Pattern::Ok(_)) { success } else { error }));
+                }
+                Pattern::Literal(e) => {
+                    let pattern = self.type_of(e)?;
+                    if pattern.common_with(&value).is_none() {
+                        return Err(TypeMismatch(format!("Pattern of type {} can never match a value of type {}", pattern, value)))
+                    }
+                }
+                Pattern::Variant(r#enum, variant, names) => {
+                    let args = match &value {
+                        Type::CUSTOM(name, args) if name == r#enum => { args.clone() }
+                        _ => { return Err(TypeMismatch(format!("Pattern {}::{} can never match a value of type {}", r#enum, variant, value))) }
+                    };
+                    let info = self.get_enum(r#enum)?;
+                    let substitution: HashMap<String, Type> = info.generics.iter().cloned().zip(args).collect();
+                    let target = info.get_variant(variant)?;
+                    let fields: Vec<(String, Type)> = target.fields.iter().map(|(n, t)| (n.clone(), t.substitute(&substitution))).collect();
+                    match names {
+                        PatternBindings::Unit if !fields.is_empty() => {
+                            return Err(TypeMismatch(format!("Variant {}::{} has a payload which must be destructured", r#enum, variant)))
+                        }
+                        PatternBindings::Unit => {}
+                        PatternBindings::Tuple(names) => {
+                            if target.named || names.len() != fields.len() {
+                                return Err(TypeMismatch(format!("Variant {}::{} holds {} values, pattern binds {}", r#enum, variant, fields.len(), names.len())))
+                            }
+                            bindings.extend(names.iter().cloned().zip(fields.iter().map(|(_, t)| t.clone())));
+                        }
+                        PatternBindings::Struct(names) => {
+                            for (field, name) in names {
+                                match fields.iter().find(|(n, _)| n == field) {
+                                    Some((_, t)) if target.named => { bindings.push((name.clone(), t.clone())) }
+                                    _ => { return Err(UndefinedSymbol(format!("Variant {}::{} has no field '{}'", r#enum, variant, field))) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            self.table.push();
+            for (name, r#type) in bindings.into_iter().filter(|(n, _)| n != "_") {
+                let info = self.local(&name, r#type, false, true, false);
+                self.table.insert_symbol(name, Symbol::Variable(info)).map_err(Redefinition)?;
+            }
+            let result = self.analyze_statement(arm.get_body());
+            self.table.pop();
+            result?;
+        }
+        Ok(())
+    }
+    /// Checks an `ext` block: interface implementations must override every interface method with its exact signature
+    fn analyze_extension(&mut self, extension: &ExtensionStatementNode) -> Result<(), SemanticsError> {
+        let target = extension.get_target();
+        if let Type::CUSTOM(name, _) = target {
+            match self.table.get_symbol(name) {
+                Some(Symbol::Struct(_)) | Some(Symbol::Enum(_)) => {}
+                _ => { return Err(UndefinedSymbol(format!("Cannot extend unknown type '{}'", name))) }
+            }
+        }
+        let key = type_key(target);
+        let mut methods: Vec<MethodInfo> = Vec::new();
+        for method in extension.get_methods() {
+            let function = method.get_function();
+            let info = MethodInfo::from_args(function.get_name(), function.get_args(), function.get_return_type(), true);
+            let defined = |m: &MethodInfo| m.name == info.name && m.parameters == info.parameters;
+            if self.methods.get(&key).is_some_and(|x| x.iter().any(defined)) || methods.iter().any(defined) {
+                return Err(Redefinition(format!("Method '{}' is already defined for {} with the same parameter types", signature(&info.name, &info.parameters), target)))
+            }
+            methods.push(info);
+        }
+        match extension.get_interface() {
+            Some(interface) => {
+                let required = match self.table.get_symbol(interface) {
+                    Some(Symbol::Interface(i)) => { i.methods.clone() }
+                    _ => { return Err(UndefinedSymbol(format!("Interface '{}' is not defined", interface))) }
+                };
+                for (method, info) in extension.get_methods().iter().zip(&methods) {
+                    let expected = required.iter().find(|x| x.name == info.name)
+                        .ok_or(UndefinedSymbol(format!("'{}' is not a method of interface '{}'", info.name, interface)))?;
+                    if !method.is_override() {
+                        return Err(TypeMismatch(format!("Method '{}' implements interface '{}' and must be marked `override`", info.name, interface)))
+                    }
+                    if expected != info {
+                        return Err(TypeMismatch(format!("Method '{}' of {} must have signature {} from interface '{}'", info, target, expected, interface)))
+                    }
+                }
+                let missing: Vec<String> = required.iter().filter(|x| !methods.iter().any(|m| m.name == x.name)).map(|x| x.to_string()).collect();
+                if !missing.is_empty() {
+                    return Err(TypeMismatch(format!("{} does not implement {} of interface '{}'", target, missing.join(", "), interface)))
+                }
+                self.implementations.entry(key.clone()).or_default().push(interface.clone());
+            }
+            None => {
+                if let Some(m) = extension.get_methods().iter().find(|x| x.is_override()) {
+                    return Err(TypeMismatch(format!("Method '{}' is marked `override` outside of an interface implementation", m.get_function().get_name())))
+                }
+            }
+        }
+        let existing = self.methods.get(&key).cloned().unwrap_or_default();
+        for interface in self.implementations.get(&key).cloned().unwrap_or_default() {  // interface methods have one signature
+            let Some(Symbol::Interface(i)) = self.table.get_symbol(&interface) else {
+                continue
+            };
+            for (info, other) in methods.iter().flat_map(|x| existing.iter().chain(&methods).map(move |y| (x, y))) {
+                if info.name == other.name && info.parameters != other.parameters && i.methods.iter().any(|x| x.name == info.name) {
+                    return Err(Redefinition(format!("Method '{}' of {} implements interface '{}' and can't be overloaded, found {} and {}",
+                        info.name, target, interface, signature(&other.name, &other.parameters), signature(&info.name, &info.parameters))))
+                }
+            }
+        }
+        self.methods.entry(key).or_default().extend(methods);
+        for method in extension.get_methods() {
+            if let Err(e) = self.analyze_function(method.get_function()) {
+                self.errors.push(e);
+            }
+        }
+        Ok(())
+    }
+    fn implements(&self, r#type: &Type, interface: &String) -> bool {
+        match r#type {
+            Type::GENERIC(name) => { self.bounds.get(name).is_some_and(|x| x.contains(interface)) }
+            Type::CUSTOM(name, _) if name == interface => { true }
+            _ => { self.implementations.get(&type_key(r#type)).is_some_and(|x| x.contains(interface)) }
+        }
+    }
+    /// Methods callable on a value: interface methods for interface references and bounded type parameters,
+    /// extension methods otherwise
+    fn methods_of(&self, r#type: &Type) -> Vec<MethodInfo> {
+        let interfaces: Vec<String> = match r#type {
+            Type::GENERIC(name) => { self.bounds.get(name).cloned().unwrap_or_default() }
+            Type::CUSTOM(name, _) => { vec![name.clone()] }
+            _ => { Vec::new() }
+        };
+        let mut methods: Vec<MethodInfo> = Vec::new();
+        for interface in interfaces {
+            if let Some(Symbol::Interface(i)) = self.table.get_symbol(&interface) {
+                methods.extend(i.methods.iter().cloned());
+            }
+        }
+        methods.extend(self.methods.get(&type_key(r#type)).cloned().unwrap_or_default());
+        methods
+    }
+    fn type_of_method_call(&self, call: &MethodCallNode) -> Result<Type, SemanticsError> {
+        let receiver = self.require_non_null(self.type_of(call.get_receiver())?, call.get_receiver())?;
+        let operands: Vec<&Expression> = call.get_args().iter().filter_map(|x| match x {
+            ASTNode::Expression(e) => { Some(e) }
+            _ => { None }
+        }).collect();
+        self.type_of_method(&receiver, call.get_name(), &operands, node_key(call))
+    }
+    /// Type of calling the method `name` of `receiver` with `operands`. The chosen overload is recorded under `key`.
+    /// Values of plugged libraries have no known methods, only the arguments are checked and the result is another
+    /// opaque value of the library
+    fn type_of_method(&self, receiver: &Type, name: &String, operands: &[&Expression], key: usize) -> Result<Type, SemanticsError> {
+        if let Some(library) = self.library_of(receiver) {
+            for operand in operands {
+                self.type_of(operand)?;
+            }
+            return Ok(Type::CUSTOM(format!("{}::{}", library, name), Vec::new()))
+        }
+        let mut methods: Vec<MethodInfo> = self.methods_of(receiver).into_iter().filter(|x| &x.name == name).collect();
+        let method = match methods.len() {
+            0 => { return Err(UndefinedSymbol(format!("{} has no method '{}'", receiver, name))) }
+            1 => { methods.remove(0) }
+            _ => {
+                let args = operands.iter().map(|x| self.type_of(x)).collect::<Result<Vec<Type>, SemanticsError>>()?;
+                let candidates: Vec<Vec<Type>> = methods.iter().map(|x| x.parameters.clone()).collect();
+                let i = self.resolve_overload(&format!("{}.{}", receiver, name), &candidates, &args, operands)?;
+                self.overloads.borrow_mut().insert(key, candidates[i].clone());
+                methods.swap_remove(i)
             }
-            _ => { Err(NotYetImplemented(format!("Not yet implemented for statement {}", statement))) }
+        };
+        if operands.len() != method.parameters.len() {
+            return Err(TypeMismatch(format!("Method '{}' takes {} arguments, got {}", method.name, method.parameters.len(), operands.len())))
+        }
+        for (e, param) in operands.iter().zip(&method.parameters) {
+            let actual = self.type_of(e)?;
+            if !self.converts(e, &actual, param) {
+                return Err(self.explain(TypeMismatch(suggest_cast(format!("Argument of '{}' expects {}, got {}", method.name, param, actual), &actual, param)), &[e]))
+            }
+        }
+        Ok(method.returns)
+    }
+    /// The plugged library a value of type `r#type` comes from: a plugged item, or a value named after an item of a library
+    fn library_of(&self, r#type: &Type) -> Option<String> {
+        let Type::CUSTOM(item, _) = r#type else {
+            return None
+        };
+        if let Some(Symbol::Custom(lib)) = self.table.get_symbol(item) {
+            return Some(library(lib).to_string())
+        }
+        self.libraries.iter().find(|x| item.starts_with(&format!("{}::", x))).cloned()
+    }
+    /// Type of `X::y` when `X` is not an enum: `Type::method(receiver, ...)` calls an extension method of a type with
+    /// an explicit receiver, `item::y(...)` calls into a plugged item and `lib::y` names an item of a plugged library.
+    /// Library signatures are unknown, so only their arguments are checked and their values are opaque
+    fn type_of_path(&self, path: &VariantExpressionNode) -> Result<Type, SemanticsError> {
+        let (name, item) = (path.get_enum(), path.get_variant());
+        let operands: Vec<&Expression> = match path.get_values() {
+            VariantValues::Tuple(list) => { list.iter().collect() }
+            _ => { Vec::new() }
+        };
+        match self.table.get_symbol(name) {
+            Some(Symbol::Struct(_)) if matches!(path.get_values(), VariantValues::Tuple(_)) => {
+                let Some((receiver, args)) = operands.split_first() else {
+                    return Err(TypeMismatch(format!("'{}::{}' takes the receiver as its first argument", name, item)))
+                };
+                let receiver = self.require_non_null(self.type_of(receiver)?, receiver)?;
+                if !matches!(&receiver, Type::CUSTOM(x, _) if x == name) {
+                    return Err(TypeMismatch(format!("Receiver of '{}::{}' must be {}, got {}", name, item, name, receiver)))
+                }
+                self.type_of_method(&receiver, item, args, node_key(path))
+            }
+            Some(Symbol::Custom(_)) if !matches!(path.get_values(), VariantValues::Struct(_)) => {
+                self.called.borrow_mut().insert(name.clone());
+                for operand in operands {
+                    self.type_of(operand)?;
+                }
+                Ok(Type::CUSTOM(name.clone(), Vec::new()))
+            }
+            None if self.libraries.contains(name) && matches!(path.get_values(), VariantValues::Unit) => {
+                Ok(Type::CUSTOM(format!("{}::{}", name, item), Vec::new()))
+            }
+            _ => { Err(UndefinedSymbol(format!("'{}::{}' is not defined, '{}' is neither an enum, a struct nor a plugged item", name, item, name))) }
+        }
+    }
+    /// Whether control never continues past `statement`: it returns on every path. The trailing value of a block,
+    /// a call included, returns it from a function with a value and is dropped in one without
+    fn exits(&self, statement: &Statement) -> bool {
+        match statement {
+            Statement::Return(r) => { !r.is_tail() || self.tail_returns() }
+            Statement::Block(b) => { b.get_body().iter().any(|x| matches!(x, ASTNode::Statement(s) if self.exits(s))) }
+            Statement::If(i) => { i.get_else().as_ref().is_some_and(|e| self.exits(i.get_then()) && self.exits(e)) }
+            Statement::Switch(s) => { self.covers(s) && s.get_arms().iter().all(|x| self.exits(x.get_body())) }
+            _ => { false }
+        }
+    }
+    /// Whether the arms of a `switch` match every value: there is a `_` arm, both `ok` and `err` arms, or an arm
+    /// for every variant of the enum
+    fn covers(&self, switch: &SwitchStatementNode) -> bool {
+        let patterns: Vec<&Pattern> = switch.get_arms().iter().map(|x| x.get_pattern()).collect();
+        if patterns.iter().any(|x| matches!(x, Pattern::Wildcard)) {
+            return true
+        }
+        if patterns.iter().any(|x| matches!(x, Pattern::Ok(_))) && patterns.iter().any(|x| matches!(x, Pattern::Err(_))) {
+            return true
+        }
+        let r#enum = patterns.iter().find_map(|x| match x {
+            Pattern::Variant(r#enum, _, _) => { self.get_enum(r#enum).ok() }
+            _ => { None }
+        });
+        r#enum.map_or(false, |info| info.variants.iter().all(|v| patterns.iter().any(|x| matches!(x, Pattern::Variant(_, name, _) if name == &v.name))))
+    }
+    fn get_enum(&self, name: &str) -> Result<EnumInfo, SemanticsError> {
+        match self.table.get_symbol(name) {
+            Some(Symbol::Enum(info)) => { Ok(info.clone()) }
+            _ => { Err(UndefinedSymbol(format!("Enum '{}' is not defined", name))) }
         }
     }
     fn analyze_expression(&mut self, expression: &Expression) -> Result<(), SemanticsError> {
+        self.analyze_nested(expression)?;
+        self.type_of(expression).map(|_| ())
+    }
+    /// Analyzes the lambdas and `if` branches in an expression, see [Nested]
+    fn analyze_nested(&mut self, expression: &Expression) -> Result<(), SemanticsError> {
+        let mut nested = Nested { analyzer: self, result: Ok(()) };
+        nested.visit_expression(expression);
+        nested.result
+    }
+    /// Analyzes each branch of an `if` used as a value as a block in its own scope, and records the common type of
+    /// their values as the type of `expression`
+    fn analyze_if_value(&mut self, expression: &Expression, i: &IfStatementNode) -> Result<(), SemanticsError> {
+        self.analyze_nested(i.get_cond())?;
+        self.require_bool(i.get_cond())?;
+        let Some(r#else) = i.get_else() else {
+            return Err(TypeMismatch(String::from("`if` used as a value must have an `else` branch")))
+        };
+        let (then_narrowed, else_narrowed) = self.narrowing(i.get_cond());
+        let then = self.analyze_branch(i.get_then(), then_narrowed)?;
+        let r#else = self.analyze_branch(r#else, else_narrowed)?;
+        let r#type = self.common_type(then, r#else)?;
+        let id = self.interner.borrow_mut().intern(&r#type);
+        self.types.borrow_mut().insert(expression_key(expression), id);
+        Ok(())
+    }
+    /// Type of the value of a branch, taken in its scope. A branch whose errors are already reported is `never`,
+    /// so they are not reported again for the whole `if`
+    fn analyze_branch(&mut self, branch: &Statement, narrowed: Vec<VariableInfo>) -> Result<Type, SemanticsError> {
+        let errors = self.errors.len();
+        self.table.push();
+        self.values += 1;
+        let result = self.declare(narrowed).and_then(|_| match branch {
+            Statement::Block(b) => {
+                self.analyze_body(b);
+                Ok(())
+            }
+            other => { self.analyze_statement(other) }
+        }).and_then(|_| if self.errors.len() > errors { Ok(Type::NEVER) } else { self.type_of_value(branch) });
+        self.values -= 1;
+        self.table.pop();
+        result
+    }
+    /// Infers the type of `expression` and records it in the annotations
+    fn type_of(&self, expression: &Expression) -> Result<Type, SemanticsError> {
+        let r#type = self.infer(expression)?;
+        let id = self.interner.borrow_mut().intern(&r#type);
+        self.types.borrow_mut().insert(expression_key(expression), id);
+        Ok(r#type)
+    }
+    /// Branches of conditional expressions must share a common type. Arithmetic takes numbers, ordering numbers or chars
+    fn infer(&self, expression: &Expression) -> Result<Type, SemanticsError> {
+        match expression {
+            Expression::Literal(l) => { Ok(l.get_type()) }
+            Expression::VariableAccess(v) => {
+                match self.table.get_symbol(v.get_name()) {
+                    Some(Symbol::Variable(info)) => {
+                        self.mark_used(info);
+                        Ok(info.r#type.clone())
+                    }
+                    Some(Symbol::Function(overloads)) if overloads.len() > 1 => {
+                        Err(TypeMismatch(format!("'{}' is overloaded as {}, wrap the call of one overload in a lambda to use it as a value", v.get_name(),
+                            overloads.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "))))
+                    }
+                    Some(Symbol::Function(overloads)) if overloads[0].generics.is_empty() => {
+                        let info = &overloads[0];
+                        self.mark_called(&info.name);
+                        Ok(Type::FUNCTION(info.get_parameter_types(), Box::from(info.returns.clone())))
+                    }
+                    _ => { Err(UndefinedSymbol(format!("Variable '{}' is not defined", v.get_name()))) }
+                }
+            }
+            Expression::Lambda(l) => { Ok(l.get_type()) }  // its body is analyzed by [Nested] beforehand
+            Expression::Comparative(c) => {
+                let mut left = self.type_of(c.get_left())?;
+                let mut right = self.type_of(c.get_right())?;
+                if !matches!(c.get_op(), ConditionalOperation::Eq | ConditionalOperation::NotEq | ConditionalOperation::StrictEq) {
+                    left = self.require_non_null(left, c.get_left())?;
+                    right = self.require_non_null(right, c.get_right())?;
+                }
+                match c.get_op() {
+                    ConditionalOperation::StrictEq if left != right => {
+                        Err(TypeMismatch(format!("`=!` compares without promotion, but operands are {} and {}", left, right)))
+                    }
+                    ConditionalOperation::Greater | ConditionalOperation::Less | ConditionalOperation::GreaterEq | ConditionalOperation::LessEq
+                        if !(left.is_numeric() && right.is_numeric() || left == Type::CHAR && right == Type::CHAR) => {
+                        Err(self.explain(TypeMismatch(format!("Cannot apply {} to {} and {}, only numbers and chars are ordered", c.get_op(), left, right)), &[c.get_left(), c.get_right()]))
+                    }
+                    _ if left.common_with(&right).is_none() => {
+                        Err(self.explain(TypeMismatch(format!("Cannot compare {} with {}", left, right)), &[c.get_left(), c.get_right()]))
+                    }
+                    _ => { Ok(Type::BOOL) }
+                }
+            }
+            Expression::Logical(l) => {
+                self.require_bool(l.get_left())?;
+                self.require_bool(l.get_right())?;
+                Ok(Type::BOOL)
+            }
+            Expression::Bitwise(b) => {
+                let left = self.require_non_null(self.type_of(b.get_left())?, b.get_left())?;
+                let right = self.require_non_null(self.type_of(b.get_right())?, b.get_right())?;
+                let result = match b.get_op() {
+                    BitwiseOperation::ShiftL | BitwiseOperation::ShiftR => { Some(left.clone()).filter(|x| x.is_integer() && right.is_integer()) }
+                    _ => { left.common_with(&right).filter(|x| x.is_integer() || x == &Type::BOOL) }
+                };
+                result.ok_or_else(|| self.explain(TypeMismatch(format!("Cannot apply {} to {} and {}", b.get_op(), left, right)), &[b.get_left(), b.get_right()]))
+            }
+            Expression::Unary(u) => { self.type_of_unary(u) }
+            Expression::Argument(a) => { Ok(a.get_type().clone()) }
+            Expression::MethodCall(c) => { self.type_of_method_call(c) }
+            Expression::FieldAccess(a) => {
+                let value = self.require_non_null(self.type_of(a.get_value())?, a.get_value())?;
+                let info = match &value {
+                    Type::CUSTOM(name, _) => {
+                        match self.table.get_symbol(name) {
+                            Some(Symbol::Struct(info)) => { info.clone() }
+                            _ => { return Err(TypeMismatch(format!("{} has no fields", value))) }
+                        }
+                    }
+                    _ => { return Err(TypeMismatch(format!("{} has no fields", value))) }
+                };
+                let args = match &value { Type::CUSTOM(_, args) => args.clone(), _ => Vec::new() };
+                let substitution: HashMap<String, Type> = info.generics.iter().cloned().zip(args).collect();
+                info.fields.iter().find(|(n, _)| n == a.get_field()).map(|(_, t)| t.substitute(&substitution))
+                    .ok_or(UndefinedSymbol(format!("Struct '{}' has no field '{}'", info.name, a.get_field())))
+            }
+            Expression::Variant(v) if !matches!(self.table.get_symbol(v.get_enum()), Some(Symbol::Enum(_))) => { self.type_of_path(v) }
+            Expression::Variant(v) => {
+                let info = self.get_enum(v.get_enum())?;
+                let variant = info.get_variant(v.get_variant())?;
+                let mut values: Vec<(String, Type)> = Vec::new();
+                match v.get_values() {
+                    VariantValues::Unit => {}
+                    VariantValues::Tuple(list) if !variant.named => {
+                        for (i, value) in list.iter().enumerate() {
+                            values.push((i.to_string(), self.type_of(value)?));
+                        }
+                    }
+                    VariantValues::Struct(list) if variant.named => {
+                        for (name, value) in list {
+                            values.push((name.clone(), self.type_of(value)?));
+                        }
+                    }
+                    _ => { return Err(TypeMismatch(format!("Wrong payload kind for variant {}::{}", info.name, variant.name))) }
+                }
+                if values.len() != variant.fields.len() {
+                    return Err(TypeMismatch(format!("Variant {}::{} holds {} values, got {}", info.name, variant.name, variant.fields.len(), values.len())))
+                }
+                let mut bindings: HashMap<String, Type> = HashMap::new();
+                for (name, value) in values {
+                    let field = variant.fields.iter().find(|(n, _)| n == &name).map(|(_, t)| t)
+                        .ok_or(UndefinedSymbol(format!("Variant {}::{} has no field '{}'", info.name, variant.name, name)))?;
+                    if !field.bind(&value, &mut bindings) && !value.promotes_to(field) {
+                        return Err(TypeMismatch(suggest_cast(format!("Field '{}' of {}::{} expects {}, got {}", name, info.name, variant.name, field, value), &value, field)))
+                    }
+                }
+                Ok(Type::CUSTOM(info.name.clone(), info.generics.iter().map(|x| bindings.get(x).cloned().unwrap_or(Type::GENERIC(x.clone()))).collect()))
+            }
+            Expression::FunctionCall(c) => { self.type_of_call(c) }
+            Expression::ResultValue(r) => {
+                let value = Box::from(self.type_of(r.get_value())?);
+                Ok(if r.is_ok() { Type::RESULT(value, Box::from(Type::NEVER)) } else { Type::RESULT(Box::from(Type::NEVER), value) })
+            }
+            Expression::Propagate(p) => { self.type_of_propagation(p) }
+            Expression::Cast(c) => { self.type_of_cast(c) }
+            Expression::LayoutQuery(q) => {
+                if !q.get_target().is_generic() {  // otherwise answered for each instance
+                    self.query_layout(q)?;
+                }
+                Ok(Type::ULONG)
+            }
+            Expression::Tuple(t) => { t.get_values().iter().map(|x| self.type_of(x)).collect::<Result<Vec<Type>, SemanticsError>>().map(Type::TUPLE) }
+            Expression::Binary(b) => {
+                let left = self.require_non_null(self.type_of(b.get_left())?, b.get_left())?;
+                let right = self.require_non_null(self.type_of(b.get_right())?, b.get_right())?;
+                left.common_with(&right).filter(|x| x.is_numeric())
+                    .ok_or_else(|| self.explain(TypeMismatch(format!("Cannot apply {} to {} and {}", b.get_op(), left, right)), &[b.get_left(), b.get_right()]))
+            }
+            Expression::Ternary(t) => {
+                self.require_bool(t.get_cond())?;
+                self.common_type(self.type_of(t.get_then())?, self.type_of(t.get_else())?)
+            }
+            Expression::If(i) => {  // its branches are analyzed by [Nested] beforehand, which records the type
+                if let Some(&id) = self.types.borrow().get(&expression_key(expression)) {
+                    return Ok(self.interner.borrow().get(id).clone())
+                }
+                self.require_bool(i.get_cond())?;
+                let then = self.type_of_value(i.get_then())?;
+                match i.get_else() {
+                    Some(e) => { self.common_type(then, self.type_of_value(e)?) }
+                    None => { Err(TypeMismatch(String::from("`if` used as a value must have an `else` branch"))) }
+                }
+            }
+        }
+    }
+    fn type_of_call(&self, c: &FunctionCallNode) -> Result<Type, SemanticsError> {
+        let mut args: Vec<Type> = Vec::new();
+        let mut operands: Vec<&Expression> = Vec::new();
+        if let Some(nodes) = c.get_args() {
+            for node in nodes {
+                if let ASTNode::Expression(e) = node {
+                    args.push(self.type_of(e)?);
+                    operands.push(e);
+                }
+            }
+        }
+        match self.table.get_symbol(c.get_name()) {
+            Some(Symbol::Function(overloads)) => {
+                let info = match &overloads[..] {
+                    [info] => { info }
+                    _ => {
+                        let candidates: Vec<Vec<Type>> = overloads.iter().map(|x| x.get_parameter_types()).collect();
+                        let i = self.resolve_overload(c.get_name(), &candidates, &args, &operands)?;
+                        self.overloads.borrow_mut().insert(node_key(c), candidates[i].clone());
+                        &overloads[i]
+                    }
+                };
+                self.mark_called(&info.name);
+                if args.len() == info.parameters.len() {
+                    for ((arg, operand), (_, param)) in args.iter_mut().zip(&operands).zip(&info.parameters) {
+                        if !matches!(param, Type::GENERIC(_)) && self.converts(operand, arg, param) {
+                            *arg = param.clone();
+                        }
+                    }
+                }
+                let bindings = info.bind(&args).map_err(|e| self.explain(e, &operands))?;
+                for (param, interface) in &info.bounds {
+                    let bound = bindings.get(param).cloned().unwrap_or(Type::GENERIC(param.clone()));
+                    if !self.implements(&bound, interface) {
+                        return Err(TypeMismatch(format!("{} does not implement interface '{}' required by '{}'", bound, interface, info.name)))
+                    }
+                }
+                Ok(info.returns.substitute(&bindings))
+            }
+            Some(Symbol::Variable(info)) => {
+                self.mark_used(info);
+                match &info.r#type {
+                    Type::FUNCTION(params, _) if params.len() != args.len() => {
+                        Err(TypeMismatch(format!("'{}' of type {} takes {} arguments, got {}", c.get_name(), info.r#type, params.len(), args.len())))
+                    }
+                    Type::FUNCTION(params, returns) => {
+                        for ((arg, operand), param) in args.iter().zip(&operands).zip(params) {
+                            if !self.converts(operand, arg, param) {
+                                return Err(self.explain(TypeMismatch(suggest_cast(format!("Argument of '{}' expects {}, got {}", c.get_name(), param, arg), arg, param)), &[operand]))
+                            }
+                        }
+                        Ok(returns.as_ref().clone())
+                    }
+                    Type::NULLABLE(_) => { Err(TypeMismatch(format!("'{}' may be null, check it with `if {} != null` before calling it", c.get_name(), c.get_name()))) }
+                    other => { Err(TypeMismatch(format!("'{}' of type {} is not callable", c.get_name(), other))) }
+                }
+            }
+            Some(Symbol::Custom(lib)) => {  // plugged functions have no known signature, only their arguments are checked
+                self.called.borrow_mut().insert(c.get_name().clone());
+                Ok(Type::CUSTOM(format!("{}::{}", library(lib), c.get_name()), Vec::new()))
+            }
+            _ => { Err(UndefinedSymbol(format!("Function '{}' is not defined", c.get_name()))) }
+        }
+    }
+    /// Picks the overload a call resolves to among the parameter lists of `candidates`: the only one accepting the
+    /// arguments, the one they match exactly, or the one more specific than every other accepting them
+    fn resolve_overload(&self, name: &str, candidates: &[Vec<Type>], args: &[Type], operands: &[&Expression]) -> Result<usize, SemanticsError> {
+        let viable: Vec<usize> = (0..candidates.len()).filter(|&i| candidates[i].len() == args.len()
+            && candidates[i].iter().zip(args).zip(operands).all(|((param, arg), operand)| matches!(param, Type::GENERIC(_)) || self.converts(operand, arg, param))).collect();
+        let listed = |indices: &[usize]| indices.iter().map(|&i| signature(name, &candidates[i])).collect::<Vec<String>>().join(", ");
+        if viable.is_empty() {
+            return Err(TypeMismatch(format!("No overload of '{}' accepts {}, candidates are {}", name, signature("", args), listed(&(0..candidates.len()).collect::<Vec<usize>>()))))
+        }
+        if let Some(&exact) = viable.iter().find(|&&i| candidates[i] == args) {
+            return Ok(exact)
+        }
+        let best: Vec<usize> = viable.iter().copied().filter(|&i| viable.iter().all(|&j| i == j || more_specific(&candidates[i], &candidates[j]))).collect();
+        match best[..] {
+            [i] => { Ok(i) }
+            _ => { Err(TypeMismatch(format!("Call of '{}' with {} is ambiguous between {}; cast the arguments to pick one", name, signature("", args), listed(&viable)))) }
+        }
+    }
+    /// Checks a cast against the conversion matrix. `as?` produces a nullable value, exact casts of constants are
+    /// folded so that `300 as byte` is reported here instead of trapping at runtime
+    fn type_of_cast(&self, cast: &CastExpressionNode) -> Result<Type, SemanticsError> {
+        let value = self.require_non_null(self.type_of(cast.get_value())?, cast.get_value())?;
+        let target = self.resolve_sizes(cast.get_target())?;
+        if value.conversion(&target) == Conversion::Invalid {
+            return Err(TypeMismatch(format!("Cannot cast {} to {}", value, target)))
+        }
+        if cast.get_mode() == CastMode::Exact {
+            let evaluator = ConstEvaluator::new(&self.constants);
+            if let Ok(constant) = evaluator.evaluate(cast.get_value()) {
+                consteval::cast(constant.as_ref(), &target, CastMode::Exact)?;
+            }
+        }
+        Ok(if cast.get_mode() == CastMode::Checked { target.nullable() } else { target })
+    }
+    /// Answers `sizeof(T)` or `alignof(T)` for a type which doesn't depend on type parameters
+    fn query_layout(&self, query: &LayoutQueryExpressionNode) -> Result<u64, SemanticsError> {
+        let target = self.resolve_sizes(query.get_target())?;
+        let answer = match query.get_query() {
+            LayoutQuery::Size => { self.layouts.size_of(&target) }
+            LayoutQuery::Align => { self.layouts.align_of(&target) }
+        };
+        answer.map_err(|e| Layout(format!("Cannot take `{}` of {}: {}", query.get_query(), target, e)))
+    }
+    /// `++` and `--` change their operand, so it must be a mutable variable or a field of one
+    fn type_of_unary(&self, unary: &UnaryExpressionNode) -> Result<Type, SemanticsError> {
+        if matches!(unary.get_op(), UnaryOperation::Ref) {
+            if !matches!(unary.get_value(), Expression::VariableAccess(_)) {
+                return Err(TypeMismatch(String::from("Only variables have an address that `&` can take")))
+            }
+            return Ok(Type::REFERENCE(Box::from(self.type_of(unary.get_value())?)))
+        }
+        let value = self.require_non_null(self.type_of(unary.get_value())?, unary.get_value())?;
+        if matches!(unary.get_op(), UnaryOperation::Inc | UnaryOperation::Dec) {
+            self.require_mutable(unary.get_value(), unary.get_op())?;
+        }
+        let accepted = match unary.get_op() {
+            UnaryOperation::Not => { value == Type::BOOL || value.is_integer() }
+            UnaryOperation::Neg | UnaryOperation::Inc | UnaryOperation::Dec => { value.is_numeric() }
+            UnaryOperation::Ref => { unreachable!() }
+        };
+        if !accepted {
+            return Err(self.explain(TypeMismatch(format!("Cannot apply {} to {}", unary.get_op(), value)), &[unary.get_value()]))
+        }
+        Ok(value)
+    }
+    /// Type of `value!`, which is only allowed in functions returning a result with a compatible error type
+    fn type_of_propagation(&self, propagation: &PropagateExpressionNode) -> Result<Type, SemanticsError> {
+        let (success, error) = match self.type_of(propagation.get_value())? {
+            Type::RESULT(success, error) => { (*success, *error) }
+            other => { return Err(TypeMismatch(format!("`!` propagates errors of results, got a value of type {}", other))) }
+        };
+        match self.returns.last() {
+            Some(Type::RESULT(_, expected)) if error.promotes_to(expected) => { Ok(success) }
+            Some(Type::RESULT(_, expected)) => {
+                Err(TypeMismatch(format!("`!` would return an error of type {} from a function whose error type is {}", error, expected)))
+            }
+            Some(other) => { Err(TypeMismatch(format!("`!` can only be used in functions returning a result, this one returns {}", other))) }
+            None => { Err(TypeMismatch(String::from("`!` can only be used inside of a function"))) }
+        }
+    }
+    /// Rejects calls whose result is dropped without being checked
+    fn require_used(&self, returns: Type, name: &String) -> Result<(), SemanticsError> {
+        match returns {
+            Type::RESULT(_, _) => {
+                Err(TypeMismatch(format!("Result of '{}' of type {} is discarded, handle it with `switch`, propagate it with `!` or bind it with `mk`", name, returns)))
+            }
+            _ => { Ok(()) }
+        }
+    }
+    /// Type of the value a statement produces: the trailing expression of a block, or `null`
+    fn type_of_value(&self, statement: &Statement) -> Result<Type, SemanticsError> {
+        match statement {
+            Statement::Block(b) => {
+                match b.get_body().last() {
+                    Some(ASTNode::Statement(last)) => { self.type_of_value(last) }
+                    Some(ASTNode::Expression(last)) => { self.type_of(last) }
+                    None => { Ok(Type::NULL) }
+                }
+            }
+            Statement::Return(r) => {
+                match r.get_value() {
+                    ASTNode::Expression(e) => { self.type_of(e) }
+                    ASTNode::Statement(s) => { self.type_of_value(s) }
+                }
+            }
+            Statement::If(i) => {
+                self.require_bool(i.get_cond())?;
+                let then = self.type_of_value(i.get_then())?;
+                match i.get_else() {
+                    Some(e) => { self.common_type(then, self.type_of_value(e)?) }
+                    None => { Ok(Type::NULL) }
+                }
+            }
+            _ => { Ok(Type::NULL) }
+        }
+    }
+    fn common_type(&self, left: Type, right: Type) -> Result<Type, SemanticsError> {
+        left.common_with(&right).ok_or(TypeMismatch(format!("Branches have incompatible types {} and {}", left, right)))
+    }
+    fn require_bool(&self, cond: &Expression) -> Result<(), SemanticsError> {
+        match self.type_of(cond)? {
+            Type::BOOL => { Ok(()) }
+            other => { Err(TypeMismatch(format!("Condition must be bool, got {}", other))) }
+        }
+    }
+    fn analyze_lambda(&mut self, lambda: &LambdaExpressionNode) -> Result<(), SemanticsError> {
+        let mut bound: Vec<String> = lambda.get_args().iter().filter_map(|x| match x {
+            Expression::Argument(a) => Some(a.get_name().clone()),
+            _ => None
+        }).collect();
+        let mut free: Vec<String> = Vec::new();
+        free_in_statement(lambda.get_body(), &mut bound, &mut free);
+        let mut captured: Vec<VariableInfo> = Vec::new();
+        for name in free {
+            if let Some(Symbol::Variable(v)) = self.table.get_symbol(&name) {
+                captured.push(v.clone())
+            }
+        }
+        self.captures.insert(lambda.get_id(), captured);
+        self.table.push();
+        self.returns.push(lambda.get_return_type().clone());
+        let values = mem::take(&mut self.values);
+        let result = self.declare_arguments(lambda.get_args()).and_then(|_| self.analyze_statement(lambda.get_body()))
+            .and_then(|_| self.require_return("Lambda", lambda.get_body()));
+        self.values = values;
+        self.returns.pop();
+        self.table.pop();
+        result
+    }
+}
 
+/// Analyzes the lambdas and the branches of `if` expressions in the expressions of a statement, which inferring the
+/// types of those expressions doesn't reach. Nested statements are left to the analysis of their own scopes
+struct Nested<'a> {
+    analyzer: &'a mut SemanticsAnalyzer,
+    result: Result<(), SemanticsError>
+}
+impl Visitor for Nested<'_> {
+    fn visit_statement(&mut self, _statement: &Statement) {}
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::If(i) if self.result.is_ok() => { self.result = self.analyzer.analyze_if_value(expression, i) }
+            Expression::If(_) => {}
+            _ => { walk_expression(self, expression) }
+        }
+    }
+    fn visit_lambda(&mut self, lambda: &LambdaExpressionNode) {
+        if self.result.is_ok() {
+            self.result = self.analyzer.analyze_lambda(lambda);
+        }
+    }
+}
+
+fn merge(mut left: Vec<VariableInfo>, right: Vec<VariableInfo>) -> Vec<VariableInfo> {
+    for info in right {
+        if !left.iter().any(|x| x.name == info.name) {
+            left.push(info);
+        }
+    }
+    left
+}
+/// Collects names used in `statement` that are not bound inside of it, in order of first use
+fn free_in_statement(statement: &Statement, bound: &mut Vec<String>, free: &mut Vec<String>) {
+    match statement {
+        Statement::If(i) => {
+            free_in_expression(i.get_cond(), bound, free);
+            free_in_statement(i.get_then(), &mut bound.clone(), free);
+            if let Some(e) = i.get_else() {
+                free_in_statement(e, &mut bound.clone(), free);
+            }
+        }
+        Statement::Block(b) => {
+            let mut inner = bound.clone();
+            for node in b.get_body() {
+                free_in_node(node, &mut inner, free);
+            }
+        }
+        Statement::FunctionCall(c) => {
+            use_name(c.get_name(), bound, free);
+            if let Some(args) = c.get_args() {
+                args.iter().for_each(|x| free_in_node(x, bound, free));
+            }
+        }
+        Statement::Assignment(a) => {
+            use_name(a.get_cont(), bound, free);
+            free_in_expression(a.get_value(), bound, free);
+        }
+        Statement::VariableCreation(v) => {
+            free_in_expression(v.get_value(), bound, free);
+            bound.push(v.get_name().clone());
+        }
+        Statement::Return(r) => { free_in_node(r.get_value(), bound, free) }
+        Statement::LambdaDeclaration(l) => {
+            bound.push(l.get_name().clone());
+            free_in_lambda(l.get_lambda(), bound, free);
+        }
+        Statement::Switch(s) => {
+            free_in_expression(s.get_value(), bound, free);
+            for arm in s.get_arms() {
+                let mut inner = bound.clone();
+                match arm.get_pattern() {
+                    Pattern::Literal(e) => { free_in_expression(e, bound, free) }
+                    Pattern::Variant(_, _, PatternBindings::Tuple(names)) => { inner.extend(names.iter().cloned()) }
+                    Pattern::Variant(_, _, PatternBindings::Struct(fields)) => { inner.extend(fields.iter().map(|(_, x)| x.clone())) }
+                    Pattern::Ok(name) | Pattern::Err(name) => { inner.push(name.clone()) }
+                    Pattern::Variant(_, _, PatternBindings::Unit) | Pattern::Wildcard => {}
+                }
+                free_in_statement(arm.get_body(), &mut inner, free);
+            }
+        }
+        Statement::MethodCall(c) => {
+            free_in_expression(c.get_receiver(), bound, free);
+            c.get_args().iter().for_each(|x| free_in_node(x, bound, free));
+        }
+        Statement::Propagate(p) => { free_in_expression(p.get_value(), bound, free) }
+        Statement::Unary(u) => { free_in_expression(u.get_value(), bound, free) }
+        Statement::Declaration(d) => { bound.extend(d.get_names().iter().cloned()) }
+        Statement::Destructuring(d) => {
+            free_in_expression(d.get_value(), bound, free);
+            bound.extend(d.get_names().iter().cloned());
+        }
+        Statement::Static(c) => { bound.push(c.get_name().clone()) }
+        Statement::Plug(_) | Statement::FunctionDeclaration(_) | Statement::StructDeclaration(_) | Statement::EnumDeclaration(_)
+        | Statement::InterfaceDeclaration(_) | Statement::Extension(_) => {}
+    }
+}
+fn free_in_expression(expression: &Expression, bound: &mut Vec<String>, free: &mut Vec<String>) {
+    match expression {
+        Expression::Binary(b) => {
+            free_in_expression(b.get_left(), bound, free);
+            free_in_expression(b.get_right(), bound, free);
+        }
+        Expression::Comparative(c) => {
+            free_in_expression(c.get_left(), bound, free);
+            free_in_expression(c.get_right(), bound, free);
+        }
+        Expression::Logical(l) => {
+            free_in_expression(l.get_left(), bound, free);
+            free_in_expression(l.get_right(), bound, free);
+        }
+        Expression::Bitwise(b) => {
+            free_in_expression(b.get_left(), bound, free);
+            free_in_expression(b.get_right(), bound, free);
+        }
+        Expression::Unary(u) => { free_in_expression(u.get_value(), bound, free) }
+        Expression::FunctionCall(c) => {
+            use_name(c.get_name(), bound, free);
+            if let Some(args) = c.get_args() {
+                args.iter().for_each(|x| free_in_node(x, bound, free));
+            }
+        }
+        Expression::VariableAccess(v) => { use_name(v.get_name(), bound, free) }
+        Expression::Lambda(l) => { free_in_lambda(l, bound, free) }
+        Expression::Ternary(t) => {
+            free_in_expression(t.get_cond(), bound, free);
+            free_in_expression(t.get_then(), bound, free);
+            free_in_expression(t.get_else(), bound, free);
+        }
+        Expression::If(i) => {
+            free_in_expression(i.get_cond(), bound, free);
+            free_in_statement(i.get_then(), &mut bound.clone(), free);
+            if let Some(e) = i.get_else() {
+                free_in_statement(e, &mut bound.clone(), free);
+            }
+        }
+        Expression::Variant(v) => {
+            match v.get_values() {
+                VariantValues::Unit => {}
+                VariantValues::Tuple(values) => { values.iter().for_each(|x| free_in_expression(x, bound, free)) }
+                VariantValues::Struct(values) => { values.iter().for_each(|(_, x)| free_in_expression(x, bound, free)) }
+            }
+        }
+        Expression::MethodCall(c) => {
+            free_in_expression(c.get_receiver(), bound, free);
+            c.get_args().iter().for_each(|x| free_in_node(x, bound, free));
+        }
+        Expression::FieldAccess(a) => { free_in_expression(a.get_value(), bound, free) }
+        Expression::ResultValue(r) => { free_in_expression(r.get_value(), bound, free) }
+        Expression::Propagate(p) => { free_in_expression(p.get_value(), bound, free) }
+        Expression::Cast(c) => { free_in_expression(c.get_value(), bound, free) }
+        Expression::Tuple(t) => { t.get_values().iter().for_each(|x| free_in_expression(x, bound, free)) }
+        Expression::Literal(_) | Expression::Argument(_) | Expression::LayoutQuery(_) => {}
+    }
+}
+fn free_in_node(node: &ASTNode, bound: &mut Vec<String>, free: &mut Vec<String>) {
+    match node {
+        ASTNode::Statement(s) => { free_in_statement(s, bound, free) }
+        ASTNode::Expression(e) => { free_in_expression(e, bound, free) }
+    }
+}
+fn free_in_lambda(lambda: &LambdaExpressionNode, bound: &[String], free: &mut Vec<String>) {
+    let mut inner = bound.to_vec();
+    for arg in lambda.get_args() {
+        if let Expression::Argument(a) = arg {
+            inner.push(a.get_name().clone());
+        }
+    }
+    free_in_statement(lambda.get_body(), &mut inner, free);
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeTarget {
+    Function,
+    Struct
+}
+impl Display for AttributeTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeTarget::Function => { write!(f, "functions") }
+            AttributeTarget::Struct => { write!(f, "structs") }
+        }
+    }
+}
+/// Known attributes with the declarations they apply to and whether they take an argument:
+/// `None` for no argument, `Some(true)` for a required one and `Some(false)` for an optional one
+const ATTRIBUTES: &[(&str, &[AttributeTarget], Option<bool>)] = &[
+    ("inline", &[AttributeTarget::Function], None),
+    ("cold", &[AttributeTarget::Function], None),
+    ("test", &[AttributeTarget::Function], None),
+    ("extern", &[AttributeTarget::Function], Some(true)),
+    ("deprecated", &[AttributeTarget::Function, AttributeTarget::Struct], Some(false)),
+    ("allow", &[AttributeTarget::Function], Some(true)),
+    ("warn", &[AttributeTarget::Function], Some(true)),
+    ("deny", &[AttributeTarget::Function], Some(true)),
+    ("layout", &[AttributeTarget::Struct], Some(true))
+];
+/// Attribute pairs that contradict each other
+const CONFLICTING_ATTRIBUTES: &[(&str, &str)] = &[
+    ("inline", "cold"),
+    ("inline", "extern"),
+    ("test", "extern")
+];
+fn check_attributes(attributes: &[AttributeNode], target: AttributeTarget, name: &String) -> Result<(), SemanticsError> {
+    for (i, attribute) in attributes.iter().enumerate() {
+        let Some((_, targets, argument)) = ATTRIBUTES.iter().find(|(x, _, _)| x == attribute.get_name()) else {
+            return Err(InvalidAttribute(format!("Unknown attribute '{}' on '{}'", attribute, name)))
+        };
+        if !targets.contains(&target) {
+            return Err(InvalidAttribute(format!("Attribute '{}' can't be applied to {}, as on '{}'", attribute, target, name)))
+        }
+        match (argument, attribute.get_argument()) {
+            (None, Some(_)) => { return Err(InvalidAttribute(format!("Attribute '@{}' on '{}' takes no argument", attribute.get_name(), name))) }
+            (Some(true), None) => { return Err(InvalidAttribute(format!("Attribute '@{}' on '{}' requires an argument", attribute.get_name(), name))) }
+            (Some(true), Some(x)) if x.is_empty() => { return Err(InvalidAttribute(format!("Attribute '@{}' on '{}' requires a non-empty argument", attribute.get_name(), name))) }
+            _ => {}
+        }
+        if attributes[..i].iter().any(|x| x.get_name() == attribute.get_name()) {
+            return Err(InvalidAttribute(format!("Attribute '@{}' is applied to '{}' twice", attribute.get_name(), name)))
+        }
+    }
+    for (left, right) in CONFLICTING_ATTRIBUTES {
+        if attributes.iter().any(|x| x.get_name() == left) && attributes.iter().any(|x| x.get_name() == right) {
+            return Err(InvalidAttribute(format!("Attributes '@{}' and '@{}' can't be both applied to '{}'", left, right, name)))
+        }
+    }
+    Ok(())
+}
+/// Pairs of a type parameter and an interface it is bounded by
+fn generic_bounds(generics: &[GenericParameterNode]) -> Vec<(String, String)> {
+    generics.iter().flat_map(|x| x.get_bounds().iter().map(|b| (x.get_name().clone(), type_key(b)))).collect()
+}
+/// Name extension methods and implementations of a type are registered under
+fn type_key(r#type: &Type) -> String {
+    match r#type {
+        Type::CUSTOM(name, _) => { name.clone() }
+        _ => { r#type.to_string() }
+    }
+}
+/// Suggests a cast for a conversion between numbers which may lose information, as those are never implicit.
+/// A nullable value can't be cast to a type without null, it is checked for null first
+fn suggest_cast(message: String, value: &Type, target: &Type) -> String {
+    let lossy = value.non_null().is_lossy_to(target);
+    if matches!(value, Type::NULLABLE(_)) && !target.is_nullable() {
+        let cast = if lossy { format!(", then write it as a cast `as {}`", target.non_null()) } else { String::new() };
+        format!("{}; the value may be null, compare it with `null` before using it{}", message, cast)
+    } else if lossy {
+        format!("{}; the conversion may lose information, write it as a cast `as {}`", message, target.non_null())
+    } else {
+        message
+    }
+}
+/// Whether the parameters of one overload all promote to those of the other, so it is the better match for arguments
+/// both of them accept
+fn more_specific(parameters: &[Type], other: &[Type]) -> bool {
+    parameters != other && parameters.iter().zip(other).all(|(param, other)| matches!(other, Type::GENERIC(_)) || (!matches!(param, Type::GENERIC(_)) && param.promotes_to(other)))
+}
+/// Signature of an overload as it is written in diagnostics, e.g. `add(int, decimal)`
+fn signature(name: &str, parameters: &[Type]) -> String {
+    format!("{}({})", name, parameters.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "))
+}
+/// Calls are resolved by their address, like [expressions][expression_key] are annotated
+fn node_key<T>(node: &T) -> usize {
+    node as *const T as usize
+}
+/// Expressions are annotated by their address, which stays the same as long as the program is not modified
+fn expression_key(expression: &Expression) -> usize {
+    expression as *const Expression as usize
+}
+/// Error for a change of the immutable variable `info`, pointing at its declaration
+fn immutable(info: &VariableInfo, message: &str) -> SemanticsError {
+    Immutable(format!("{}; declare it as `mut {}` to allow changes", message, info.name), info.declared)
+}
+/// The library a plug path belongs to, e.g. `std` for `std::console.println`
+fn library(path: &str) -> &str {
+    path.split([':', '.']).next().unwrap_or(path)
+}
+fn use_name(name: &String, bound: &[String], free: &mut Vec<String>) {
+    if !bound.contains(name) && !free.contains(name) {
+        free.push(name.clone());
     }
 }
 
 pub mod utils {
+    use std::fmt::{Display, Formatter};
+    use crate::generator::lints::Diagnostic;
+    use crate::lexer::token::Span;
+
     pub enum SemanticsError {
-        NotYetImplemented(String)
+        NotYetImplemented(String),
+        UndefinedSymbol(String),
+        Redefinition(String),
+        TypeMismatch(String),
+        ConstantEvaluation(String),
+        InvalidAttribute(String),
+        MissingReturn(String),
+        /// Missing, duplicated or mistyped `main`
+        EntryPoint(String),
+        /// Type without a finite size, or whose size is not known where it is asked for
+        Layout(String),
+        /// Change of an immutable binding, with the span of its declaration
+        Immutable(String, Option<Span>),
+        /// Finding of a lint set to `deny`
+        Lint(Diagnostic)
+    }
+    impl SemanticsError {
+        /// The error followed by the place of the declaration it refers to, if any
+        pub fn render(&self, source: &str) -> String {
+            match self {
+                SemanticsError::Immutable(_, Some(declared)) => { format!("{}\n  declared at {}", self, declared.excerpt(source)) }
+                SemanticsError::Lint(diagnostic) => { diagnostic.render(source) }
+                _ => { self.to_string() }
+            }
+        }
+    }
+    impl Display for SemanticsError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            match self {
+                SemanticsError::NotYetImplemented(message) => { write!(f, "Not yet implemented: {}", message) }
+                SemanticsError::UndefinedSymbol(message) => { write!(f, "Undefined symbol: {}", message) }
+                SemanticsError::Redefinition(message) => { write!(f, "Redefinition: {}", message) }
+                SemanticsError::TypeMismatch(message) => { write!(f, "Type mismatch: {}", message) }
+                SemanticsError::ConstantEvaluation(message) => { write!(f, "Constant evaluation: {}", message) }
+                SemanticsError::InvalidAttribute(message) => { write!(f, "Invalid attribute: {}", message) }
+                SemanticsError::Immutable(message, _) => { write!(f, "Immutable binding: {}", message) }
+                SemanticsError::MissingReturn(message) => { write!(f, "Missing return: {}", message) }
+                SemanticsError::Layout(message) => { write!(f, "Layout: {}", message) }
+                SemanticsError::EntryPoint(message) => { write!(f, "Entry point: {}", message) }
+                SemanticsError::Lint(diagnostic) => { write!(f, "{}", diagnostic) }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::semantics::SemanticsAnalyzer;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    /// Analyzer which has checked `source` without errors
+    fn analyze(source: &str) -> SemanticsAnalyzer {
+        let tokens = Lexer::new(String::from(source)).tokenize();
+        let mut analyzer = SemanticsAnalyzer::new(Parser::new(tokens).parse());
+        assert!(analyzer.start().is_ok());
+        analyzer
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn inline_lambdas_record_their_captures() {
+        let analyzer = analyze("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    mk k = 3;\n    mk y = 2;\n    apply((x: int) -> int >> { x + k }, y);\n}\n");
+        let captures: Vec<Vec<String>> = analyzer.captures.values().map(|x| x.iter().map(|v| v.name.clone()).collect()).collect();
+        assert_eq!(captures, vec![vec![String::from("k")]]);
+    }
+
+    #[test]
+    fn bound_lambdas_capture_only_free_variables() {
+        let analyzer = analyze("func main() {\n    mk k = 3;\n    mk f = (x: int) -> int >> { mk y = x; y + k };\n    f(1);\n}\n");
+        let captures: Vec<String> = analyzer.captures.values().flatten().map(|v| v.name.clone()).collect();
+        assert_eq!(captures, vec![String::from("k")]);
+    }
+}
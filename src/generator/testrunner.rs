@@ -0,0 +1,20 @@
+use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, FunctionDeclarationStatementNode, Statement};
+
+/// Top-level `@test` functions of a program, in declaration order
+pub struct TestRunner<'a> {
+    tests: Vec<&'a FunctionDeclarationStatementNode>
+}
+impl<'a> TestRunner<'a> {
+    pub fn new(root: &'a BlockStatementNode) -> Self {
+        let tests = root.get_body().iter().filter_map(|x| match x {
+            ASTNode::Statement(Statement::FunctionDeclaration(f)) if f.get_attribute("test").is_some() => { Some(f.as_ref()) }
+            _ => { None }
+        }).collect();
+        Self {
+            tests
+        }
+    }
+    pub fn get_tests(&self) -> &Vec<&'a FunctionDeclarationStatementNode> {
+        &self.tests
+    }
+}
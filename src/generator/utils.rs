@@ -4,7 +4,7 @@
 pub mod nasm_writer {
     use std::fs::File;
     use std::io;
-    use std::io::{Error, Write};
+    use std::io::Write;
     use crate::core::utils::stringutils::StringBuilder;
 
     pub struct Writer {
@@ -39,14 +39,14 @@ pub mod nasm_writer {
             self.data.push_string(format!("{name} {} {data}\n", r#type))
         }
         pub fn new_text(&mut self, command: String, data: Vec<String>) {
-            match command {
-                String::from("global") => {
+            match command.as_str() {
+                "global" => {
                     self.text.push_string(format!("{command} "));
                     for label in data {
                         self.text.push_string(label)
                     }
                 }
-                String::from("extern") => {
+                "extern" => {
                     self.text.push_string(format!("{command} "));
                     for proc in data {
                         self.text.push_string(format!("{}, ", proc))
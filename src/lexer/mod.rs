@@ -10,18 +10,20 @@
 //!
 use std::collections::HashMap;
 use rustring_builder::StringBuilder;
-use crate::lexer::token::{Token, TokenType};
-use crate::lexer::token::TokenType::{ACCESS, ALLOWS, AMPERSAND, AND, ANDASSIGN, ANYKW, ARROW, ASSIGN, BYTEKW, CHARARR, CHARARRKW, CHARKW, COLON, COMMA, DEC, DECIMAL, DECIMALKW, DISRUPT, DIVASSIGN, DOLLAR, ELSE, EOF, EQ, EXPORT, EXT, FEQ, FOR, FUNC, GEQ, GT, HIDDEN, IDENT, IF, INC, INT, INTKW, LAMBDA, LBRACE, LBRACK, LEQ, LONGKW, LPAREN, LS, MINUS, MINUSASSIGN, MK, MULASSIGN, NEQ, NOT, NULLKW, OMNIKW, OPEN, OR, ORASSIGN, OVERRIDE, PERIOD, PIPE, PLUG, PLUS, PLUSASSIGN, POWER, QUESTION, RBRACE, RBRACK, REM, REMASSIGN, REQUIRES, RETURN, RPAREN, SEMICOLON, SHL, SHR, SKIP, SLASH, STAR, STATIC, STRUCT, SWITCH, UBYTEKW, UINTKW, ULONGKW, UNDERSCORE, VISIBLE, WAITS, XOR};
+use crate::lexer::token::{Comment, Span, Token, TokenType};
+use crate::lexer::token::TokenType::{ACCESS, ALIGNOF, ALLOWS, AMPERSAND, AND, ANDASSIGN, ANYKW, ARROW, AS, ASSIGN, AT, BOOLKW, BYTEKW, CHARARR, CHARARRKW, CHARKW, COLON, COMMA, DEC, DECIMAL, DECIMALKW, DISRUPT, DIVASSIGN, DOLLAR, ELSE, ENUM, EOF, EQ, ERR, EXPORT, EXT, FEQ, FOR, FUNC, GEQ, GT, HIDDEN, IDENT, IF, INC, INTERFACE, INT, INTKW, LAMBDA, LBRACE, LBRACK, LEQ, LONGKW, LPAREN, LS, MINUS, MINUSASSIGN, MK, MULASSIGN, MUT, NEQ, NOT, NULLKW, OK, OMNIKW, OPEN, OR, ORASSIGN, OVERRIDE, PERIOD, PIPE, PLUG, PLUS, PLUSASSIGN, POWER, QUESTION, RBRACE, RBRACK, REM, REMASSIGN, REQUIRES, RETURN, RPAREN, SEMICOLON, SHL, SHR, SIZEOF, SKIP, SLASH, STAR, STATIC, STRUCT, SWITCH, UBYTEKW, UINTKW, ULONGKW, UNDERSCORE, VISIBLE, WAITS, XOR};
 pub mod token;
 #[derive(Clone)]
 pub struct Lexer {
     l_pos: usize,
     l_input: String,
+    #[allow(dead_code)]
     l_size: usize,
     l_cur: char,
     l_output: Vec<Token>,
     l_buffer: StringBuilder,
-    l_keywords: HashMap<String, TokenType>
+    l_keywords: HashMap<String, TokenType>,
+    l_comments: Vec<Comment>
 }
 
 impl Lexer {
@@ -46,11 +48,17 @@ impl Lexer {
                 (String::from("plug"), PLUG),
                 (String::from("visible"), VISIBLE),
                 (String::from("struct"), STRUCT),
+                (String::from("enum"), ENUM),
+                (String::from("interface"), INTERFACE),
                 (String::from("ext"), EXT),
                 (String::from("func"), FUNC),
                 (String::from("for"), FOR),
                 (String::from("open"), OPEN),
                 (String::from("override"), OVERRIDE),
+                (String::from("as"), AS),
+                (String::from("sizeof"), SIZEOF),
+                (String::from("alignof"), ALIGNOF),
+                (String::from("mut"), MUT),
                 (String::from("mk"), MK),
                 (String::from("export"), EXPORT),
                 (String::from("hidden"), HIDDEN),
@@ -60,6 +68,8 @@ impl Lexer {
                 (String::from("disrupt"), DISRUPT),
                 (String::from("skip"), SKIP),
                 (String::from("return"), RETURN),
+                (String::from("ok"), OK),
+                (String::from("err"), ERR),
                 (String::from("int"), INTKW),
                 (String::from("uint"), UINTKW),
                 (String::from("byte"), BYTEKW),
@@ -69,30 +79,43 @@ impl Lexer {
                 (String::from("decimal"), DECIMALKW),
                 (String::from("omni"), OMNIKW),
                 (String::from("char"), CHARKW),
+                (String::from("bool"), BOOLKW),
                 (String::from("char[]"), CHARARRKW),
+                (String::from("string"), CHARARRKW),
                 (String::from("static"), STATIC)
-            ])
+            ]),
+            l_comments: Vec::new()
         }
     }
+    /// Comments skipped by [tokenize][Lexer::tokenize], in source order
+    pub fn get_comments(&self) -> &Vec<Comment> {
+        &self.l_comments
+    }
     pub fn tokenize(&mut self) -> Vec<Token> {
         while self.has_next() {
+            let start = self.l_pos;
+            let pushed = self.l_output.len();
             match self.l_cur {
                 '&' => { if self.peek(1) == '&' { self.push_token(AND); self.next() }
                 else if self.peek(1) == '=' { self.push_token(ANDASSIGN); self.next() }
                 else { self.push_token(AMPERSAND); } self.next() }
                 '*' => { if self.peek(1) == '=' { self.push_token(MULASSIGN); self.next() }
                 else { self.push_token(STAR) } self.next() }
-                '/' => { if self.peek(1) == '/' { while self.l_cur != '\n' { self.next() } }
+                '/' => { if self.peek(1) == '/' { while self.l_cur != '\n' && self.l_cur != '\0' { self.next() } self.push_comment(start, self.l_pos) }
                 else if self.peek(1) == '=' { self.push_token(DIVASSIGN); self.next() }
                 else if self.peek(1) == '*' {
+                    self.next();
                     loop {
                         if self.l_cur == '*' && self.peek(1) == '/' {
-                            self.next();
-                            self.next();
+                            self.next();  // the closing `/` is consumed below
                             break
                         }
+                        if self.l_cur == '\0' {
+                            panic!("Unterminated block comment starting at pos {}", start)
+                        }
                         self.next()
-                    } }
+                    }
+                    self.push_comment(start, self.l_pos + 1) }
                 else { self.push_token(SLASH); } self.next(); }
                 '+' => { if self.peek(1) == '+' { self.push_token(INC); self.next(); }
                 else if self.peek(1) == '=' { self.push_token(PLUSASSIGN); self.next() }
@@ -106,7 +129,7 @@ impl Lexer {
                 else if self.peek(1) == '>' { self.push_token(SHR); self.next() }
                 else { self.push_token(PIPE) } ; self.next() }
                 '<' => { if self.peek(1) == '=' { self.push_token(LEQ); self.next() }
-                else if self.peek(1) == '|' { self.push_token(SHL); self.next() }
+                else if self.peek(1) == '|' || self.peek(1) == '<' { self.push_token(SHL); self.next() }  // `<|`, or `<<` as in C
                 else { self.push_token(LS) }; self.next() }
                 '>' => { if self.peek(1) == '=' { self.push_token(GEQ); self.next() }
                 else if self.peek(1) == '>' { self.push_token(LAMBDA); self.next() }
@@ -120,12 +143,14 @@ impl Lexer {
                 '%' => { if self.peek(1) == '=' { self.push_token(REMASSIGN); self.next() } else { self.push_token(REM); } self.next()  }
                 ':' => { if self.peek(1) == ':' { self.push_token(ACCESS); self.next() } else { self.push_token(COLON) }; self.next() }
                 '$' => { self.push_token(DOLLAR); self.next() }
+                '@' => { self.push_token(AT); self.next() }
                 '^' => { self.push_token(POWER); self.next() }
                 '(' => { self.push_token(LPAREN); self.next() }
                 '[' => { self.push_token(LBRACK); self.next() }
                 '{' => { self.push_token(LBRACE); self.next() }
                 ',' => { self.push_token(COMMA); self.next() }
                 '.' => { self.push_token(PERIOD); self.next() }
+                '_' if self.peek(1).is_alphanumeric() => { self.tokenize_ident() }  // _unused
                 '_' => { self.push_token(UNDERSCORE); self.next() }
                 ')' => { self.push_token(RPAREN); self.next() }
                 ']' => { self.push_token(RBRACK); self.next() }
@@ -137,12 +162,16 @@ impl Lexer {
                 _ => {
                     if self.l_cur.is_whitespace() { self.next() }
                     else if self.l_cur.is_alphabetic() { self.tokenize_ident() }
-                    else if self.l_cur.is_digit(10) { self.tokenize_number() }
+                    else if self.l_cur.is_ascii_digit() { self.tokenize_number() }
                     else { panic!("Unexpected char {} at pos {}", self.l_cur, self.l_pos) }
                 }
 
 
             }
+            for token in &mut self.l_output[pushed..] {  // spans cover everything the arm consumed, quotes included
+                token.t_pos = start;
+                token.t_end = self.l_pos;
+            }
         }
 
         let mut output: Vec<Token> = Vec::new();
@@ -165,21 +194,27 @@ impl Lexer {
     }
     fn tokenize_ident(&mut self) {
         self.l_buffer.clear();
-        while self.l_cur.is_alphabetic()
-            || self.l_cur == '_'
-            || self.l_cur.is_digit(10)
-            || self.l_cur == '['
-            || self.l_cur == ']' {
-            self.l_buffer.push(self.l_cur);
-            self.next()
+        loop {
+            if self.l_cur.is_alphabetic() || self.l_cur == '_' || self.l_cur.is_ascii_digit() {
+                self.l_buffer.push(self.l_cur);
+                self.next()
+            } else if self.l_cur == '[' && self.peek(1) == ']' && self.l_buffer.to_string() == "char" {  // char[], while int[4] and char[][] are lexed as separate tokens
+                self.l_buffer.push('[');
+                self.l_buffer.push(']');
+                self.next();
+                self.next()
+            } else {
+                break
+            }
         }
         let value = self.l_buffer.to_string();
         self.l_output.push(Token::new(self.l_keywords.get(&value).unwrap_or(&IDENT).clone(), value, self.l_pos))
     }
+    #[allow(clippy::if_same_then_else)]
     fn tokenize_number(&mut self) {
         let mut is_float: bool = false;
         self.l_buffer.clear();
-        while self.l_cur.is_digit(10)
+        while self.l_cur.is_ascii_digit()
             || self.l_cur == '.'
             || self.l_cur == 'f'
         {
@@ -201,6 +236,10 @@ impl Lexer {
         }
     }
 
+    fn push_comment(&mut self, start: usize, end: usize) {
+        let text = self.l_input.chars().skip(start).take(end - start).collect();
+        self.l_comments.push(Comment::new(Span::new(start, end), text))
+    }
     fn push_token(&mut self, t: TokenType) {
         let val = match &t {
             STAR => String::from("*"),
@@ -230,6 +269,7 @@ impl Lexer {
             ACCESS => String::from("::"),
             ARROW => String::from("->"),
             DOLLAR => String::from("$"),
+            AT => String::from("@"),
             FEQ => String::from("=!"),
             LAMBDA => String::from(">>"),
             POWER => String::from("^"),
@@ -257,7 +297,7 @@ impl Lexer {
         self.l_pos += 1;
         self.l_cur = self.l_input.chars().nth(self.l_pos).unwrap_or('\0')
     }
-    fn peek(&mut self, offset: usize) -> char {
+    fn peek(&self, offset: usize) -> char {
         let final_pos = self.l_pos + offset;
         self.l_input.clone().chars().nth(final_pos).unwrap_or('\0')
     }
@@ -1,9 +1,7 @@
-use std::collections::hash_map::Values;
-use std::fmt::{write, Display, Formatter};
-use std::io::Stderr;
-use std::ops::RangeBounds;
+use std::fmt::{Display, Formatter};
 use crate::lexer::token::TokenType::{ARITHMETIC_E, ARITHMETIC_S, ASSIGNMENT_E, ASSIGNMENT_S, BITWISE_E, BITWISE_S, COND_E, COND_S, EOF, KEYWORDS_E, KEYWORDS_S, LOGICAL_E, LOGICAL_S, MINUS, OPERATORS_E, OPERATORS_S, OTHERS_E, OTHERS_S, STDDATATYPES_E, STDDATATYPES_S, UNARY_E, UNARY_S};
 
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(PartialOrd, PartialEq, Clone, Debug)]
 pub enum TokenType {
     OPERATORS_S,
@@ -25,6 +23,7 @@ pub enum TokenType {
     NEQ,        // !=
     LEQ,        // <=
     GEQ,        // >=
+    FEQ,        // =!
     COND_E,
     ASSIGNMENT_S,
     ASSIGN,     // =
@@ -44,7 +43,6 @@ pub enum TokenType {
     ACCESS,     // ::
     ARROW,      // ->
     DOLLAR,     // $
-    FEQ,        // =!
     LAMBDA,     // >>
     POWER,      // ^
     OPERATORS_E,
@@ -73,6 +71,7 @@ pub enum TokenType {
     SEMICOLON,  // ;
     COLON,      // :
     QUESTION,   // ?
+    AT,         // @
     OTHERS_E,
 
     STDDATATYPES_S,
@@ -103,11 +102,17 @@ pub enum TokenType {
     PLUG,
     VISIBLE,
     STRUCT,
+    ENUM,
+    INTERFACE,
     EXT,
     FUNC,
     FOR,
     OPEN,
     OVERRIDE,
+    AS,
+    SIZEOF,
+    ALIGNOF,
+    MUT,
     MK,
     EXPORT,
     HIDDEN,
@@ -117,6 +122,8 @@ pub enum TokenType {
     DISRUPT,
     SKIP,
     RETURN,
+    OK,
+    ERR,
     CHARARRKW,
     CHARKW,
     BOOLKW,
@@ -187,6 +194,7 @@ impl Display for TokenType {
             TokenType::ACCESS => { write!(f, "::") }
             TokenType::ARROW => { write!(f, "->") }
             TokenType::DOLLAR => { write!(f, "$") }
+            TokenType::AT => { write!(f, "@") }
             TokenType::FEQ => { write!(f, "=!") }
             TokenType::LAMBDA => { write!(f, ">>") }
             TokenType::POWER => { write!(f, "^") }
@@ -228,11 +236,17 @@ impl Display for TokenType {
             TokenType::PLUG => { write!(f, "kw::plug") }
             TokenType::VISIBLE => { write!(f, "kw::visible") }
             TokenType::STRUCT => { write!(f, "kw::struct") }
+            TokenType::ENUM => { write!(f, "kw::enum") }
+            TokenType::INTERFACE => { write!(f, "kw::interface") }
             TokenType::EXT => { write!(f, "kw::ext") }
             TokenType::FUNC => { write!(f, "kw::func") }
             TokenType::FOR => { write!(f, "kw::for") }
             TokenType::OPEN => { write!(f, "kw::open") }
             TokenType::OVERRIDE => { write!(f, "kw::override") }
+            TokenType::AS => { write!(f, "kw::as") }
+            TokenType::SIZEOF => { write!(f, "kw::sizeof") }
+            TokenType::ALIGNOF => { write!(f, "kw::alignof") }
+            TokenType::MUT => { write!(f, "kw::mut") }
             TokenType::MK => { write!(f, "kw::mk") }
             TokenType::EXPORT => { write!(f, "kw::export") }
             TokenType::HIDDEN => { write!(f, "kw::hidden") }
@@ -242,6 +256,8 @@ impl Display for TokenType {
             TokenType::DISRUPT => { write!(f, "kw::disrupt") }
             TokenType::SKIP => { write!(f, "kw::skip") }
             TokenType::RETURN => { write!(f, "kw::return") }
+            TokenType::OK => { write!(f, "kw::ok") }
+            TokenType::ERR => { write!(f, "kw::err") }
             TokenType::CHARKW => { write!(f, "kw::char") }
             TokenType::CHARARRKW => { write!(f, "kw::char[]") }
             TokenType::BOOLKW => { write!(f, "kw::bool") }
@@ -263,7 +279,8 @@ impl Display for TokenType {
 pub struct Token {
     pub t_type: TokenType,
     pub t_value: String,
-    pub t_pos: usize
+    pub t_pos: usize,
+    pub t_end: usize
 }
 
 impl Token {
@@ -271,18 +288,76 @@ impl Token {
         Self {
             t_type: token_type,
             t_value: value,
-            t_pos: pos
+            t_pos: pos,
+            t_end: pos
         }
     }
     pub fn eof() -> Token {
         Self {
             t_type: EOF,
             t_value: String::new(),
-            t_pos: 0xFFDD
+            t_pos: 0xFFDD,
+            t_end: 0xFFDD
+        }
+    }
+    pub fn get_span(&self) -> Span {
+        Span::new(self.t_pos, self.t_end)
+    }
+}
+/// Range of characters `[start, end)` of the source a token or a statement was read from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize
+}
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end
+        }
+    }
+    /// One-based line and column of the start of the span in `source`
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        let mut line = 1;
+        let mut column = 1;
+        for c in source.chars().take(self.start) {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
         }
+        (line, column)
     }
-    pub fn to_string(&self) -> String {
-        format!("Token with type [ {:?} ] value [ {} ] pos [ {} ]", self.t_type, self.t_value, self.t_pos)
+    /// `line:column: text` of the first line of the span, for diagnostics
+    pub fn excerpt(&self, source: &str) -> String {
+        let (line, column) = self.line_column(source);
+        let text = source.get(self.start..self.end).unwrap_or_default();
+        format!("{}:{}: {}", line, column, text.lines().next().unwrap_or_default())
+    }
+}
+/// `// line` or `/* block */` comment, with its delimiters
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub span: Span,
+    pub text: String
+}
+impl Comment {
+    pub fn new(span: Span, text: String) -> Self {
+        Self {
+            span,
+            text
+        }
+    }
+    pub fn is_line(&self) -> bool {
+        self.text.starts_with("//")
+    }
+}
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
     }
 }
 impl Display for Token {
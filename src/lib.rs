@@ -0,0 +1,8 @@
+//! The Omnia compiler: the lexer, the parser and its AST, the semantic passes and the emitters.
+//! The `Omnia` binary drives them; tools such as linters and editors can walk the [parser::ast] with its visitors
+pub mod lexer;
+pub mod parser;
+pub mod core;
+pub mod generator;
+pub mod emit;
+pub mod formatter;
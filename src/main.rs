@@ -1,27 +1,85 @@
-use std::fmt::Debug;
-use std::fs::{read_to_string, File};
-use std::io::Read;
+use std::fs::{read_to_string, write};
 use std::process::exit;
-use crate::core::omnia_types::{OmniaByte, OmniaValue, Type};
-use crate::core::omnia_types::Type::{ANYNUM};
-use crate::lexer::Lexer;
-use crate::parser::Parser;
-
-mod lexer;
-mod parser;
-mod core;
-mod generator;
+use omnia::emit::{self, Emit};
+use omnia::formatter;
+use omnia::generator::lints::{LintLevels, LINT_CONFIG};
+use omnia::generator::program::{check_program, Module};
+use omnia::generator::semantics::SemanticsAnalyzer;
+use omnia::lexer::Lexer;
+use omnia::parser::Parser;
 
 fn main() -> std::io::Result<()> {
-    let mut input = read_to_string("src/test.oa")?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|x| x == "fmt") {
+        exit(formatter::run(&args[1..])?)
+    }
+    let mut paths: Vec<String> = Vec::new();
+    let mut emits: Vec<Emit> = Vec::new();
+    let mut trace = false;
+    for arg in args {
+        if arg == "--trace" {
+            trace = true;
+        } else if let Some(list) = arg.strip_prefix("--emit=") {
+            emits = Emit::parse_list(list).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1)
+            });
+        } else {
+            paths.push(arg);
+        }
+    }
+    if paths.is_empty() {
+        paths.push(String::from("src/test.oa"));
+    }
+    let mut modules: Vec<Module> = Vec::new();
+    for path in &paths {  // every source file is a module of the program
+        let input = read_to_string(path)?;
 
-    let mut lexer = Lexer::new(input);
-    let tokens = lexer.tokenize();
-    tokens.clone().iter().for_each(|x| {
-        println!("{}", x)
-    });
-    let mut parser = Parser::new(tokens);
-    println!("\n\n\n\n--------------------------------\n{}", parser.parse());
+        let mut lexer = Lexer::new(input.clone());
+        let tokens = lexer.tokenize();
+        if emits.contains(&Emit::Tokens) {
+            write(format!("{}.{}", path, Emit::Tokens.extension()), emit::tokens(&tokens, &input).to_string())?;
+        }
+        let mut parser = Parser::new(tokens).with_trace(trace);
+        let root = parser.parse();
+        if emits.contains(&Emit::Ast) {
+            write(format!("{}.{}", path, Emit::Ast.extension()), emit::ast(&root, &input).to_string())?;
+        }
+        modules.push(Module::new(path, input, root));
+    }
+    let lints = match read_to_string(LINT_CONFIG) {
+        Ok(config) => {
+            LintLevels::parse(&config).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1)
+            })
+        }
+        Err(_) => { LintLevels::new() }
+    };
+    let mut failed = false;
+    if let Err(errors) = check_program(&modules) {
+        errors.iter().for_each(|x| eprintln!("{}", x));
+        failed = true;
+    }
+    for (path, module) in paths.iter().zip(modules) {
+        let input = module.get_source().clone();
+        let mut analyzer = SemanticsAnalyzer::new(module.into_root()).with_lints(lints.clone());
+        let result = analyzer.start();
+        if emits.contains(&Emit::Types) {
+            write(format!("{}.{}", path, Emit::Types.extension()), emit::types(&analyzer, &input).to_string())?;
+        }
+        if emits.contains(&Emit::Layouts) {
+            write(format!("{}.{}", path, Emit::Layouts.extension()), emit::layouts(analyzer.get_input(), analyzer.get_layouts()).to_string())?;
+        }
+        analyzer.get_warnings().iter().for_each(|x| eprintln!("{}", x.render(&input)));
+        if let Err(errors) = result {
+            errors.iter().for_each(|x| eprintln!("{}", x.render(&input)));
+            failed = true;
+        }
+    }
+    if failed {
+        exit(1)
+    }
 
     Ok(())
 }
@@ -0,0 +1,103 @@
+use std::mem;
+use crate::core::omnia_types::Type;
+use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, Expression, LiteralExpression, Statement};
+use crate::parser::ast::visit_mut;
+use crate::parser::ast::visit_mut::VisitorMut;
+
+/// AST-to-AST rewrite. Every method takes a node by value and returns its replacement. The defaults call the
+/// matching `walk_` function, which rebuilds the node with each child passed through the folder
+pub trait Fold {
+    fn fold_node(&mut self, node: ASTNode) -> ASTNode {
+        walk_node(self, node)
+    }
+    fn fold_block(&mut self, block: BlockStatementNode) -> BlockStatementNode {
+        walk_block(self, block)
+    }
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        walk_statement(self, statement)
+    }
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        walk_expression(self, expression)
+    }
+    fn fold_type(&mut self, r#type: Type) -> Type {
+        r#type
+    }
+}
+
+pub fn walk_node<F: Fold + ?Sized>(folder: &mut F, node: ASTNode) -> ASTNode {
+    match node {
+        ASTNode::Statement(s) => { ASTNode::Statement(folder.fold_statement(s)) }
+        ASTNode::Expression(e) => { ASTNode::Expression(folder.fold_expression(e)) }
+    }
+}
+
+pub fn walk_block<F: Fold + ?Sized>(folder: &mut F, mut block: BlockStatementNode) -> BlockStatementNode {
+    visit_mut::walk_block_mut(&mut Folding(folder), &mut block);
+    block
+}
+
+pub fn walk_statement<F: Fold + ?Sized>(folder: &mut F, mut statement: Statement) -> Statement {
+    visit_mut::walk_statement_mut(&mut Folding(folder), &mut statement);
+    statement
+}
+
+pub fn walk_expression<F: Fold + ?Sized>(folder: &mut F, mut expression: Expression) -> Expression {
+    visit_mut::walk_expression_mut(&mut Folding(folder), &mut expression);
+    expression
+}
+
+/// Replaces each child the in-place walk reaches with what the folder returns for it, leaving a placeholder
+/// in its slot while the folder owns it
+struct Folding<'a, F: Fold + ?Sized>(&'a mut F);
+impl<F: Fold + ?Sized> VisitorMut for Folding<'_, F> {
+    fn visit_node_mut(&mut self, node: &mut ASTNode) {
+        let old = mem::replace(node, ASTNode::Expression(Expression::Literal(Box::from(LiteralExpression::Null))));
+        *node = self.0.fold_node(old);
+    }
+    fn visit_block_mut(&mut self, block: &mut BlockStatementNode) {
+        let old = std::mem::take(block);
+        *block = self.0.fold_block(old);
+    }
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        let old = mem::replace(statement, Statement::Block(Box::from(BlockStatementNode::new())));
+        *statement = self.0.fold_statement(old);
+    }
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        let old = mem::replace(expression, Expression::Literal(Box::from(LiteralExpression::Null)));
+        *expression = self.0.fold_expression(old);
+    }
+    fn visit_type_mut(&mut self, r#type: &mut Type) {
+        let old = mem::replace(r#type, Type::NULL);
+        *r#type = self.0.fold_type(old);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Lexer;
+    use crate::parser::ast::fold::{walk_expression, Fold};
+    use crate::parser::ast::nodes::{Expression, VariableAccessExpressionNode};
+    use crate::parser::Parser;
+
+    /// Renames the reads of one variable
+    struct Rename(&'static str, &'static str);
+    impl Fold for Rename {
+        fn fold_expression(&mut self, expression: Expression) -> Expression {
+            match expression {
+                Expression::VariableAccess(v) if v.get_name() == self.0 => {
+                    Expression::VariableAccess(Box::from(VariableAccessExpressionNode::new(String::from(self.1))))
+                }
+                other => { walk_expression(self, other) }
+            }
+        }
+    }
+
+    #[test]
+    fn fold_rewrites_nested_expressions() {
+        let source = "func main() {\n    mk a = 1;\n    mk b = if a > 0 { a + 1 } else { 0 };\n}\n";
+        let root = Parser::new(Lexer::new(String::from(source)).tokenize()).parse();
+        let folded = Rename("a", "z").fold_block(root).to_string();
+        assert_eq!(folded.matches("VariableAccess(z)").count(), 2, "{}", folded);
+        assert!(!folded.contains("VariableAccess(a)"), "{}", folded);
+    }
+}
@@ -1 +1,4 @@
-pub mod nodes;
\ No newline at end of file
+pub mod nodes;
+pub mod visit;
+pub mod visit_mut;
+pub mod fold;
\ No newline at end of file
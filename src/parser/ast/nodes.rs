@@ -1,15 +1,14 @@
-use std::fmt::{write, Display, Formatter};
-use std::io::stdin;
+use std::fmt::{Display, Formatter};
 use num_traits::FromPrimitive;
-use crate::core::omnia_types::{OmniaByte, OmniaChar, OmniaChararr, OmniaDecimal, OmniaInt, OmniaLong, OmniaOmni, OmniaSpan, OmniaUByte, OmniaUInt, OmniaULong, OmniaValue, Type};
+use crate::core::omnia_types::{OmniaByte, OmniaChar, OmniaChararr, OmniaDecimal, OmniaInt, OmniaLong, OmniaOmni, OmniaSpan, OmniaUByte, OmniaUInt, OmniaULong, OmniaValue, Type, CastMode};
 use crate::core::utils::numeric_utils::omni::f128;
-use crate::lexer::token::TokenType;
+use crate::lexer::token::{Span, TokenType};
 use crate::parser::ast::nodes::AssignmentOperator::{ANDA, ASSIGN, DIVA, MINUSA, MULA, ORA, PLUSA, REMA};
 use crate::parser::ast::nodes::BinaryOperation::{Add, Div, Mul, Rem, Sub, Power};
 use crate::parser::ast::nodes::BitwiseOperation::{BitwiseAnd, BitwiseOr, BitwiseXor, ShiftL, ShiftR};
-use crate::parser::ast::nodes::ConditionalOperation::{Eq, Greater, GreaterEq, Less, LessEq, NotEq};
+use crate::parser::ast::nodes::ConditionalOperation::{Eq, Greater, GreaterEq, Less, LessEq, NotEq, StrictEq};
 use crate::parser::ast::nodes::LogicalOperation::{And, Or};
-use crate::parser::ast::nodes::UnaryOperation::{Dec, Inc, Neg, Not};
+use crate::parser::ast::nodes::UnaryOperation::{Dec, Inc, Neg, Not, Ref};
 use crate::parser::CompilerError;
 use crate::parser::CompilerError::TypeError;
 
@@ -18,6 +17,7 @@ pub enum ASTNode {
     Statement(Statement)
 }
 
+#[allow(dead_code)]
 trait CanBeVisible {
     fn is_visible(&self) -> bool;
 }
@@ -43,7 +43,9 @@ pub enum UnaryOperation {
     Inc,
     Dec,
     Not,
-    Neg
+    Neg,
+    /// `&x`, the address of a variable, e.g. to let a library function fill it in
+    Ref
 }
 pub enum ConditionalOperation {
     Greater,
@@ -51,7 +53,8 @@ pub enum ConditionalOperation {
     GreaterEq,
     LessEq,
     Eq,
-    NotEq
+    NotEq,
+    StrictEq
 }
 pub enum LogicalOperation {
     And,
@@ -86,7 +89,41 @@ pub enum LiteralExpression {
     Decimal(DecimalNode),
     Omni(OmniNode),
     Char(CharNode),
-    Chararr(CharArrNode)
+    Chararr(CharArrNode),
+    Null
+}
+impl LiteralExpression {
+    /// The value of the literal, `None` for `null`
+    pub fn get_value(&self) -> Option<&dyn OmniaValue> {
+        match self {
+            LiteralExpression::Byte(v) => { Some(&v.value) }
+            LiteralExpression::Int(v) => { Some(&v.value) }
+            LiteralExpression::Long(v) => { Some(&v.value) }
+            LiteralExpression::UByte(v) => { Some(&v.value) }
+            LiteralExpression::UInt(v) => { Some(&v.value) }
+            LiteralExpression::ULong(v) => { Some(&v.value) }
+            LiteralExpression::Decimal(v) => { Some(&v.value) }
+            LiteralExpression::Char(v) => { Some(&v.value) }
+            LiteralExpression::Chararr(v) => { Some(&v.value) }
+            LiteralExpression::Omni(v) => { Some(&v.value) }
+            LiteralExpression::Null => { None }
+        }
+    }
+    pub fn get_type(&self) -> Type {
+        match self {
+            LiteralExpression::Byte(_) => { Type::BYTE }
+            LiteralExpression::Int(_) => { Type::INT }
+            LiteralExpression::Long(_) => { Type::LONG }
+            LiteralExpression::UByte(_) => { Type::UBYTE }
+            LiteralExpression::UInt(_) => { Type::UINT }
+            LiteralExpression::ULong(_) => { Type::ULONG }
+            LiteralExpression::Decimal(_) => { Type::DECIMAL }
+            LiteralExpression::Omni(_) => { Type::OMNI }
+            LiteralExpression::Char(_) => { Type::CHAR }
+            LiteralExpression::Chararr(_) => { Type::CHARARR }
+            LiteralExpression::Null => { Type::NULL }
+        }
+    }
 }
 impl Display for LiteralExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -101,6 +138,7 @@ impl Display for LiteralExpression {
             LiteralExpression::Omni(v) => { write!(f, "Omni literal: {}", v.value.get_value_as::<String>()) }
             LiteralExpression::Char(v) => { write!(f, "Char literal: {}", v.value.get_value_as::<char>()) }
             LiteralExpression::Chararr(v) => { write!(f, "Chararr literal: {}", v.value.get_as_string()) }
+            LiteralExpression::Null => { write!(f, "Null literal") }
         }
     }
 }
@@ -121,7 +159,18 @@ pub enum Expression {
     Argument(Box<ArgumentExpressionNode>),
     Comparative(Box<ComparativeExpressionNode>),
     Logical(Box<LogicalExpressionNode>),
-    Bitwise(Box<BitwiseExpressionNode>)
+    Bitwise(Box<BitwiseExpressionNode>),
+    Lambda(Box<LambdaExpressionNode>),
+    Ternary(Box<TernaryExpressionNode>),
+    If(Box<IfStatementNode>),
+    Variant(Box<VariantExpressionNode>),
+    MethodCall(Box<MethodCallNode>),
+    FieldAccess(Box<FieldAccessExpressionNode>),
+    ResultValue(Box<ResultExpressionNode>),
+    Propagate(Box<PropagateExpressionNode>),
+    Tuple(Box<TupleExpressionNode>),
+    Cast(Box<CastExpressionNode>),
+    LayoutQuery(Box<LayoutQueryExpressionNode>)
 }
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -135,6 +184,17 @@ impl Display for Expression {
             Expression::Comparative(v) => { write!(f, "{}", v) }
             Expression::Logical(v) => { write!(f, "{}", v) }
             Expression::Bitwise(v) => { write!(f, "{}", v) }
+            Expression::Lambda(v) => { write!(f, "{}", v) }
+            Expression::Ternary(v) => { write!(f, "{}", v) }
+            Expression::If(v) => { write!(f, "{}", v) }
+            Expression::Variant(v) => { write!(f, "{}", v) }
+            Expression::MethodCall(v) => { write!(f, "{}", v) }
+            Expression::FieldAccess(v) => { write!(f, "{}", v) }
+            Expression::ResultValue(v) => { write!(f, "{}", v) }
+            Expression::Propagate(v) => { write!(f, "{}", v) }
+            Expression::Tuple(v) => { write!(f, "{}", v) }
+            Expression::Cast(v) => { write!(f, "{}", v) }
+            Expression::LayoutQuery(v) => { write!(f, "{}", v) }
         }
     }
 }
@@ -149,7 +209,19 @@ pub enum Statement {
     VariableCreation(Box<VariableCreationStatementNode>),
     Return(Box<ReturnStatementNode>),
     LambdaDeclaration(Box<LambdaDeclarationStatementNode>),
-    StructDeclaration(Box<StructDeclarationStatementNode>)
+    StructDeclaration(Box<StructDeclarationStatementNode>),
+    EnumDeclaration(Box<EnumDeclarationStatementNode>),
+    Switch(Box<SwitchStatementNode>),
+    InterfaceDeclaration(Box<InterfaceDeclarationStatementNode>),
+    Extension(Box<ExtensionStatementNode>),
+    MethodCall(Box<MethodCallNode>),
+    Propagate(Box<PropagateExpressionNode>),
+    /// `x++;` or `x--;`
+    Unary(Box<UnaryExpressionNode>),
+    Destructuring(Box<DestructuringStatementNode>),
+    /// `int a, b;`
+    Declaration(Box<DeclarationStatementNode>),
+    Static(Box<StaticDeclarationStatementNode>)
 }
 impl Display for Statement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -164,6 +236,16 @@ impl Display for Statement {
             Statement::Return(v) => { write!(f, "{}", v) }
             Statement::LambdaDeclaration(v) => { write!(f, "{}", v) }
             Statement::StructDeclaration(v) => { write!(f, "{}", v) }
+            Statement::EnumDeclaration(v) => { write!(f, "{}", v) }
+            Statement::Switch(v) => { write!(f, "{}", v) }
+            Statement::InterfaceDeclaration(v) => { write!(f, "{}", v) }
+            Statement::Extension(v) => { write!(f, "{}", v) }
+            Statement::MethodCall(v) => { write!(f, "{}", v) }
+            Statement::Propagate(v) => { write!(f, "{}", v) }
+            Statement::Unary(v) => { write!(f, "{}", v) }
+            Statement::Destructuring(v) => { write!(f, "{}", v) }
+            Statement::Declaration(v) => { write!(f, "{}", v) }
+            Statement::Static(v) => { write!(f, "{}", v) }
         }
     }
 }
@@ -176,6 +258,7 @@ impl Display for ConditionalOperation {
             LessEq => { write!(f, "<=") }
             Eq => { write!(f, "==") }
             NotEq => { write!(f, "!=") }
+            StrictEq => { write!(f, "=!") }
         }
     }
 }
@@ -232,6 +315,9 @@ impl ConditionalOperation {
             "!=" => {
                 Ok(NotEq)
             }
+            "=!" => {
+                Ok(StrictEq)
+            }
             &_ => {
                 Err(TypeError(format!("Unexpected conditional operator in string {}", string)))
             }
@@ -260,6 +346,9 @@ impl ConditionalOperation {
             TokenType::NEQ => {
                 Ok(NotEq)
             }
+            TokenType::FEQ => {
+                Ok(StrictEq)
+            }
             &_ => {
                 Err(TypeError(format!("Unexpected conditional operator in token {}", t)))
             }
@@ -273,6 +362,7 @@ impl Display for UnaryOperation {
             Dec => { write!(f, "--") }
             Not => { write!(f, "!") }
             Neg => { write!(f, "-") }
+            Ref => { write!(f, "&") }
         }
     }
 }
@@ -291,6 +381,9 @@ impl UnaryOperation {
             "-" => {
                 Ok(Neg)
             }
+            "&" => {
+                Ok(Ref)
+            }
             &_ => {
                 Err(TypeError(format!("Unexpected unary operator in string {}", string)))
             }
@@ -559,6 +652,21 @@ impl BinaryExpressionNode {
             right
         }
     }
+    pub fn get_left(&self) -> &Expression {
+        &self.left
+    }
+    pub fn get_left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+    pub fn get_op(&self) -> &BinaryOperation {
+        &self.op
+    }
+    pub fn get_right(&self) -> &Expression {
+        &self.right
+    }
+    pub fn get_right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
 }
 impl Display for BinaryExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -578,6 +686,21 @@ impl ComparativeExpressionNode {
             right
         }
     }
+    pub fn get_left(&self) -> &Expression {
+        &self.left
+    }
+    pub fn get_left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+    pub fn get_op(&self) -> &ConditionalOperation {
+        &self.op
+    }
+    pub fn get_right(&self) -> &Expression {
+        &self.right
+    }
+    pub fn get_right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
 }
 impl Display for ComparativeExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -598,6 +721,21 @@ impl LogicalExpressionNode {
             right
         }
     }
+    pub fn get_left(&self) -> &Expression {
+        &self.left
+    }
+    pub fn get_left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+    pub fn get_op(&self) -> &LogicalOperation {
+        &self.op
+    }
+    pub fn get_right(&self) -> &Expression {
+        &self.right
+    }
+    pub fn get_right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
 }
 impl Display for LogicalExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -618,6 +756,21 @@ impl BitwiseExpressionNode {
             right
         }
     }
+    pub fn get_left(&self) -> &Expression {
+        &self.left
+    }
+    pub fn get_left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+    pub fn get_op(&self) -> &BitwiseOperation {
+        &self.op
+    }
+    pub fn get_right(&self) -> &Expression {
+        &self.right
+    }
+    pub fn get_right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
 }
 impl Display for BitwiseExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -636,6 +789,15 @@ impl UnaryExpressionNode {
             op
         }
     }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+    pub fn get_op(&self) -> &UnaryOperation {
+        &self.op
+    }
 }
 impl Display for UnaryExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -643,6 +805,44 @@ impl Display for UnaryExpressionNode {
     }
 }
 
+pub struct TernaryExpressionNode {
+    cond: Expression,
+    then: Expression,
+    r#else: Expression
+}
+impl TernaryExpressionNode {
+    pub fn new(cond: Expression, then: Expression, r#else: Expression) -> Self {
+        Self {
+            cond,
+            then,
+            r#else
+        }
+    }
+    pub fn get_cond(&self) -> &Expression {
+        &self.cond
+    }
+    pub fn get_cond_mut(&mut self) -> &mut Expression {
+        &mut self.cond
+    }
+    pub fn get_then(&self) -> &Expression {
+        &self.then
+    }
+    pub fn get_then_mut(&mut self) -> &mut Expression {
+        &mut self.then
+    }
+    pub fn get_else(&self) -> &Expression {
+        &self.r#else
+    }
+    pub fn get_else_mut(&mut self) -> &mut Expression {
+        &mut self.r#else
+    }
+}
+impl Display for TernaryExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TernaryExpression({} ? {} : {})", self.cond, self.then, self.r#else)
+    }
+}
+
 pub struct VariableAccessExpressionNode {
     name: String
 }
@@ -657,24 +857,124 @@ impl VariableAccessExpressionNode {
             name
         }
     }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
 }
 pub struct ArgumentExpressionNode {
     name: String,
-    r#type: Type
+    r#type: Type,
+    mutable: bool
 }
 
 impl Display for ArgumentExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Argument({}, {})", self.name, self.r#type)
+        if self.mutable {
+            write!(f, "Argument(mut {}, {})", self.name, self.r#type)
+        } else {
+            write!(f, "Argument({}, {})", self.name, self.r#type)
+        }
     }
 }
 impl ArgumentExpressionNode {
-    pub fn new(name: String, r#type: Type) -> Self {
+    /// Parameters are immutable unless written `mut x: T`
+    pub fn new(name: String, r#type: Type, mutable: bool) -> Self {
         Self {
             name,
-            r#type
+            r#type,
+            mutable
         }
     }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+    pub fn get_type(&self) -> &Type {
+        &self.r#type
+    }
+    pub fn get_type_mut(&mut self) -> &mut Type {
+        &mut self.r#type
+    }
+}
+
+pub struct GenericParameterNode {
+    name: String,
+    bounds: Vec<Type>
+}
+impl GenericParameterNode {
+    pub fn new(name: String, bounds: Vec<Type>) -> Self {
+        Self {
+            name,
+            bounds
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_bounds(&self) -> &Vec<Type> {
+        &self.bounds
+    }
+    pub fn get_bounds_mut(&mut self) -> &mut Vec<Type> {
+        &mut self.bounds
+    }
+}
+impl Display for GenericParameterNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (i, val) in self.bounds.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { " + " } else { ": " }, val)?;
+        }
+        Ok(())
+    }
+}
+fn write_generics(f: &mut Formatter<'_>, generics: &[GenericParameterNode]) -> std::fmt::Result {
+    if generics.is_empty() {
+        return Ok(())
+    }
+    write!(f, "<")?;
+    for (i, val) in generics.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", val)?;
+    }
+    write!(f, ">")
+}
+
+pub struct AttributeNode {
+    name: String,
+    argument: Option<String>
+}
+impl AttributeNode {
+    pub fn new(name: String, argument: Option<String>) -> Self {
+        Self {
+            name,
+            argument
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_argument(&self) -> Option<&String> {
+        self.argument.as_ref()
+    }
+}
+impl Display for AttributeNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}", self.name)?;
+        if let Some(argument) = &self.argument {
+            write!(f, "(\"{}\")", argument)?;
+        }
+        Ok(())
+    }
+}
+fn write_attributes(f: &mut Formatter<'_>, attributes: &Vec<AttributeNode>) -> std::fmt::Result {
+    for val in attributes {
+        write!(f, "{} ", val)?;
+    }
+    Ok(())
 }
 
 pub struct FieldExpressionNode {
@@ -689,6 +989,15 @@ impl FieldExpressionNode {
             r#type
         }
     }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_type(&self) -> &Type {
+        &self.r#type
+    }
+    pub fn get_type_mut(&mut self) -> &mut Type {
+        &mut self.r#type
+    }
 }
 impl Display for FieldExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -698,22 +1007,57 @@ impl Display for FieldExpressionNode {
 
 pub struct StructDeclarationStatementNode {
     name: String,
+    generics: Vec<GenericParameterNode>,
     fields: Vec<FieldExpressionNode>,
-    is_visible: bool
+    is_visible: bool,
+    attributes: Vec<AttributeNode>
 }
 
 impl StructDeclarationStatementNode {
-    pub fn new(name: String, fields: Vec<FieldExpressionNode>, is_visible: bool) -> StructDeclarationStatementNode {
+    pub fn new(name: String, generics: Vec<GenericParameterNode>, fields: Vec<FieldExpressionNode>, is_visible: bool) -> StructDeclarationStatementNode {
         Self {
             name,
+            generics,
             fields,
-            is_visible
+            is_visible,
+            attributes: Vec::new()
         }
     }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_generics(&self) -> &Vec<GenericParameterNode> {
+        &self.generics
+    }
+    pub fn get_generics_mut(&mut self) -> &mut Vec<GenericParameterNode> {
+        &mut self.generics
+    }
+    pub fn get_fields(&self) -> &Vec<FieldExpressionNode> {
+        &self.fields
+    }
+    pub fn get_fields_mut(&mut self) -> &mut Vec<FieldExpressionNode> {
+        &mut self.fields
+    }
+    pub fn with_attributes(mut self, attributes: Vec<AttributeNode>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+    pub fn get_attributes(&self) -> &Vec<AttributeNode> {
+        &self.attributes
+    }
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeNode> {
+        self.attributes.iter().find(|a| a.get_name() == name)
+    }
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
 }
 impl Display for StructDeclarationStatementNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "StructDeclaration({}, [", self.name)?;
+        write_attributes(f, &self.attributes)?;
+        write!(f, "StructDeclaration({}", self.name)?;
+        write_generics(f, &self.generics)?;
+        write!(f, ", [")?;
         for (i, val) in self.fields.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
@@ -729,169 +1073,1101 @@ impl CanBeVisible for StructDeclarationStatementNode {
     }
 }
 
-pub struct LambdaDeclarationStatementNode {
+pub enum VariantPayload {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Vec<FieldExpressionNode>)
+}
+pub struct EnumVariantNode {
     name: String,
-    args: Vec<Expression>,
-    returns: Type,
-    body: Statement
-
+    payload: VariantPayload
 }
-impl LambdaDeclarationStatementNode {
-    pub fn new(name: String, args: Vec<Expression>, returns: Type, body: Statement) -> Self {
+impl EnumVariantNode {
+    pub fn new(name: String, payload: VariantPayload) -> Self {
         Self {
             name,
-            args,
-            returns,
-            body
+            payload
         }
     }
-}
-impl Display for LambdaDeclarationStatementNode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "LambdaDeclaration({}, [", self.name)?;
-        for (i, val) in self.args.iter().enumerate() {
-            if i > 0 {
-                write!(f, ", ")?;
-            }
-            write!(f, "{}", val)?;
-        }
-        write!(f, "], {}, {}", self.returns, self.body)
+    pub fn get_name(&self) -> &String {
+        &self.name
     }
-}
-
-pub struct AssignmentStatementNode {
-    cont: String,
-    op: AssignmentOperator,
-    value: Expression
-}
-impl AssignmentStatementNode {
-    pub fn new(cont: String, op: AssignmentOperator, value: Expression) -> Self {
-        Self {
-            cont,
-            op,
-            value
-        }
+    pub fn get_payload(&self) -> &VariantPayload {
+        &self.payload
     }
-}
-impl Display for AssignmentStatementNode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Assignment({} {} {})", self.cont, self.op, self.value)
+    pub fn get_payload_mut(&mut self) -> &mut VariantPayload {
+        &mut self.payload
     }
-}
-pub struct FunctionCallNode {
-    name: String,
-    args: Option<Vec<ASTNode>>
-}
-impl FunctionCallNode {
-    pub fn new(name: String, args: Option<Vec<ASTNode>>) -> Self {
-        Self {
-            name,
-            args
+    /// Payload fields in declaration order. Tuple payload fields are named by their index
+    pub fn get_payload_fields(&self) -> Vec<(String, Type)> {
+        match &self.payload {
+            VariantPayload::Unit => { Vec::new() }
+            VariantPayload::Tuple(types) => { types.iter().enumerate().map(|(i, x)| (i.to_string(), x.clone())).collect() }
+            VariantPayload::Struct(fields) => { fields.iter().map(|x| (x.get_name().clone(), x.get_type().clone())).collect() }
         }
     }
 }
-impl Display for FunctionCallNode {
+impl Display for EnumVariantNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Some(v) = &self.args {
-            write!(f, "FunctionCall({}, [", self.name)?;
-            for (i, val) in v.iter().enumerate() {
-                if i > 0 {
-                    write!(f, ", ")?;
+        write!(f, "Variant({}", self.name)?;
+        match &self.payload {
+            VariantPayload::Unit => {}
+            VariantPayload::Tuple(types) => {
+                for val in types {
+                    write!(f, ", {}", val)?;
+                }
+            }
+            VariantPayload::Struct(fields) => {
+                for val in fields {
+                    write!(f, ", {}", val)?;
                 }
-                write!(f, "{}", val)?;
             }
-            write!(f, "]")
-        }
-        else {
-            write!(f, "FunctionCall({})", self.name)
         }
+        write!(f, ")")
     }
 }
-pub struct FunctionDeclarationStatementNode {
+
+pub struct EnumDeclarationStatementNode {
     name: String,
-    args: Vec<Expression>,
-    returns: Type,
-    body: Statement,
+    generics: Vec<GenericParameterNode>,
+    variants: Vec<EnumVariantNode>,
     is_visible: bool
 }
-
-impl FunctionDeclarationStatementNode {
-    pub fn new(name: String, args: Vec<Expression>, returns: Type, body: Statement, is_visible: bool) -> Self {
+impl EnumDeclarationStatementNode {
+    pub fn new(name: String, generics: Vec<GenericParameterNode>, variants: Vec<EnumVariantNode>, is_visible: bool) -> Self {
         Self {
             name,
-            args,
-            returns,
-            body,
+            generics,
+            variants,
             is_visible
         }
     }
     pub fn get_name(&self) -> &String {
         &self.name
     }
-    pub fn get_args(&self) -> &Vec<Expression> {
-        &self.args
+    pub fn get_generics(&self) -> &Vec<GenericParameterNode> {
+        &self.generics
     }
-    pub fn get_return_type(&self) -> &Type {
-        &self.returns
+    pub fn get_generics_mut(&mut self) -> &mut Vec<GenericParameterNode> {
+        &mut self.generics
     }
-    pub fn get_body(&self) -> &Statement {
-        &self.body
+    pub fn get_variants(&self) -> &Vec<EnumVariantNode> {
+        &self.variants
     }
-    pub fn is_visible(&self) -> &bool {
-        &self.is_visible
+    pub fn get_variants_mut(&mut self) -> &mut Vec<EnumVariantNode> {
+        &mut self.variants
+    }
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
     }
 }
-impl Display for FunctionDeclarationStatementNode {
+impl Display for EnumDeclarationStatementNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "FunctionDeclaration({}, [", self.name)?;
-        for (i, val) in self.args.iter().enumerate() {
+        write!(f, "EnumDeclaration({}", self.name)?;
+        write_generics(f, &self.generics)?;
+        write!(f, ", [")?;
+        for (i, val) in self.variants.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
             }
             write!(f, "{}", val)?;
         }
-        write!(f, "], {}, {}, visible: {})", self.returns, self.body, self.is_visible)
+        write!(f, "], visible: {})", self.is_visible)
     }
 }
-impl CanBeVisible for FunctionDeclarationStatementNode {
+impl CanBeVisible for EnumDeclarationStatementNode {
     fn is_visible(&self) -> bool {
         self.is_visible
     }
 }
-pub struct VariableCreationStatementNode {
-    name: String,
-    r#type: Type,
-    value: Expression
-}
 
-impl VariableCreationStatementNode {
-    pub fn new(name: String, r#type: Type, value: Expression) -> VariableCreationStatementNode {
+pub enum VariantValues {
+    Unit,
+    Tuple(Vec<Expression>),
+    Struct(Vec<(String, Expression)>)
+}
+pub struct VariantExpressionNode {
+    r#enum: String,
+    variant: String,
+    values: VariantValues
+}
+impl VariantExpressionNode {
+    pub fn new(r#enum: String, variant: String, values: VariantValues) -> Self {
         Self {
-            name,
-            r#type,
-            value
+            r#enum,
+            variant,
+            values
         }
     }
+    pub fn get_enum(&self) -> &String {
+        &self.r#enum
+    }
+    pub fn get_variant(&self) -> &String {
+        &self.variant
+    }
+    pub fn get_values(&self) -> &VariantValues {
+        &self.values
+    }
+    pub fn get_values_mut(&mut self) -> &mut VariantValues {
+        &mut self.values
+    }
 }
-impl Display for VariableCreationStatementNode {
+impl Display for VariantExpressionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "VariableCreation({}, {}, {})", self.name, self.r#type, self.value)
+        write!(f, "VariantConstruction({}::{}", self.r#enum, self.variant)?;
+        match &self.values {
+            VariantValues::Unit => {}
+            VariantValues::Tuple(values) => {
+                for val in values {
+                    write!(f, ", {}", val)?;
+                }
+            }
+            VariantValues::Struct(values) => {
+                for (name, val) in values {
+                    write!(f, ", {}: {}", name, val)?;
+                }
+            }
+        }
+        write!(f, ")")
     }
 }
 
-pub struct ReturnStatementNode {
-    value: ASTNode
+pub enum PatternBindings {
+    Unit,
+    Tuple(Vec<String>),
+    Struct(Vec<(String, String)>)
+}
+pub enum Pattern {
+    Wildcard,
+    Literal(Expression),
+    Variant(String, String, PatternBindings),
+    Ok(String),
+    Err(String)
+}
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard => { write!(f, "_") }
+            Pattern::Literal(e) => { write!(f, "{}", e) }
+            Pattern::Ok(name) => { write!(f, "ok({})", name) }
+            Pattern::Err(name) => { write!(f, "err({})", name) }
+            Pattern::Variant(r#enum, variant, bindings) => {
+                write!(f, "{}::{}", r#enum, variant)?;
+                match bindings {
+                    PatternBindings::Unit => { Ok(()) }
+                    PatternBindings::Tuple(names) => { write!(f, "({})", names.join(", ")) }
+                    PatternBindings::Struct(fields) => {
+                        write!(f, " {{ ")?;
+                        for (i, (field, name)) in fields.iter().enumerate() {
+                            if i > 0 {
+                                write!(f, ", ")?;
+                            }
+                            write!(f, "{}: {}", field, name)?;
+                        }
+                        write!(f, " }}")
+                    }
+                }
+            }
+        }
+    }
 }
-impl ReturnStatementNode {
-    pub fn new(value: ASTNode) -> ReturnStatementNode {
+pub struct SwitchArmNode {
+    pattern: Pattern,
+    body: Statement
+}
+impl SwitchArmNode {
+    pub fn new(pattern: Pattern, body: Statement) -> Self {
         Self {
-            value
+            pattern,
+            body
         }
     }
-}
-impl Display for ReturnStatementNode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Return({})", self.value)
+    pub fn get_pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+    pub fn get_pattern_mut(&mut self) -> &mut Pattern {
+        &mut self.pattern
+    }
+    pub fn get_body(&self) -> &Statement {
+        &self.body
+    }
+    pub fn get_body_mut(&mut self) -> &mut Statement {
+        &mut self.body
+    }
+}
+pub struct SwitchStatementNode {
+    value: Expression,
+    arms: Vec<SwitchArmNode>
+}
+impl SwitchStatementNode {
+    pub fn new(value: Expression, arms: Vec<SwitchArmNode>) -> Self {
+        Self {
+            value,
+            arms
+        }
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+    pub fn get_arms(&self) -> &Vec<SwitchArmNode> {
+        &self.arms
+    }
+    pub fn get_arms_mut(&mut self) -> &mut Vec<SwitchArmNode> {
+        &mut self.arms
+    }
+}
+impl Display for SwitchStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Switch({}, [", self.value)?;
+        for (i, arm) in self.arms.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} -> {}", arm.pattern, arm.body)?;
+        }
+        write!(f, "])")
+    }
+}
+
+pub struct LambdaExpressionNode {
+    id: usize,
+    args: Vec<Expression>,
+    returns: Type,
+    body: Statement
+}
+impl LambdaExpressionNode {
+    pub fn new(id: usize, args: Vec<Expression>, returns: Type, body: Statement) -> Self {
+        Self {
+            id,
+            args,
+            returns,
+            body
+        }
+    }
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+    pub fn get_args(&self) -> &Vec<Expression> {
+        &self.args
+    }
+    pub fn get_args_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.args
+    }
+    pub fn get_return_type(&self) -> &Type {
+        &self.returns
+    }
+    pub fn get_return_type_mut(&mut self) -> &mut Type {
+        &mut self.returns
+    }
+    pub fn get_body(&self) -> &Statement {
+        &self.body
+    }
+    pub fn get_body_mut(&mut self) -> &mut Statement {
+        &mut self.body
+    }
+    pub fn get_type(&self) -> Type {
+        let params = self.args.iter().filter_map(|x| match x {
+            Expression::Argument(a) => Some(a.get_type().clone()),
+            _ => None
+        }).collect();
+        Type::FUNCTION(params, Box::from(self.returns.clone()))
+    }
+}
+impl Display for LambdaExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lambda#{}([", self.id)?;
+        for (i, val) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, "], {}, {})", self.returns, self.body)
+    }
+}
+
+pub struct LambdaDeclarationStatementNode {
+    name: String,
+    lambda: LambdaExpressionNode,
+    mutable: bool
+}
+impl LambdaDeclarationStatementNode {
+    /// A lambda bound with `mk mut f = ...` can later be reassigned to another lambda of the same type
+    pub fn new(name: String, lambda: LambdaExpressionNode, mutable: bool) -> Self {
+        Self {
+            name,
+            lambda,
+            mutable
+        }
+    }
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_lambda(&self) -> &LambdaExpressionNode {
+        &self.lambda
+    }
+    pub fn get_lambda_mut(&mut self) -> &mut LambdaExpressionNode {
+        &mut self.lambda
+    }
+}
+impl Display for LambdaDeclarationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = if self.mutable { format!("mut {}", self.name) } else { self.name.clone() };
+        write!(f, "LambdaDeclaration({}, {})", name, self.lambda)
+    }
+}
+
+pub struct AssignmentStatementNode {
+    cont: String,
+    op: AssignmentOperator,
+    value: Expression
+}
+impl AssignmentStatementNode {
+    pub fn new(cont: String, op: AssignmentOperator, value: Expression) -> Self {
+        Self {
+            cont,
+            op,
+            value
+        }
+    }
+    pub fn get_cont(&self) -> &String {
+        &self.cont
+    }
+    pub fn get_op(&self) -> &AssignmentOperator {
+        &self.op
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for AssignmentStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Assignment({} {} {})", self.cont, self.op, self.value)
+    }
+}
+pub struct FunctionCallNode {
+    name: String,
+    args: Option<Vec<ASTNode>>
+}
+impl FunctionCallNode {
+    pub fn new(name: String, args: Option<Vec<ASTNode>>) -> Self {
+        Self {
+            name,
+            args
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_args(&self) -> &Option<Vec<ASTNode>> {
+        &self.args
+    }
+    pub fn get_args_mut(&mut self) -> &mut Option<Vec<ASTNode>> {
+        &mut self.args
+    }
+}
+impl Display for FunctionCallNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(v) = &self.args {
+            write!(f, "FunctionCall({}, [", self.name)?;
+            for (i, val) in v.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", val)?;
+            }
+            write!(f, "]")
+        }
+        else {
+            write!(f, "FunctionCall({})", self.name)
+        }
+    }
+}
+pub struct FunctionDeclarationStatementNode {
+    name: String,
+    generics: Vec<GenericParameterNode>,
+    args: Vec<Expression>,
+    returns: Type,
+    body: Statement,
+    is_visible: bool,
+    is_static: bool,
+    attributes: Vec<AttributeNode>
+}
+
+impl FunctionDeclarationStatementNode {
+    pub fn new(name: String, generics: Vec<GenericParameterNode>, args: Vec<Expression>, returns: Type, body: Statement, is_visible: bool) -> Self {
+        Self {
+            name,
+            generics,
+            args,
+            returns,
+            body,
+            is_visible,
+            is_static: false,
+            attributes: Vec::new()
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_generics(&self) -> &Vec<GenericParameterNode> {
+        &self.generics
+    }
+    pub fn get_generics_mut(&mut self) -> &mut Vec<GenericParameterNode> {
+        &mut self.generics
+    }
+    pub fn get_args(&self) -> &Vec<Expression> {
+        &self.args
+    }
+    pub fn get_args_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.args
+    }
+    /// Types of the parameters in order, `self` included for methods
+    pub fn get_parameter_types(&self) -> Vec<Type> {
+        self.args.iter().filter_map(|x| match x {
+            Expression::Argument(a) => { Some(a.get_type().clone()) }
+            _ => { None }
+        }).collect()
+    }
+    /// Makes this function a method of `target` by prepending the `self` parameter
+    pub fn with_receiver(mut self, target: Type) -> Self {
+        self.args.insert(0, Expression::Argument(Box::from(ArgumentExpressionNode::new(String::from("self"), target, false))));
+        self
+    }
+    pub fn get_return_type(&self) -> &Type {
+        &self.returns
+    }
+    pub fn get_return_type_mut(&mut self) -> &mut Type {
+        &mut self.returns
+    }
+    pub fn get_body(&self) -> &Statement {
+        &self.body
+    }
+    pub fn get_body_mut(&mut self) -> &mut Statement {
+        &mut self.body
+    }
+    pub fn is_visible(&self) -> &bool {
+        &self.is_visible
+    }
+    /// Declared as `static func`, the way the entry point is often written, e.g. `static func main(&string[] args)`
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+    pub fn with_static(mut self, is_static: bool) -> Self {
+        self.is_static = is_static;
+        self
+    }
+    pub fn with_attributes(mut self, attributes: Vec<AttributeNode>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+    pub fn get_attributes(&self) -> &Vec<AttributeNode> {
+        &self.attributes
+    }
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeNode> {
+        self.attributes.iter().find(|a| a.get_name() == name)
+    }
+}
+impl Display for FunctionDeclarationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_attributes(f, &self.attributes)?;
+        write!(f, "FunctionDeclaration({}", self.name)?;
+        write_generics(f, &self.generics)?;
+        write!(f, ", [")?;
+        for (i, val) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, "], {}, {}, visible: {})", self.returns, self.body, self.is_visible)
+    }
+}
+impl CanBeVisible for FunctionDeclarationStatementNode {
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+pub struct MethodSignatureNode {
+    name: String,
+    args: Vec<Expression>,
+    returns: Type
+}
+impl MethodSignatureNode {
+    pub fn new(name: String, args: Vec<Expression>, returns: Type) -> Self {
+        Self {
+            name,
+            args,
+            returns
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_args(&self) -> &Vec<Expression> {
+        &self.args
+    }
+    pub fn get_args_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.args
+    }
+    pub fn get_return_type(&self) -> &Type {
+        &self.returns
+    }
+    pub fn get_return_type_mut(&mut self) -> &mut Type {
+        &mut self.returns
+    }
+}
+impl Display for MethodSignatureNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Method({}, [", self.name)?;
+        for (i, val) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, "], {})", self.returns)
+    }
+}
+
+pub struct InterfaceDeclarationStatementNode {
+    name: String,
+    methods: Vec<MethodSignatureNode>,
+    is_visible: bool
+}
+impl InterfaceDeclarationStatementNode {
+    pub fn new(name: String, methods: Vec<MethodSignatureNode>, is_visible: bool) -> Self {
+        Self {
+            name,
+            methods,
+            is_visible
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_methods(&self) -> &Vec<MethodSignatureNode> {
+        &self.methods
+    }
+    pub fn get_methods_mut(&mut self) -> &mut Vec<MethodSignatureNode> {
+        &mut self.methods
+    }
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+impl Display for InterfaceDeclarationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InterfaceDeclaration({}, [", self.name)?;
+        for (i, val) in self.methods.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, "], visible: {})", self.is_visible)
+    }
+}
+impl CanBeVisible for InterfaceDeclarationStatementNode {
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+
+pub struct ExtensionMethodNode {
+    function: FunctionDeclarationStatementNode,
+    is_override: bool
+}
+impl ExtensionMethodNode {
+    pub fn new(function: FunctionDeclarationStatementNode, is_override: bool) -> Self {
+        Self {
+            function,
+            is_override
+        }
+    }
+    pub fn get_function(&self) -> &FunctionDeclarationStatementNode {
+        &self.function
+    }
+    pub fn get_function_mut(&mut self) -> &mut FunctionDeclarationStatementNode {
+        &mut self.function
+    }
+    pub fn is_override(&self) -> bool {
+        self.is_override
+    }
+}
+
+/// `ext Interface for Type { ... }` block, or a single `ext func ... for Type` when there is no interface
+pub struct ExtensionStatementNode {
+    interface: Option<String>,
+    target: Type,
+    methods: Vec<ExtensionMethodNode>
+}
+impl ExtensionStatementNode {
+    pub fn new(interface: Option<String>, target: Type, methods: Vec<ExtensionMethodNode>) -> Self {
+        Self {
+            interface,
+            target,
+            methods
+        }
+    }
+    pub fn get_interface(&self) -> &Option<String> {
+        &self.interface
+    }
+    pub fn get_target(&self) -> &Type {
+        &self.target
+    }
+    pub fn get_target_mut(&mut self) -> &mut Type {
+        &mut self.target
+    }
+    pub fn get_methods(&self) -> &Vec<ExtensionMethodNode> {
+        &self.methods
+    }
+    pub fn get_methods_mut(&mut self) -> &mut Vec<ExtensionMethodNode> {
+        &mut self.methods
+    }
+}
+impl Display for ExtensionStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.interface {
+            Some(i) => { write!(f, "Extension({} for {}, [", i, self.target)? }
+            None => { write!(f, "Extension({}, [", self.target)? }
+        }
+        for (i, val) in self.methods.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            if val.is_override {
+                write!(f, "override ")?;
+            }
+            write!(f, "{}", val.function)?;
+        }
+        write!(f, "])")
+    }
+}
+
+pub struct MethodCallNode {
+    receiver: Expression,
+    name: String,
+    args: Vec<ASTNode>
+}
+impl MethodCallNode {
+    pub fn new(receiver: Expression, name: String, args: Vec<ASTNode>) -> Self {
+        Self {
+            receiver,
+            name,
+            args
+        }
+    }
+    pub fn get_receiver(&self) -> &Expression {
+        &self.receiver
+    }
+    pub fn get_receiver_mut(&mut self) -> &mut Expression {
+        &mut self.receiver
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_args(&self) -> &Vec<ASTNode> {
+        &self.args
+    }
+    pub fn get_args_mut(&mut self) -> &mut Vec<ASTNode> {
+        &mut self.args
+    }
+}
+impl Display for MethodCallNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MethodCall({}.{}, [", self.receiver, self.name)?;
+        for (i, val) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, "])")
+    }
+}
+
+pub struct FieldAccessExpressionNode {
+    value: Expression,
+    field: String
+}
+impl FieldAccessExpressionNode {
+    pub fn new(value: Expression, field: String) -> Self {
+        Self {
+            value,
+            field
+        }
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+    pub fn get_field(&self) -> &String {
+        &self.field
+    }
+}
+impl Display for FieldAccessExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FieldAccess({}.{})", self.value, self.field)
+    }
+}
+
+/// `ok(value)` or `err(value)`
+pub struct ResultExpressionNode {
+    is_ok: bool,
+    value: Expression
+}
+impl ResultExpressionNode {
+    pub fn new(is_ok: bool, value: Expression) -> Self {
+        Self {
+            is_ok,
+            value
+        }
+    }
+    pub fn is_ok(&self) -> bool {
+        self.is_ok
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for ResultExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", if self.is_ok { "Ok" } else { "Err" }, self.value)
+    }
+}
+
+/// `value!`: the success value of a result, returning its error from the enclosing function
+pub struct PropagateExpressionNode {
+    value: Expression
+}
+impl PropagateExpressionNode {
+    pub fn new(value: Expression) -> Self {
+        Self {
+            value
+        }
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for PropagateExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Propagate({})", self.value)
+    }
+}
+
+/// `value as T`, `value as? T`, `value as saturating T` or `value as wrapping T`
+pub struct CastExpressionNode {
+    value: Expression,
+    target: Type,
+    mode: CastMode
+}
+impl CastExpressionNode {
+    pub fn new(value: Expression, target: Type, mode: CastMode) -> Self {
+        Self {
+            value,
+            target,
+            mode
+        }
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+    pub fn get_target(&self) -> &Type {
+        &self.target
+    }
+    pub fn get_target_mut(&mut self) -> &mut Type {
+        &mut self.target
+    }
+    pub fn get_mode(&self) -> CastMode {
+        self.mode
+    }
+}
+impl Display for CastExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cast({} {} {})", self.value, self.mode, self.target)
+    }
+}
+
+/// What `sizeof` and `alignof` ask about a type
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LayoutQuery {
+    Size,
+    Align
+}
+impl Display for LayoutQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutQuery::Size => { write!(f, "sizeof") }
+            LayoutQuery::Align => { write!(f, "alignof") }
+        }
+    }
+}
+
+/// `sizeof(T)` or `alignof(T)`, a `ulong` constant
+pub struct LayoutQueryExpressionNode {
+    query: LayoutQuery,
+    target: Type
+}
+impl LayoutQueryExpressionNode {
+    pub fn new(query: LayoutQuery, target: Type) -> Self {
+        Self {
+            query,
+            target
+        }
+    }
+    pub fn get_query(&self) -> LayoutQuery {
+        self.query
+    }
+    pub fn get_target(&self) -> &Type {
+        &self.target
+    }
+    pub fn get_target_mut(&mut self) -> &mut Type {
+        &mut self.target
+    }
+}
+impl Display for LayoutQueryExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LayoutQuery({} {})", self.query, self.target)
+    }
+}
+
+pub struct TupleExpressionNode {
+    values: Vec<Expression>
+}
+impl TupleExpressionNode {
+    pub fn new(values: Vec<Expression>) -> Self {
+        Self {
+            values
+        }
+    }
+    pub fn get_values(&self) -> &Vec<Expression> {
+        &self.values
+    }
+    pub fn get_values_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.values
+    }
+}
+impl Display for TupleExpressionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tuple(")?;
+        for (i, val) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", val)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// `static int MAX = 1 << 10;`, a top-level constant evaluated at compile time
+pub struct StaticDeclarationStatementNode {
+    name: String,
+    r#type: Type,
+    value: Expression
+}
+impl StaticDeclarationStatementNode {
+    pub fn new(name: String, r#type: Type, value: Expression) -> Self {
+        Self {
+            name,
+            r#type,
+            value
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_type(&self) -> &Type {
+        &self.r#type
+    }
+    pub fn get_type_mut(&mut self) -> &mut Type {
+        &mut self.r#type
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for StaticDeclarationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Static({}, {}, {})", self.name, self.r#type, self.value)
+    }
+}
+
+/// `mk (q, r) = value;`, binding each element of a tuple. `_` skips an element
+pub struct DestructuringStatementNode {
+    id: usize,
+    names: Vec<String>,
+    value: Expression
+}
+impl DestructuringStatementNode {
+    pub fn new(id: usize, names: Vec<String>, value: Expression) -> Self {
+        Self {
+            id,
+            names,
+            value
+        }
+    }
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+    pub fn get_names(&self) -> &Vec<String> {
+        &self.names
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for DestructuringStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Destructuring(({}), {})", self.names.join(", "), self.value)
+    }
+}
+
+pub struct DeclarationStatementNode {
+    names: Vec<String>,
+    r#type: Type,
+    mutable: bool
+}
+impl DeclarationStatementNode {
+    /// Variables declared without a value, as in `int a, b;`, which are filled in later through a reference, e.g. `input.next_int(&a)`
+    pub fn new(names: Vec<String>, r#type: Type, mutable: bool) -> Self {
+        Self {
+            names,
+            r#type,
+            mutable
+        }
+    }
+    pub fn get_names(&self) -> &Vec<String> {
+        &self.names
+    }
+    pub fn get_type(&self) -> &Type {
+        &self.r#type
+    }
+    pub fn get_type_mut(&mut self) -> &mut Type {
+        &mut self.r#type
+    }
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+}
+impl Display for DeclarationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names = if self.mutable { format!("mut {}", self.names.join(", ")) } else { self.names.join(", ") };
+        write!(f, "Declaration({}, {})", names, self.r#type)
+    }
+}
+
+pub struct VariableCreationStatementNode {
+    id: usize,
+    name: String,
+    r#type: Option<Type>,
+    value: Expression,
+    mutable: bool
+}
+
+impl VariableCreationStatementNode {
+    /// `r#type` is `None` for `mk` declarations, which take the type of their initializer. Variables are immutable
+    /// unless declared with `mut`, as in `int mut i = 0;` or `mk mut i = 0;`
+    pub fn new(id: usize, name: String, r#type: Option<Type>, value: Expression, mutable: bool) -> VariableCreationStatementNode {
+        Self {
+            id,
+            name,
+            r#type,
+            value,
+            mutable
+        }
+    }
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_type(&self) -> Option<&Type> {
+        self.r#type.as_ref()
+    }
+    pub fn get_type_mut(&mut self) -> Option<&mut Type> {
+        self.r#type.as_mut()
+    }
+    pub fn get_value(&self) -> &Expression {
+        &self.value
+    }
+    pub fn get_value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+}
+impl Display for VariableCreationStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = if self.mutable { format!("mut {}", self.name) } else { self.name.clone() };
+        match &self.r#type {
+            Some(t) => { write!(f, "VariableCreation({}, {}, {})", name, t, self.value) }
+            None => { write!(f, "VariableCreation({}, mk, {})", name, self.value) }
+        }
+    }
+}
+
+pub struct ReturnStatementNode {
+    value: ASTNode,
+    tail: bool
+}
+impl ReturnStatementNode {
+    pub fn new(value: ASTNode) -> ReturnStatementNode {
+        Self {
+            value,
+            tail: false
+        }
+    }
+    /// Trailing value of a block, written without `return`
+    pub fn tail(value: ASTNode) -> ReturnStatementNode {
+        Self {
+            value,
+            tail: true
+        }
+    }
+    pub fn get_value(&self) -> &ASTNode {
+        &self.value
+    }
+    pub fn is_tail(&self) -> bool {
+        self.tail
+    }
+    pub fn get_value_mut(&mut self) -> &mut ASTNode {
+        &mut self.value
+    }
+}
+impl Display for ReturnStatementNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Return({})", self.value)
     }
 }
 
@@ -904,6 +2180,13 @@ impl PlugStatementNode {
             lib
         }
     }
+    pub fn get_lib(&self) -> &String {
+        &self.lib
+    }
+    /// Names the plug brings into scope: the last segment of the path, or each of `a|b`
+    pub fn get_items(&self) -> Vec<&str> {
+        self.lib.rsplit(['.', ':']).next().unwrap_or(&self.lib).split('|').collect()
+    }
 }
 impl Display for PlugStatementNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -924,6 +2207,24 @@ impl IfStatementNode {
             r#else
         }
     }
+    pub fn get_cond(&self) -> &Expression {
+        &self.cond
+    }
+    pub fn get_cond_mut(&mut self) -> &mut Expression {
+        &mut self.cond
+    }
+    pub fn get_then(&self) -> &Statement {
+        &self.then
+    }
+    pub fn get_then_mut(&mut self) -> &mut Statement {
+        &mut self.then
+    }
+    pub fn get_else(&self) -> &Option<Statement> {
+        &self.r#else
+    }
+    pub fn get_else_mut(&mut self) -> &mut Option<Statement> {
+        &mut self.r#else
+    }
 }
 
 impl Display for IfStatementNode {
@@ -937,27 +2238,46 @@ impl Display for IfStatementNode {
 }
 
 pub struct BlockStatementNode {
-    body: Vec<ASTNode>
+    body: Vec<ASTNode>,
+    spans: Vec<Option<Span>>
+}
+impl Default for BlockStatementNode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl BlockStatementNode {
     pub fn new() -> BlockStatementNode {
         Self {
-            body: Vec::new()
+            body: Vec::new(),
+            spans: Vec::new()
         }
     }
     pub fn add_node(&mut self, node: ASTNode) {
-        self.body.push(node)
+        self.body.push(node);
+        self.spans.push(None)
+    }
+    pub fn add_spanned_node(&mut self, node: ASTNode, span: Span) {
+        self.body.push(node);
+        self.spans.push(Some(span))
+    }
+    /// Span of the `i`-th node, `None` for nodes not read from source
+    pub fn get_span(&self, i: usize) -> Option<Span> {
+        self.spans.get(i).copied().flatten()
     }
     pub fn get_body(&self) -> &Vec<ASTNode> {
         &self.body
     }
+    pub fn get_body_mut(&mut self) -> &mut Vec<ASTNode> {
+        &mut self.body
+    }
 }
 impl Display for BlockStatementNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "BlockStatement[\n")?;
+        writeln!(f, "BlockStatement[")?;
         for (i, val) in self.body.iter().enumerate() {
             if i > 0 {
-                write!(f, "\n")?;
+                writeln!(f)?;
             }
             write!(f, "{}", val)?;
         }
@@ -974,6 +2294,9 @@ impl IntNode {
             value: OmniaInt::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaInt {
+        &self.value
+    }
 }
 impl ByteNode {
     pub fn new(value: i8) -> Self {
@@ -981,6 +2304,9 @@ impl ByteNode {
             value: OmniaByte::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaByte {
+        &self.value
+    }
 }
 impl LongNode {
     pub fn new(value: i64) -> Self {
@@ -988,6 +2314,9 @@ impl LongNode {
             value: OmniaLong::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaLong {
+        &self.value
+    }
 }
 impl DecimalNode {
     pub fn new(value: f64) -> Self {
@@ -995,6 +2324,9 @@ impl DecimalNode {
             value: OmniaDecimal::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaDecimal {
+        &self.value
+    }
 }
 impl OmniNode {
     pub fn new(value: f128) -> Self {
@@ -1002,6 +2334,9 @@ impl OmniNode {
             value: OmniaOmni::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaOmni {
+        &self.value
+    }
 }
 
 impl UByteNode {
@@ -1010,6 +2345,9 @@ impl UByteNode {
             value: OmniaUByte::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaUByte {
+        &self.value
+    }
 }
 impl UIntNode {
     pub fn new(value: u32) -> Self {
@@ -1017,6 +2355,9 @@ impl UIntNode {
             value: OmniaUInt::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaUInt {
+        &self.value
+    }
 }
 impl ULongNode {
     pub fn new(value: u64) -> Self {
@@ -1024,6 +2365,9 @@ impl ULongNode {
             value: OmniaULong::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaULong {
+        &self.value
+    }
 }
 impl CharNode {
     pub fn new(value: char) -> Self {
@@ -1031,6 +2375,9 @@ impl CharNode {
             value: OmniaChar::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaChar {
+        &self.value
+    }
 }
 impl CharArrNode {
     pub fn new(value: String) -> Self {
@@ -1038,6 +2385,9 @@ impl CharArrNode {
             value: OmniaChararr::get_from(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaChararr {
+        &self.value
+    }
 }
 impl <T: FromPrimitive + Clone> SpanNode<T> {
     pub fn new(value: Vec<T>) -> Self {
@@ -1045,4 +2395,7 @@ impl <T: FromPrimitive + Clone> SpanNode<T> {
             value: OmniaSpan::new(value)
         }
     }
+    pub fn get_value(&self) -> &OmniaSpan<T> {
+        &self.value
+    }
 }
@@ -0,0 +1,275 @@
+use crate::core::omnia_types::Type;
+use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, Expression, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, LambdaExpressionNode, Pattern, Statement, SwitchArmNode, VariantPayload, VariantValues};
+
+/// Read-only traversal of the AST. Every method defaults to the matching `walk_` function, which visits the children
+/// of the node, so an implementation overrides only the nodes it is interested in and calls `walk_` to keep descending
+pub trait Visitor {
+    fn visit_node(&mut self, node: &ASTNode) {
+        walk_node(self, node)
+    }
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement)
+    }
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+    fn visit_block(&mut self, block: &BlockStatementNode) {
+        walk_block(self, block)
+    }
+    fn visit_function_declaration(&mut self, function: &FunctionDeclarationStatementNode) {
+        walk_function_declaration(self, function)
+    }
+    fn visit_if(&mut self, r#if: &IfStatementNode) {
+        walk_if(self, r#if)
+    }
+    fn visit_lambda(&mut self, lambda: &LambdaExpressionNode) {
+        walk_lambda(self, lambda)
+    }
+    fn visit_switch_arm(&mut self, arm: &SwitchArmNode) {
+        walk_switch_arm(self, arm)
+    }
+    fn visit_generic_parameter(&mut self, generic: &GenericParameterNode) {
+        walk_generic_parameter(self, generic)
+    }
+    fn visit_type(&mut self, _type: &Type) {}
+}
+
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &ASTNode) {
+    match node {
+        ASTNode::Statement(s) => { visitor.visit_statement(s) }
+        ASTNode::Expression(e) => { visitor.visit_expression(e) }
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockStatementNode) {
+    for node in block.get_body() {
+        visitor.visit_node(node);
+    }
+}
+
+pub fn walk_function_declaration<V: Visitor + ?Sized>(visitor: &mut V, function: &FunctionDeclarationStatementNode) {
+    for generic in function.get_generics() {
+        visitor.visit_generic_parameter(generic);
+    }
+    for arg in function.get_args() {
+        visitor.visit_expression(arg);
+    }
+    visitor.visit_type(function.get_return_type());
+    visitor.visit_statement(function.get_body());
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, r#if: &IfStatementNode) {
+    visitor.visit_expression(r#if.get_cond());
+    visitor.visit_statement(r#if.get_then());
+    if let Some(e) = r#if.get_else() {
+        visitor.visit_statement(e);
+    }
+}
+
+pub fn walk_lambda<V: Visitor + ?Sized>(visitor: &mut V, lambda: &LambdaExpressionNode) {
+    for arg in lambda.get_args() {
+        visitor.visit_expression(arg);
+    }
+    visitor.visit_type(lambda.get_return_type());
+    visitor.visit_statement(lambda.get_body());
+}
+
+pub fn walk_switch_arm<V: Visitor + ?Sized>(visitor: &mut V, arm: &SwitchArmNode) {
+    if let Pattern::Literal(e) = arm.get_pattern() {
+        visitor.visit_expression(e);
+    }
+    visitor.visit_statement(arm.get_body());
+}
+
+pub fn walk_generic_parameter<V: Visitor + ?Sized>(visitor: &mut V, generic: &GenericParameterNode) {
+    for bound in generic.get_bounds() {
+        visitor.visit_type(bound);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::If(i) => { visitor.visit_if(i) }
+        Statement::Block(b) => { visitor.visit_block(b) }
+        Statement::Plug(_) => {}
+        Statement::FunctionDeclaration(f) => { visitor.visit_function_declaration(f) }
+        Statement::FunctionCall(c) => {
+            if let Some(args) = c.get_args() {
+                for arg in args {
+                    visitor.visit_node(arg);
+                }
+            }
+        }
+        Statement::Assignment(a) => { visitor.visit_expression(a.get_value()) }
+        Statement::VariableCreation(v) => {
+            if let Some(t) = v.get_type() {
+                visitor.visit_type(t);
+            }
+            visitor.visit_expression(v.get_value());
+        }
+        Statement::Return(r) => { visitor.visit_node(r.get_value()) }
+        Statement::LambdaDeclaration(l) => { visitor.visit_lambda(l.get_lambda()) }
+        Statement::StructDeclaration(s) => {
+            for generic in s.get_generics() {
+                visitor.visit_generic_parameter(generic);
+            }
+            for field in s.get_fields() {
+                visitor.visit_type(field.get_type());
+            }
+        }
+        Statement::EnumDeclaration(e) => {
+            for generic in e.get_generics() {
+                visitor.visit_generic_parameter(generic);
+            }
+            for variant in e.get_variants() {
+                match variant.get_payload() {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Tuple(types) => {
+                        for t in types {
+                            visitor.visit_type(t);
+                        }
+                    }
+                    VariantPayload::Struct(fields) => {
+                        for field in fields {
+                            visitor.visit_type(field.get_type());
+                        }
+                    }
+                }
+            }
+        }
+        Statement::Switch(s) => {
+            visitor.visit_expression(s.get_value());
+            for arm in s.get_arms() {
+                visitor.visit_switch_arm(arm);
+            }
+        }
+        Statement::InterfaceDeclaration(i) => {
+            for method in i.get_methods() {
+                for arg in method.get_args() {
+                    visitor.visit_expression(arg);
+                }
+                visitor.visit_type(method.get_return_type());
+            }
+        }
+        Statement::Extension(e) => {
+            visitor.visit_type(e.get_target());
+            for method in e.get_methods() {
+                visitor.visit_function_declaration(method.get_function());
+            }
+        }
+        Statement::MethodCall(m) => {
+            visitor.visit_expression(m.get_receiver());
+            for arg in m.get_args() {
+                visitor.visit_node(arg);
+            }
+        }
+        Statement::Propagate(p) => { visitor.visit_expression(p.get_value()) }
+        Statement::Unary(u) => { visitor.visit_expression(u.get_value()) }
+        Statement::Destructuring(d) => { visitor.visit_expression(d.get_value()) }
+        Statement::Declaration(d) => { visitor.visit_type(d.get_type()) }
+        Statement::Static(s) => {
+            visitor.visit_type(s.get_type());
+            visitor.visit_expression(s.get_value());
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Literal(_) | Expression::VariableAccess(_) => {}
+        Expression::Binary(b) => {
+            visitor.visit_expression(b.get_left());
+            visitor.visit_expression(b.get_right());
+        }
+        Expression::Comparative(c) => {
+            visitor.visit_expression(c.get_left());
+            visitor.visit_expression(c.get_right());
+        }
+        Expression::Logical(l) => {
+            visitor.visit_expression(l.get_left());
+            visitor.visit_expression(l.get_right());
+        }
+        Expression::Bitwise(b) => {
+            visitor.visit_expression(b.get_left());
+            visitor.visit_expression(b.get_right());
+        }
+        Expression::Unary(u) => { visitor.visit_expression(u.get_value()) }
+        Expression::FunctionCall(c) => {
+            if let Some(args) = c.get_args() {
+                for arg in args {
+                    visitor.visit_node(arg);
+                }
+            }
+        }
+        Expression::Argument(a) => { visitor.visit_type(a.get_type()) }
+        Expression::Lambda(l) => { visitor.visit_lambda(l) }
+        Expression::Ternary(t) => {
+            visitor.visit_expression(t.get_cond());
+            visitor.visit_expression(t.get_then());
+            visitor.visit_expression(t.get_else());
+        }
+        Expression::If(i) => { visitor.visit_if(i) }
+        Expression::Variant(v) => {
+            match v.get_values() {
+                VariantValues::Unit => {}
+                VariantValues::Tuple(values) => {
+                    for val in values {
+                        visitor.visit_expression(val);
+                    }
+                }
+                VariantValues::Struct(values) => {
+                    for (_, val) in values {
+                        visitor.visit_expression(val);
+                    }
+                }
+            }
+        }
+        Expression::MethodCall(m) => {
+            visitor.visit_expression(m.get_receiver());
+            for arg in m.get_args() {
+                visitor.visit_node(arg);
+            }
+        }
+        Expression::FieldAccess(f) => { visitor.visit_expression(f.get_value()) }
+        Expression::ResultValue(r) => { visitor.visit_expression(r.get_value()) }
+        Expression::Propagate(p) => { visitor.visit_expression(p.get_value()) }
+        Expression::Tuple(t) => {
+            for val in t.get_values() {
+                visitor.visit_expression(val);
+            }
+        }
+        Expression::Cast(c) => {
+            visitor.visit_expression(c.get_value());
+            visitor.visit_type(c.get_target());
+        }
+        Expression::LayoutQuery(q) => { visitor.visit_type(q.get_target()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Lexer;
+    use crate::parser::ast::nodes::Expression;
+    use crate::parser::ast::visit::{walk_expression, Visitor};
+    use crate::parser::Parser;
+
+    /// Names of the variables read, in the order they are visited
+    struct Reads(Vec<String>);
+    impl Visitor for Reads {
+        fn visit_expression(&mut self, expression: &Expression) {
+            if let Expression::VariableAccess(v) = expression {
+                self.0.push(v.get_name().clone())
+            }
+            walk_expression(self, expression)
+        }
+    }
+
+    #[test]
+    fn visitor_reaches_nested_expressions() {
+        let source = "func main() {\n    mk a = 1;\n    mk b = if a > 0 { a + 1 } else { 0 };\n    mk f = (x: int) -> int >> { x * b };\n}\n";
+        let root = Parser::new(Lexer::new(String::from(source)).tokenize()).parse();
+        let mut reads = Reads(Vec::new());
+        reads.visit_block(&root);
+        assert_eq!(reads.0, vec!["a", "a", "x", "b"]);
+    }
+}
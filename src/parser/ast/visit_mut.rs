@@ -0,0 +1,247 @@
+use crate::core::omnia_types::Type;
+use crate::parser::ast::nodes::{ASTNode, BlockStatementNode, Expression, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, LambdaExpressionNode, Pattern, Statement, SwitchArmNode, VariantPayload, VariantValues};
+
+/// In-place traversal of the AST, the mutable counterpart of [Visitor][crate::parser::ast::visit::Visitor].
+/// Methods default to the matching `walk_..._mut` function, which visits the children of the node
+pub trait VisitorMut {
+    fn visit_node_mut(&mut self, node: &mut ASTNode) {
+        walk_node_mut(self, node)
+    }
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+    fn visit_block_mut(&mut self, block: &mut BlockStatementNode) {
+        walk_block_mut(self, block)
+    }
+    fn visit_function_declaration_mut(&mut self, function: &mut FunctionDeclarationStatementNode) {
+        walk_function_declaration_mut(self, function)
+    }
+    fn visit_if_mut(&mut self, r#if: &mut IfStatementNode) {
+        walk_if_mut(self, r#if)
+    }
+    fn visit_lambda_mut(&mut self, lambda: &mut LambdaExpressionNode) {
+        walk_lambda_mut(self, lambda)
+    }
+    fn visit_switch_arm_mut(&mut self, arm: &mut SwitchArmNode) {
+        walk_switch_arm_mut(self, arm)
+    }
+    fn visit_generic_parameter_mut(&mut self, generic: &mut GenericParameterNode) {
+        walk_generic_parameter_mut(self, generic)
+    }
+    fn visit_type_mut(&mut self, _type: &mut Type) {}
+}
+
+pub fn walk_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ASTNode) {
+    match node {
+        ASTNode::Statement(s) => { visitor.visit_statement_mut(s) }
+        ASTNode::Expression(e) => { visitor.visit_expression_mut(e) }
+    }
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut BlockStatementNode) {
+    for node in block.get_body_mut() {
+        visitor.visit_node_mut(node);
+    }
+}
+
+pub fn walk_function_declaration_mut<V: VisitorMut + ?Sized>(visitor: &mut V, function: &mut FunctionDeclarationStatementNode) {
+    for generic in function.get_generics_mut() {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for arg in function.get_args_mut() {
+        visitor.visit_expression_mut(arg);
+    }
+    visitor.visit_type_mut(function.get_return_type_mut());
+    visitor.visit_statement_mut(function.get_body_mut());
+}
+
+pub fn walk_if_mut<V: VisitorMut + ?Sized>(visitor: &mut V, r#if: &mut IfStatementNode) {
+    visitor.visit_expression_mut(r#if.get_cond_mut());
+    visitor.visit_statement_mut(r#if.get_then_mut());
+    if let Some(e) = r#if.get_else_mut() {
+        visitor.visit_statement_mut(e);
+    }
+}
+
+pub fn walk_lambda_mut<V: VisitorMut + ?Sized>(visitor: &mut V, lambda: &mut LambdaExpressionNode) {
+    for arg in lambda.get_args_mut() {
+        visitor.visit_expression_mut(arg);
+    }
+    visitor.visit_type_mut(lambda.get_return_type_mut());
+    visitor.visit_statement_mut(lambda.get_body_mut());
+}
+
+pub fn walk_switch_arm_mut<V: VisitorMut + ?Sized>(visitor: &mut V, arm: &mut SwitchArmNode) {
+    if let Pattern::Literal(e) = arm.get_pattern_mut() {
+        visitor.visit_expression_mut(e);
+    }
+    visitor.visit_statement_mut(arm.get_body_mut());
+}
+
+pub fn walk_generic_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, generic: &mut GenericParameterNode) {
+    for bound in generic.get_bounds_mut() {
+        visitor.visit_type_mut(bound);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::If(i) => { visitor.visit_if_mut(i) }
+        Statement::Block(b) => { visitor.visit_block_mut(b) }
+        Statement::Plug(_) => {}
+        Statement::FunctionDeclaration(f) => { visitor.visit_function_declaration_mut(f) }
+        Statement::FunctionCall(c) => {
+            if let Some(args) = c.get_args_mut() {
+                for arg in args {
+                    visitor.visit_node_mut(arg);
+                }
+            }
+        }
+        Statement::Assignment(a) => { visitor.visit_expression_mut(a.get_value_mut()) }
+        Statement::VariableCreation(v) => {
+            if let Some(t) = v.get_type_mut() {
+                visitor.visit_type_mut(t);
+            }
+            visitor.visit_expression_mut(v.get_value_mut());
+        }
+        Statement::Return(r) => { visitor.visit_node_mut(r.get_value_mut()) }
+        Statement::LambdaDeclaration(l) => { visitor.visit_lambda_mut(l.get_lambda_mut()) }
+        Statement::StructDeclaration(s) => {
+            for generic in s.get_generics_mut() {
+                visitor.visit_generic_parameter_mut(generic);
+            }
+            for field in s.get_fields_mut() {
+                visitor.visit_type_mut(field.get_type_mut());
+            }
+        }
+        Statement::EnumDeclaration(e) => {
+            for generic in e.get_generics_mut() {
+                visitor.visit_generic_parameter_mut(generic);
+            }
+            for variant in e.get_variants_mut() {
+                match variant.get_payload_mut() {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Tuple(types) => {
+                        for t in types {
+                            visitor.visit_type_mut(t);
+                        }
+                    }
+                    VariantPayload::Struct(fields) => {
+                        for field in fields {
+                            visitor.visit_type_mut(field.get_type_mut());
+                        }
+                    }
+                }
+            }
+        }
+        Statement::Switch(s) => {
+            visitor.visit_expression_mut(s.get_value_mut());
+            for arm in s.get_arms_mut() {
+                visitor.visit_switch_arm_mut(arm);
+            }
+        }
+        Statement::InterfaceDeclaration(i) => {
+            for method in i.get_methods_mut() {
+                for arg in method.get_args_mut() {
+                    visitor.visit_expression_mut(arg);
+                }
+                visitor.visit_type_mut(method.get_return_type_mut());
+            }
+        }
+        Statement::Extension(e) => {
+            visitor.visit_type_mut(e.get_target_mut());
+            for method in e.get_methods_mut() {
+                visitor.visit_function_declaration_mut(method.get_function_mut());
+            }
+        }
+        Statement::MethodCall(m) => {
+            visitor.visit_expression_mut(m.get_receiver_mut());
+            for arg in m.get_args_mut() {
+                visitor.visit_node_mut(arg);
+            }
+        }
+        Statement::Propagate(p) => { visitor.visit_expression_mut(p.get_value_mut()) }
+        Statement::Unary(u) => { visitor.visit_expression_mut(u.get_value_mut()) }
+        Statement::Destructuring(d) => { visitor.visit_expression_mut(d.get_value_mut()) }
+        Statement::Declaration(d) => { visitor.visit_type_mut(d.get_type_mut()) }
+        Statement::Static(s) => {
+            visitor.visit_type_mut(s.get_type_mut());
+            visitor.visit_expression_mut(s.get_value_mut());
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Literal(_) | Expression::VariableAccess(_) => {}
+        Expression::Binary(b) => {
+            visitor.visit_expression_mut(b.get_left_mut());
+            visitor.visit_expression_mut(b.get_right_mut());
+        }
+        Expression::Comparative(c) => {
+            visitor.visit_expression_mut(c.get_left_mut());
+            visitor.visit_expression_mut(c.get_right_mut());
+        }
+        Expression::Logical(l) => {
+            visitor.visit_expression_mut(l.get_left_mut());
+            visitor.visit_expression_mut(l.get_right_mut());
+        }
+        Expression::Bitwise(b) => {
+            visitor.visit_expression_mut(b.get_left_mut());
+            visitor.visit_expression_mut(b.get_right_mut());
+        }
+        Expression::Unary(u) => { visitor.visit_expression_mut(u.get_value_mut()) }
+        Expression::FunctionCall(c) => {
+            if let Some(args) = c.get_args_mut() {
+                for arg in args {
+                    visitor.visit_node_mut(arg);
+                }
+            }
+        }
+        Expression::Argument(a) => { visitor.visit_type_mut(a.get_type_mut()) }
+        Expression::Lambda(l) => { visitor.visit_lambda_mut(l) }
+        Expression::Ternary(t) => {
+            visitor.visit_expression_mut(t.get_cond_mut());
+            visitor.visit_expression_mut(t.get_then_mut());
+            visitor.visit_expression_mut(t.get_else_mut());
+        }
+        Expression::If(i) => { visitor.visit_if_mut(i) }
+        Expression::Variant(v) => {
+            match v.get_values_mut() {
+                VariantValues::Unit => {}
+                VariantValues::Tuple(values) => {
+                    for val in values {
+                        visitor.visit_expression_mut(val);
+                    }
+                }
+                VariantValues::Struct(values) => {
+                    for (_, val) in values {
+                        visitor.visit_expression_mut(val);
+                    }
+                }
+            }
+        }
+        Expression::MethodCall(m) => {
+            visitor.visit_expression_mut(m.get_receiver_mut());
+            for arg in m.get_args_mut() {
+                visitor.visit_node_mut(arg);
+            }
+        }
+        Expression::FieldAccess(f) => { visitor.visit_expression_mut(f.get_value_mut()) }
+        Expression::ResultValue(r) => { visitor.visit_expression_mut(r.get_value_mut()) }
+        Expression::Propagate(p) => { visitor.visit_expression_mut(p.get_value_mut()) }
+        Expression::Tuple(t) => {
+            for val in t.get_values_mut() {
+                visitor.visit_expression_mut(val);
+            }
+        }
+        Expression::Cast(c) => {
+            visitor.visit_expression_mut(c.get_value_mut());
+            visitor.visit_type_mut(c.get_target_mut());
+        }
+        Expression::LayoutQuery(q) => { visitor.visit_type_mut(q.get_target_mut()) }
+    }
+}
@@ -1,47 +1,62 @@
 pub mod ast;
 
-use std::collections::VecDeque;
-use std::process::exit;
-use crate::core::omnia_types::Type;
+use crate::core::omnia_types::{ArraySize, CastMode, Type};
 use crate::core::omnia_types::Type::NULL;
 use crate::core::utils::numeric_utils::omni::f128;
 use crate::core::utils::stringutils::StringBuilder;
-use crate::lexer::token::{Token, TokenType};
-use crate::lexer::token::TokenType::{AMPERSAND, AND, ANDASSIGN, ARROW, ASSIGN, BOOL, BOOLKW, BYTE, BYTEKW, CHAR, CHARARR, CHARKW, COLON, COMMA, DEC, DECIMAL, DECIMALKW, DIVASSIGN, ELSE, EOF, EQ, EXT, FUNC, GEQ, GT, IDENT, IF, INC, INT, INTKW, LAMBDA, LBRACE, LEQ, LONG, LONGKW, LPAREN, LS, MINUS, MINUSASSIGN, MK, MULASSIGN, NEQ, NULLKW, OMNI, OMNIKW, OR, ORASSIGN, PIPE, PLUG, PLUS, PLUSASSIGN, POWER, RBRACE, REM, REMASSIGN, RETURN, RPAREN, SEMICOLON, SHL, SHR, SLASH, STAR, STRUCT, UBYTE, UBYTEKW, UINT, UINTKW, ULONG, ULONGKW, VISIBLE, XOR};
-use crate::parser::ast::nodes;
-use crate::parser::ast::nodes::{ASTNode, ArgumentExpressionNode, AssignmentOperator, AssignmentStatementNode, BinaryExpressionNode, BinaryOperation, BitwiseExpressionNode, BitwiseOperation, BlockStatementNode, ByteNode, CharArrNode, CharNode, ComparativeExpressionNode, ConditionalOperation, DecimalNode, Expression, FieldExpressionNode, FunctionCallNode, FunctionDeclarationStatementNode, IfStatementNode, IntNode, LambdaDeclarationStatementNode, LiteralExpression, LogicalExpressionNode, LogicalOperation, LongNode, OmniNode, PlugStatementNode, ReturnStatementNode, Statement, StructDeclarationStatementNode, UByteNode, UIntNode, ULongNode, UnaryExpressionNode, UnaryOperation, VariableAccessExpressionNode, VariableCreationStatementNode};
+use crate::lexer::token::{Span, Token, TokenType};
+use crate::lexer::token::TokenType::{ACCESS, ALIGNOF, AMPERSAND, AND, ANDASSIGN, ARROW, AS, ASSIGN, AT, BOOLKW, BYTE, BYTEKW, CHAR, CHARARR, CHARARRKW, CHARKW, COLON, COMMA, DEC, DECIMAL, DECIMALKW, DIVASSIGN, ELSE, ENUM, EOF, EQ, ERR, EXT, FEQ, FOR, FUNC, GEQ, GT, IDENT, IF, INC, INT, INTERFACE, INTKW, LAMBDA, LBRACE, LBRACK, LEQ, LONG, LONGKW, LPAREN, LS, MINUS, MINUSASSIGN, MK, MULASSIGN, MUT, NEQ, NOT, NULLKW, OK, OMNI, OMNIKW, OR, ORASSIGN, OVERRIDE, PERIOD, PIPE, PLUG, PLUS, PLUSASSIGN, POWER, QUESTION, RBRACE, RBRACK, REM, REMASSIGN, RETURN, RPAREN, SEMICOLON, SHL, SHR, SIZEOF, SLASH, STAR, STATIC, STRUCT, SWITCH, UBYTE, UBYTEKW, UINT, UINTKW, ULONG, ULONGKW, UNDERSCORE, VISIBLE, XOR};
+use crate::parser::ast::nodes::{ASTNode, ArgumentExpressionNode, AssignmentOperator, AttributeNode, AssignmentStatementNode, BinaryExpressionNode, BinaryOperation, BitwiseExpressionNode, BitwiseOperation, BlockStatementNode, ByteNode, CastExpressionNode, CharArrNode, CharNode, ComparativeExpressionNode, ConditionalOperation, DecimalNode, DeclarationStatementNode, DestructuringStatementNode, EnumDeclarationStatementNode, EnumVariantNode, Expression, ExtensionMethodNode, ExtensionStatementNode, FieldAccessExpressionNode, FieldExpressionNode, FunctionCallNode, FunctionDeclarationStatementNode, GenericParameterNode, IfStatementNode, IntNode, InterfaceDeclarationStatementNode, LambdaDeclarationStatementNode, LambdaExpressionNode, LayoutQuery, LayoutQueryExpressionNode, LiteralExpression, LogicalExpressionNode, LogicalOperation, LongNode, MethodCallNode, MethodSignatureNode, OmniNode, Pattern, PropagateExpressionNode, ResultExpressionNode, PatternBindings, PlugStatementNode, ReturnStatementNode, Statement, StaticDeclarationStatementNode, StructDeclarationStatementNode, SwitchArmNode, SwitchStatementNode, TernaryExpressionNode, TupleExpressionNode, UByteNode, UIntNode, ULongNode, UnaryExpressionNode, UnaryOperation, VariableAccessExpressionNode, VariableCreationStatementNode, VariantExpressionNode, VariantPayload, VariantValues};
 
 
 pub struct Parser {
     tokens: Vec<Token>,
     pos: usize,
     len: usize,
+    #[allow(dead_code)]
     output: Option<BlockStatementNode>,
-    buffer: Vec<Token>
+    buffer: Vec<Token>,
+    lambdas: usize,
+    bindings: usize,
+    generics: Vec<String>,
+    /// Prints every token and failed match while parsing
+    trace: bool
 }
 
 impl Parser {
-    pub fn new(mut tokens: Vec<Token>) -> Parser {
-        let mut pos = 0usize;
-        let mut len = (&tokens).len();
+    pub fn new(tokens: Vec<Token>) -> Parser {
+        let pos = 0usize;
+        let len = tokens.len();
         Self {
             tokens,
             pos,
             len,
             output: None,
-            buffer: Vec::new()
+            buffer: Vec::new(),
+            lambdas: 0,
+            bindings: 0,
+            generics: Vec::new(),
+            trace: false
         }
     }
+    /// Turns on the trace of the parser, which is silent by default
+    pub fn with_trace(mut self, trace: bool) -> Parser {
+        self.trace = trace;
+        self
+    }
     pub fn parse(&mut self) -> BlockStatementNode {
         let mut root = BlockStatementNode::new();
 
         while self.has_next_token() {
-            root.add_node(ASTNode::Statement(self.statement()))
+            let statement = self.spanned_statement();
+            root.add_spanned_node(ASTNode::Statement(statement.0), statement.1)
         }
         root
     }
     fn statement(&mut self) -> Statement {
-        println!("Processing token {}", self.get_cur());
+        if self.trace {
+            println!("Processing token {}", self.get_cur());
+        }
         self.buffer.push(self.get_cur());
         if self.r#match(&DECIMALKW)
             || self.r#match(&BYTEKW)
@@ -52,11 +67,15 @@ impl Parser {
             || self.r#match(&ULONGKW)
             || self.r#match(&CHARKW)
             || self.r#match(&BOOLKW)
+            || self.r#match(&CHARARRKW)
             || self.r#match(&MK) {
             let statement = self.variable_creation_statement();
             self.require(&SEMICOLON);
             return statement
         }
+        if self.r#match(&AT) {
+            return self.attributed_declaration_statement()
+        }
         if self.r#match(&VISIBLE) {
             if self.r#match(&FUNC) {
                 return self.function_declaration_statement(true)
@@ -64,6 +83,12 @@ impl Parser {
             if self.r#match(&STRUCT) {
                 return self.struct_declaration_statement(true)
             }
+            if self.r#match(&ENUM) {
+                return self.enum_declaration_statement(true)
+            }
+            if self.r#match(&INTERFACE) {
+                return self.interface_declaration_statement(true)
+            }
             // if self.r#match(&EXT) {
             //     if self.r#match(&FUNC) {
             //
@@ -76,9 +101,17 @@ impl Parser {
                 panic!("OmniaParser error:: you can use `visible` keyword only before structs, functions or ext functions declarations")
             }
         }
+        if self.r#match(&STATIC) {
+            if self.r#match(&FUNC) {  // static func main(&string[] args)
+                return Statement::FunctionDeclaration(Box::from(self.function_declaration(false, false).with_static(true)))
+            }
+            let statement = self.static_declaration_statement();
+            self.require(&SEMICOLON);
+            return statement
+        }
         if self.r#match(&RETURN) {
             let statement = self.return_statement();
-            self.require(&SEMICOLON);
+            self.end_of_statement();
             return statement
         }
         if self.r#match(&PLUG) {
@@ -90,12 +123,30 @@ impl Parser {
         if self.r#match(&IF) {
             return self.if_statement()
         }
+        if self.r#match(&SWITCH) {
+            return self.switch_statement()
+        }
+        if self.r#match(&ENUM) {
+            return self.enum_declaration_statement(false)
+        }
+        if self.r#match(&INTERFACE) {
+            return self.interface_declaration_statement(false)
+        }
+        if self.r#match(&EXT) {
+            return self.extension_statement()
+        }
         if self.r#match(&FUNC) {
             return self.function_declaration_statement(false)
         }
         if self.r#match(&IDENT) {
             return self.call_or_return_or_assignment()
         }
+        if self.match_any(vec![OK, ERR, INT, UINT, LONG, ULONG, BYTE, UBYTE, DECIMAL, OMNI, CHAR, CHARARR, NULLKW, LPAREN, SIZEOF, ALIGNOF]) {  // trailing value, e.g. ok(x) or 0
+            self.pos -= 1;
+            self.buffer.pop();
+            let value = self.expression();
+            return Statement::Return(Box::from(ReturnStatementNode::tail(ASTNode::Expression(value))))
+        }
         if self.r#match(&STRUCT) {
             self.struct_declaration_statement(false)
         }
@@ -103,6 +154,13 @@ impl Parser {
             panic!("Unexpected token or use of an expression as a command [{}] at pos {}", self.buffer.pop().unwrap().t_type, self.pos)
         }
     }
+    /// A statement with the span from its first token to the last one it consumed
+    fn spanned_statement(&mut self) -> (Statement, Span) {
+        let start = self.get_cur().t_pos;
+        let statement = self.statement();
+        let end = self.tokens.get(self.pos - 1).map(|x| x.t_end).unwrap_or(start);
+        (statement, Span::new(start, end))
+    }
     fn block(&mut self) -> Statement {
         let mut statements: BlockStatementNode = BlockStatementNode::new();
         loop {
@@ -112,13 +170,15 @@ impl Parser {
             if self.r#match(&RBRACE) {
                 break
             }
-            statements.add_node(ASTNode::Statement(self.statement()))
+            let statement = self.spanned_statement();
+            statements.add_spanned_node(ASTNode::Statement(statement.0), statement.1)
         }
         Statement::Block(Box::from(statements))
     }
 
     fn struct_declaration_statement(&mut self, is_visible: bool) -> Statement {
         let name = self.require(&IDENT).t_value.clone();
+        let generics = self.generic_parameters();
         let mut fields: Vec<FieldExpressionNode> = Vec::new();
         self.require(&LBRACE);
         loop {
@@ -127,53 +187,145 @@ impl Parser {
             }
             let field_name = self.require(&IDENT).t_value.clone();
             self.require(&COLON);
-            let r#type = Type::from_token_type(&self.require_any(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, CHARKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW]).t_type).unwrap();
+            let r#type = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, CHARKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW]);
             fields.push(FieldExpressionNode::new(field_name, r#type));
             if self.r#match(&RBRACE) {
                 break
             }
             self.require(&COMMA);
         }
-        Statement::StructDeclaration(Box::from(StructDeclarationStatementNode::new(name, fields, is_visible)))
+        self.generics.truncate(self.generics.len() - generics.len());
+        Statement::StructDeclaration(Box::from(StructDeclarationStatementNode::new(name, generics, fields, is_visible)))
     }
-    fn if_statement(&mut self) -> Statement {
-        let condition = self.expression();
-        let then = self.statement();
-        if self.r#match(&ELSE) {
-            let r#else = self.statement();
-            return Statement::If(Box::from(IfStatementNode::new(condition, then, Some(r#else))))
+    fn enum_declaration_statement(&mut self, is_visible: bool) -> Statement {
+        let name = self.require(&IDENT).t_value.clone();
+        let generics = self.generic_parameters();
+        let mut variants: Vec<EnumVariantNode> = Vec::new();
+        self.require(&LBRACE);
+        loop {
+            if self.r#match(&RBRACE) {
+                break
+            }
+            let variant = self.require(&IDENT).t_value.clone();
+            let payload = if self.r#match(&LPAREN) {  // Circle(decimal)
+                let mut types: Vec<Type> = Vec::new();
+                loop {
+                    if self.r#match(&RPAREN) {
+                        break
+                    }
+                    types.push(self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, CHARKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, BOOLKW]));
+                    if self.r#match(&RPAREN) {
+                        break
+                    }
+                    self.require(&COMMA);
+                }
+                VariantPayload::Tuple(types)
+            } else if self.r#match(&LBRACE) {  // Rect { w: int, h: int }
+                let mut fields: Vec<FieldExpressionNode> = Vec::new();
+                loop {
+                    if self.r#match(&RBRACE) {
+                        break
+                    }
+                    let field_name = self.require(&IDENT).t_value.clone();
+                    self.require(&COLON);
+                    let r#type = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, CHARKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, BOOLKW]);
+                    fields.push(FieldExpressionNode::new(field_name, r#type));
+                    if self.r#match(&RBRACE) {
+                        break
+                    }
+                    self.require(&COMMA);
+                }
+                VariantPayload::Struct(fields)
+            } else {
+                VariantPayload::Unit
+            };
+            variants.push(EnumVariantNode::new(variant, payload));
+            if self.r#match(&RBRACE) {
+                break
+            }
+            self.require(&COMMA);
         }
-        Statement::If(Box::from(IfStatementNode::new(condition, then, None)))
+        self.generics.truncate(self.generics.len() - generics.len());
+        Statement::EnumDeclaration(Box::from(EnumDeclarationStatementNode::new(name, generics, variants, is_visible)))
     }
-    fn call_or_return_or_assignment(&mut self) -> Statement {
-        let buffered = self.buffer.pop().unwrap();
-        self.buffer.push(self.get_cur());
-        if self.r#match(&LPAREN) {
-            self.buffer.pop();
-            let name = buffered.t_value.clone();
-            if self.r#match(&RPAREN) {
-                return Statement::FunctionCall(Box::from(FunctionCallNode::new(name, None)))
+    fn switch_statement(&mut self) -> Statement {
+        self.buffer.pop();
+        let value = self.expression();
+        let mut arms: Vec<SwitchArmNode> = Vec::new();
+        self.require(&LBRACE);
+        loop {
+            if self.r#match(&RBRACE) {
+                break
             }
-            let mut args: Vec<ASTNode> = Vec::new();
+            let pattern = self.pattern();
+            self.require(&ARROW);
+            let body = self.statement();
+            arms.push(SwitchArmNode::new(pattern, body));
+        }
+        Statement::Switch(Box::from(SwitchStatementNode::new(value, arms)))
+    }
+    fn pattern(&mut self) -> Pattern {
+        if self.r#match(&UNDERSCORE) {
+            return Pattern::Wildcard
+        }
+        let is_ok = matches!(self.peek(0).map(|x| &x.t_type), Some(&OK));
+        if self.r#match(&OK) || self.r#match(&ERR) {  // ok(value), err(e)
+            self.require(&LPAREN);
+            let name = self.require_any(vec![IDENT, UNDERSCORE]).t_value.clone();
+            self.require(&RPAREN);
+            return if is_ok { Pattern::Ok(name) } else { Pattern::Err(name) }
+        }
+        let is_variant = matches!((self.peek(0).map(|x| &x.t_type), self.peek(1).map(|x| &x.t_type)), (Some(&IDENT), Some(&ACCESS)));
+        if !is_variant {
+            return Pattern::Literal(self.expression())
+        }
+        let r#enum = self.require(&IDENT).t_value.clone();
+        self.require(&ACCESS);
+        let variant = self.require(&IDENT).t_value.clone();
+        if self.r#match(&LPAREN) {  // Circle(r)
+            let mut names: Vec<String> = Vec::new();
             loop {
                 if self.r#match(&RPAREN) {
                     break
                 }
-                let arg = self.expression();
-                args.push(ASTNode::Expression(arg));
+                names.push(self.require_any(vec![IDENT, UNDERSCORE]).t_value.clone());
                 if self.r#match(&RPAREN) {
                     break
                 }
                 self.require(&COMMA);
-
             }
-            if !self.r#match(&RBRACE) {
-                self.require(&SEMICOLON);
-            } else {
-                self.pos -= 1;
+            return Pattern::Variant(r#enum, variant, PatternBindings::Tuple(names))
+        }
+        if self.r#match(&LBRACE) {  // Rect { w, h: height }
+            let mut fields: Vec<(String, String)> = Vec::new();
+            loop {
+                if self.r#match(&RBRACE) {
+                    break
+                }
+                let field = self.require(&IDENT).t_value.clone();
+                let name = if self.r#match(&COLON) { self.require_any(vec![IDENT, UNDERSCORE]).t_value.clone() } else { field.clone() };
+                fields.push((field, name));
+                if self.r#match(&RBRACE) {
+                    break
+                }
+                self.require(&COMMA);
             }
-            return Statement::FunctionCall(Box::from(FunctionCallNode::new(name, Some(args))))
+            return Pattern::Variant(r#enum, variant, PatternBindings::Struct(fields))
+        }
+        Pattern::Variant(r#enum, variant, PatternBindings::Unit)
+    }
+    fn if_statement(&mut self) -> Statement {
+        let condition = self.expression();
+        let then = self.statement();
+        if self.r#match(&ELSE) {
+            let r#else = self.statement();
+            return Statement::If(Box::from(IfStatementNode::new(condition, then, Some(r#else))))
         }
+        Statement::If(Box::from(IfStatementNode::new(condition, then, None)))
+    }
+    fn call_or_return_or_assignment(&mut self) -> Statement {
+        let buffered = self.buffer.pop().unwrap();
+        self.buffer.push(self.get_cur());
         if self.match_any(vec![ASSIGN, PLUSASSIGN, MINUSASSIGN, MULASSIGN, DIVASSIGN, REMASSIGN, ANDASSIGN, ORASSIGN]) {
             let name = buffered.t_value.clone();
             let op = &self.buffer.pop().unwrap().t_type;
@@ -182,23 +334,161 @@ impl Parser {
             return Statement::Assignment(Box::from(AssignmentStatementNode::new(name, AssignmentOperator::from_token_type(op).unwrap(), value)))
         }
         // self.require(&SEMICOLON);
+        self.buffer.pop();
         self.pos -= 1;
         let expr = self.expression();
-        Statement::Return(Box::from(ReturnStatementNode::new(ASTNode::Expression(expr))))
+        if let Expression::FunctionCall(call) = expr {  // add(a, b); or the value of the block, e.g. { add(a, b) }
+            if self.end_of_statement() {
+                return Statement::FunctionCall(call)
+            }
+            return Statement::Return(Box::from(ReturnStatementNode::tail(ASTNode::Expression(Expression::FunctionCall(call)))))
+        }
+        if let Expression::MethodCall(call) = expr {
+            if self.end_of_statement() {
+                return Statement::MethodCall(call)
+            }
+            return Statement::Return(Box::from(ReturnStatementNode::tail(ASTNode::Expression(Expression::MethodCall(call)))))
+        }
+        if let Expression::Propagate(value) = expr {
+            self.end_of_statement();
+            return Statement::Propagate(value)
+        }
+        if let Expression::Unary(value) = expr {  // x++;
+            if self.end_of_statement() {
+                return Statement::Unary(value)
+            }
+            return Statement::Return(Box::from(ReturnStatementNode::tail(ASTNode::Expression(Expression::Unary(value)))))
+        }
+        Statement::Return(Box::from(ReturnStatementNode::tail(ASTNode::Expression(expr))))
+    }
+    /// Parses `@name` or `@name("argument")` attributes and attaches them to the declaration that follows
+    fn attributed_declaration_statement(&mut self) -> Statement {
+        self.buffer.pop();
+        let mut attributes: Vec<AttributeNode> = Vec::new();
+        loop {
+            let name = self.require(&IDENT).t_value.clone();
+            let mut argument = None;
+            if self.r#match(&LPAREN) {
+                argument = Some(self.require(&CHARARR).t_value.clone());
+                self.require(&RPAREN);
+            }
+            attributes.push(AttributeNode::new(name, argument));
+            if !self.r#match(&AT) {
+                break
+            }
+        }
+        match self.statement() {
+            Statement::FunctionDeclaration(f) => { Statement::FunctionDeclaration(Box::from(f.with_attributes(attributes))) }
+            Statement::StructDeclaration(s) => { Statement::StructDeclaration(Box::from(s.with_attributes(attributes))) }
+            other => { panic!("OmniaParser error:: attributes can only be applied to function and struct declarations, found {}", other) }
+        }
     }
     fn function_declaration_statement(&mut self, is_visible: bool) -> Statement {
-        let mut name = self.require(&IDENT);
+        Statement::FunctionDeclaration(Box::from(self.function_declaration(is_visible, false)))
+    }
+    /// With `for_target` the parameter list is followed by `for Type`, as in `ext func new(x: int) for Point -> Point`
+    fn function_declaration(&mut self, is_visible: bool, for_target: bool) -> FunctionDeclarationStatementNode {
+        let name = self.require(&IDENT);
+        let generics = self.generic_parameters();
         self.require(&LPAREN);
-        let args = self.arguments();
-        if self.r#match(&ARROW) {  // returns type
-            let ret_type = self.require_any(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW, NULLKW]);
-            let body = self.statement();
-            Statement::FunctionDeclaration(Box::from(FunctionDeclarationStatementNode::new(name.t_value, args, Type::from_token_type(&ret_type.t_type).unwrap(), body, is_visible)))
+        let mut args = self.arguments();
+        if for_target {
+            self.require(&FOR);
+            let target = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW]);
+            args.insert(0, Expression::Argument(Box::from(ArgumentExpressionNode::new(String::from("self"), target, false))));
+        }
+        let ret_type = if self.r#match(&ARROW) {  // returns type
+            self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW, NULLKW])
         } else {
-            let body = self.statement();
-            Statement::FunctionDeclaration(Box::from(FunctionDeclarationStatementNode::new(name.t_value, args, NULL, body, is_visible)))
+            NULL
+        };
+        let body = self.statement();
+        self.generics.truncate(self.generics.len() - generics.len());
+        FunctionDeclarationStatementNode::new(name.t_value, generics, args, ret_type, body, is_visible)
+    }
+    fn interface_declaration_statement(&mut self, is_visible: bool) -> Statement {
+        let name = self.require(&IDENT).t_value.clone();
+        let mut methods: Vec<MethodSignatureNode> = Vec::new();
+        self.require(&LBRACE);
+        loop {
+            if self.r#match(&RBRACE) {
+                break
+            }
+            self.require(&FUNC);
+            let method = self.require(&IDENT).t_value.clone();
+            self.require(&LPAREN);
+            let args = self.arguments();
+            let returns = if self.r#match(&ARROW) {
+                self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW, NULLKW])
+            } else {
+                NULL
+            };
+            self.require(&SEMICOLON);
+            methods.push(MethodSignatureNode::new(method, args, returns));
         }
-
+        Statement::InterfaceDeclaration(Box::from(InterfaceDeclarationStatementNode::new(name, methods, is_visible)))
+    }
+    fn extension_statement(&mut self) -> Statement {
+        if self.r#match(&FUNC) {  // ext func area() for Point -> decimal { ... }
+            let function = self.function_declaration(false, true);
+            let target = match function.get_args().first() {
+                Some(Expression::Argument(a)) => { a.get_type().clone() }
+                _ => { unreachable!() }
+            };
+            return Statement::Extension(Box::from(ExtensionStatementNode::new(None, target, vec![ExtensionMethodNode::new(function, false)])))
+        }
+        let interface = self.require(&IDENT).t_value.clone();  // ext Shape for Point { override func area() -> decimal { ... } }
+        self.require(&FOR);
+        let target = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW]);
+        let mut methods: Vec<ExtensionMethodNode> = Vec::new();
+        self.require(&LBRACE);
+        loop {
+            if self.r#match(&RBRACE) {
+                break
+            }
+            let is_override = self.r#match(&OVERRIDE);
+            self.require(&FUNC);
+            let function = self.function_declaration(false, false);
+            methods.push(ExtensionMethodNode::new(function.with_receiver(target.clone()), is_override));
+        }
+        Statement::Extension(Box::from(ExtensionStatementNode::new(Some(interface), target, methods)))
+    }
+    /// Parses `<A, B>` after a declaration name; the parameters stay in scope until the declaration ends
+    fn generic_parameters(&mut self) -> Vec<GenericParameterNode> {
+        let mut generics: Vec<GenericParameterNode> = Vec::new();
+        if !self.r#match(&LS) {
+            return generics
+        }
+        loop {
+            let name = self.require(&IDENT).t_value.clone();
+            let mut bounds: Vec<Type> = Vec::new();
+            if self.r#match(&COLON) {  // T: Shape + Printable
+                loop {
+                    bounds.push(Type::CUSTOM(self.require(&IDENT).t_value.clone(), Vec::new()));
+                    if !self.r#match(&PLUS) {
+                        break
+                    }
+                }
+            }
+            self.generics.push(name.clone());
+            generics.push(GenericParameterNode::new(name, bounds));
+            if self.close_generic() {
+                break
+            }
+            self.require(&COMMA);
+        }
+        generics
+    }
+    /// Consumes a closing `>` of a generic list. `>>` is lexed as a single token, so it is split in two
+    fn close_generic(&mut self) -> bool {
+        if let Some(cur) = self.tokens.get_mut(self.pos) {
+            if cur.t_type == LAMBDA {
+                cur.t_type = GT;
+                cur.t_value = String::from(">");
+                return true
+            }
+        }
+        self.r#match(&GT)
     }
     fn arguments(&mut self) -> Vec<Expression> {
         let mut args: Vec<Expression> = Vec::new();
@@ -206,10 +496,17 @@ impl Parser {
             if self.r#match(&RPAREN) {
                 break
             }
-            let name = self.require(&IDENT).t_value.clone();
-            self.require(&COLON);
-            let r#type = self.require_any(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW]);
-            args.push(Expression::Argument(Box::from(ArgumentExpressionNode::new(name, Type::from_token_type(&r#type.t_type).unwrap()))));
+            let mutable = self.r#match(&MUT);
+            let allowed = vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, CHARARRKW, BOOLKW];
+            let (name, r#type) = if matches!(self.peek(1).map(|x| &x.t_type), Some(&COLON)) {  // x: int
+                let name = self.require(&IDENT).t_value.clone();
+                self.require(&COLON);
+                (name, self.r#type(allowed))
+            } else {  // the type first, as in C, e.g. int x or &string[] args
+                let r#type = self.r#type(allowed);
+                (self.require(&IDENT).t_value.clone(), r#type)
+            };
+            args.push(Expression::Argument(Box::from(ArgumentExpressionNode::new(name, r#type, mutable))));
             if self.r#match(&RPAREN) {
                 break
             }
@@ -217,52 +514,204 @@ impl Parser {
         }
         args
     }
-
-    fn variable_creation_statement(&mut self) -> Statement {
-        let buffered = self.buffer.pop();
-        let r#type = &buffered.unwrap().t_type;
-        let name = self.require(&IDENT);
-        self.require(&ASSIGN);
-        if self.r#match(&LPAREN) {
-            if r#type != &MK {
-                panic!("OmniaParser error:: cannot declare a lambda in a variable with specified type")
+    fn r#type(&mut self, allowed: Vec<TokenType>) -> Type {
+        let mut base = self.base_type(allowed.clone());
+        while self.r#match(&LBRACK) {  // array, e.g. int[4] or int[MAX]
+            if self.r#match(&RBRACK) {  // unsized array, e.g. &char[][]
+                base = Type::ARRAY(Box::from(base), ArraySize::Unsized);
+                continue
             }
-            let mut args: Vec<Expression> = Vec::new();
+            let size = self.require_any(vec![INT, IDENT]);
+            let size = match size.t_type {
+                INT => ArraySize::Fixed(size.t_value.parse::<u64>().expect("OmniaParser error:: invalid array size")),
+                _ => ArraySize::Constant(size.t_value.clone())
+            };
+            self.require(&RBRACK);
+            base = Type::ARRAY(Box::from(base), size)
+        }
+        if self.r#match(&QUESTION) {  // nullable, e.g. int?
+            base = base.nullable()
+        }
+        if self.r#match(&NOT) {  // result, e.g. decimal ! ParseError
+            return Type::RESULT(Box::from(base), Box::from(self.base_type(allowed)))
+        }
+        base
+    }
+    fn base_type(&mut self, allowed: Vec<TokenType>) -> Type {
+        if self.r#match(&AMPERSAND) {  // reference, e.g. &char[][]
+            return Type::REFERENCE(Box::from(self.r#type(allowed)))
+        }
+        if self.r#match(&LPAREN) {  // function type, e.g. (int, int) -> int, or tuple, e.g. (int, long)
+            let mut params: Vec<Type> = Vec::new();
             loop {
                 if self.r#match(&RPAREN) {
                     break
                 }
-                let name = self.require(&IDENT).t_value.clone();
-                self.require(&COLON);
-                let r#type = self.require_any(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW]);
-                args.push(Expression::Argument(Box::from(ArgumentExpressionNode::new(name, Type::from_token_type(&r#type.t_type).unwrap()))));
+                params.push(self.r#type(allowed.clone()));
+                if self.r#match(&RPAREN) {
+                    break
+                }
+                self.require(&COMMA);
+            }
+            if !self.r#match(&ARROW) {
+                return Type::TUPLE(params)
+            }
+            let returns = self.r#type(allowed);
+            return Type::FUNCTION(params, Box::from(returns))
+        }
+        self.buffer.push(self.get_cur());
+        if self.r#match(&IDENT) {  // type parameter or struct, e.g. Pair<int, T>
+            let name = self.buffer.pop().unwrap().t_value;
+            if self.generics.contains(&name) {
+                return Type::GENERIC(name)
+            }
+            let mut args: Vec<Type> = Vec::new();
+            if self.r#match(&LS) {
+                loop {
+                    args.push(self.r#type(allowed.clone()));
+                    if self.close_generic() {
+                        break
+                    }
+                    self.require(&COMMA);
+                }
+            }
+            return Type::CUSTOM(name, args)
+        }
+        self.buffer.pop();
+        Type::from_token_type(&self.require_any(allowed).t_type).unwrap()
+    }
+    fn lambda(&mut self) -> LambdaExpressionNode {
+        let args = self.arguments();
+        self.require(&ARROW);
+        let returns = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW, NULLKW]);
+        self.require(&LAMBDA);
+        let body = self.statement();
+        self.lambdas += 1;
+        LambdaExpressionNode::new(self.lambdas, args, returns, body)
+    }
+    fn variant_construction(&mut self, r#enum: String) -> Expression {
+        let variant = self.require(&IDENT).t_value.clone();
+        let values = if self.r#match(&LPAREN) {  // Shape::Circle(1.0)
+            let mut values: Vec<Expression> = Vec::new();
+            loop {
                 if self.r#match(&RPAREN) {
                     break
                 }
+                values.push(self.expression());
+                if self.r#match(&RPAREN) {
+                    break
+                }
+                self.require(&COMMA);
+            }
+            VariantValues::Tuple(values)
+        } else if self.is_struct_payload_start() {  // Shape::Rect { w: 1, h: 2 }
+            self.require(&LBRACE);
+            let mut values: Vec<(String, Expression)> = Vec::new();
+            loop {
+                if self.r#match(&RBRACE) {
+                    break
+                }
+                let field = self.require(&IDENT).t_value.clone();
+                self.require(&COLON);
+                values.push((field, self.expression()));
+                if self.r#match(&RBRACE) {
+                    break
+                }
                 self.require(&COMMA);
+            }
+            VariantValues::Struct(values)
+        } else {
+            VariantValues::Unit
+        };
+        Expression::Variant(Box::from(VariantExpressionNode::new(r#enum, variant, values)))
+    }
+    /// `{ name:` after a variant, so a block following e.g. an `if` condition is not taken for a payload
+    fn is_struct_payload_start(&self) -> bool {
+        matches!(
+            (self.peek(0).map(|x| &x.t_type), self.peek(1).map(|x| &x.t_type), self.peek(2).map(|x| &x.t_type)),
+            (Some(&LBRACE), Some(&IDENT), Some(&COLON))
+        )
+    }
+    fn is_lambda_start(&self) -> bool {  // called right after `(`
+        matches!(
+            (self.peek(0).map(|x| &x.t_type), self.peek(1).map(|x| &x.t_type)),
+            (Some(&RPAREN), Some(&ARROW)) | (Some(&IDENT), Some(&COLON))
+        )
+    }
 
+    fn variable_creation_statement(&mut self) -> Statement {
+        let buffered = self.buffer.pop();
+        let r#type = &buffered.unwrap().t_type;
+        if r#type == &MK && self.r#match(&LPAREN) {  // mk (q, r) = divmod(a, b);
+            let mut names: Vec<String> = Vec::new();
+            loop {
+                names.push(self.require_any(vec![IDENT, UNDERSCORE]).t_value.clone());
+                if self.r#match(&RPAREN) {
+                    break
+                }
+                self.require(&COMMA);
             }
-            self.require(&ARROW);
-            let r_type = self.require_any(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, BOOLKW]);
-            self.require(&LAMBDA);
-            let body = self.statement();
-            return  Statement::LambdaDeclaration(Box::from(LambdaDeclarationStatementNode::new(name.t_value, args, Type::from_token_type(&r_type.t_type).unwrap(), body)))
+            self.require(&ASSIGN);
+            let value = self.expression();
+            self.bindings += 1;
+            return Statement::Destructuring(Box::from(DestructuringStatementNode::new(self.bindings, names, value)))
         }
+        let declared = if r#type == &MK {
+            None
+        } else {  // e.g. int x, int[MAX] arr, int? x = null or int ! ParseError x = parse(s)
+            self.pos -= 1;
+            Some(self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, CHARARRKW, BOOLKW]))
+        };
+        let mutable = self.r#match(&MUT);  // mutable, e.g. int mut i = 0;
+        let name = self.require(&IDENT);
+        if let Some(r#type) = declared.as_ref().filter(|_| self.get_cur().t_type != ASSIGN) {  // without a value, e.g. int a, b;
+            let mut names = vec![name.t_value];
+            while self.r#match(&COMMA) {
+                names.push(self.require(&IDENT).t_value.clone());
+            }
+            return Statement::Declaration(Box::from(DeclarationStatementNode::new(names, r#type.clone(), mutable)))
+        }
+        self.require(&ASSIGN);
+        if self.r#match(&LPAREN) {
+            if !self.is_lambda_start() {  // parenthesized value or tuple, parsed as an expression
+                self.pos -= 1;
+            } else if r#type != &MK {
+                panic!("OmniaParser error:: cannot declare a lambda in a variable with specified type")
+            } else {
+                let lambda = self.lambda();
+                return Statement::LambdaDeclaration(Box::from(LambdaDeclarationStatementNode::new(name.t_value, lambda, mutable)))
+            }
+        }
+        let value = self.expression();
+        self.bindings += 1;
+        Statement::VariableCreation(Box::from(VariableCreationStatementNode::new(self.bindings, name.t_value, declared, value, mutable)))
+    }
+    fn static_declaration_statement(&mut self) -> Statement {
+        let r#type = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, CHARARRKW, BOOLKW]);
+        let name = self.require(&IDENT).t_value.clone();
+        self.require(&ASSIGN);
         let value = self.expression();
-        Statement::VariableCreation(Box::from(VariableCreationStatementNode::new(name.t_value, Type::from_token_type(r#type).expect("Unexpected token type"), value)))
+        Statement::Static(Box::from(StaticDeclarationStatementNode::new(name, r#type, value)))
     }
     fn return_statement(&mut self) -> Statement {
         self.buffer.pop();
         let value = self.expression();
-        self.pos += 1;
         Statement::Return(Box::from(ReturnStatementNode::new(ASTNode::Expression(value))))
     }
+    /// Consumes the `;` ending a statement. The last statement of a block may leave it out, then `false` is returned
+    fn end_of_statement(&mut self) -> bool {
+        if self.peek(0).is_some_and(|x| x.t_type == RBRACE) {
+            return false
+        }
+        self.require(&SEMICOLON);
+        true
+    }
     fn plug_statement(&mut self) -> Statement {
         self.buffer.pop();
-        println!("Processing plug");
+        if self.trace {
+            println!("Processing plug");
+        }
         let mut sb = StringBuilder::new();
-        let mut cur = self.get_cur();
-        sb.push_string(cur.t_value.clone());
         while !self.r#match(&SEMICOLON) {
             let cur = self.get_cur();
             self.pos += 1;
@@ -272,20 +721,34 @@ impl Parser {
 
     }
     fn expression(&mut self) -> Expression {
-        self.bitwise()
+        self.ternary()
+    }
+
+    fn ternary(&mut self) -> Expression {
+        let cond = self.bitwise();
+        if self.r#match(&QUESTION) {
+            let then = self.expression();
+            self.require(&COLON);
+            let r#else = self.expression();
+            return Expression::Ternary(Box::from(TernaryExpressionNode::new(cond, then, r#else)))
+        }
+        cond
     }
 
     fn bitwise(&mut self) -> Expression {
         let mut left = self.logical();
         loop {
             self.buffer.push(self.get_cur());
-            if self.match_any(vec![AMPERSAND, PIPE, XOR, SHL, SHR]) {
-                let oper = BitwiseOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
-                let right = self.logical();
-                left = Expression::Bitwise(Box::from(BitwiseExpressionNode::new(left, oper, right)))
+            if !self.match_any(vec![AMPERSAND, PIPE, XOR, SHL, SHR, LAMBDA]) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let oper = match self.buffer.pop().unwrap().t_type {
+                LAMBDA => { BitwiseOperation::ShiftR }  // `>>` after a value shifts, after a lambda's return type it starts the body
+                t => { BitwiseOperation::from_token_type(&t).unwrap() }
+            };
+            let right = self.logical();
+            left = Expression::Bitwise(Box::from(BitwiseExpressionNode::new(left, oper, right)))
         }
         left
     }
@@ -293,13 +756,13 @@ impl Parser {
         let mut left = self.comparative();
         loop {
             self.buffer.push(self.get_cur());
-            if self.r#match(&AND) || self.r#match(&OR) {
-                let oper = LogicalOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
-                let right = self.comparative();
-                left = Expression::Logical(Box::from(LogicalExpressionNode::new(left, oper, right)))
+            if !(self.r#match(&AND) || self.r#match(&OR)) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let oper = LogicalOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
+            let right = self.comparative();
+            left = Expression::Logical(Box::from(LogicalExpressionNode::new(left, oper, right)))
         }
         left
     }
@@ -307,13 +770,13 @@ impl Parser {
         let mut left = self.additive();
         loop {
             self.buffer.push(self.get_cur());
-            if self.match_any(vec![GT, LS, EQ, LEQ, GEQ, NEQ]) {
-                let oper = ConditionalOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
-                let right = self.additive();
-                left = Expression::Comparative(Box::from(ComparativeExpressionNode::new(left, oper, right)))
+            if !self.match_any(vec![GT, LS, EQ, LEQ, GEQ, NEQ, FEQ]) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let oper = ConditionalOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
+            let right = self.additive();
+            left = Expression::Comparative(Box::from(ComparativeExpressionNode::new(left, oper, right)))
         }
         left
     }
@@ -322,13 +785,13 @@ impl Parser {
         let mut left = self.multiplicative();
         loop {
             self.buffer.push(self.get_cur());
-            if self.r#match(&PLUS) || self.r#match(&MINUS) {
-                let oper = BinaryOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
-                let right = self.multiplicative();
-                left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, oper, right)))
+            if !(self.r#match(&PLUS) || self.r#match(&MINUS)) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let oper = BinaryOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
+            let right = self.multiplicative();
+            left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, oper, right)))
         }
         left
     }
@@ -336,13 +799,13 @@ impl Parser {
         let mut left = self.exponential();
         loop {
             self.buffer.push(self.get_cur());
-            if self.r#match(&STAR) || self.r#match(&SLASH) ||self.r#match(&REM) {
-                let oper = BinaryOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
-                let right = self.exponential();
-                left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, oper, right)))
+            if !(self.r#match(&STAR) || self.r#match(&SLASH) ||self.r#match(&REM)) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let oper = BinaryOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
+            let right = self.exponential();
+            left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, oper, right)))
         }
         left
     }
@@ -351,26 +814,75 @@ impl Parser {
         let mut left = self.unary();
         loop {
             self.buffer.push(self.get_cur());
-            if self.r#match(&POWER) {
-                let right = self.unary();
-                left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, BinaryOperation::Power, right)))
+            if !self.r#match(&POWER) {
+                self.buffer.pop();
+                break
             }
-            self.buffer.pop();
-            break
+            let right = self.unary();
+            left = Expression::Binary(Box::from(BinaryExpressionNode::new(left, BinaryOperation::Power, right)))
         }
         left
     }
 
     fn unary(&mut self) -> Expression {
-        let mut left = self.primary();
+        if self.r#match(&AMPERSAND) {  // reference, e.g. &a
+            return Expression::Unary(Box::from(UnaryExpressionNode::new(self.access(), UnaryOperation::Ref)))
+        }
+        let mut left = self.access();
         loop {
-            self.buffer.push(self.get_cur());
+            let token = self.get_cur();
             if self.r#match(&INC) || self.r#match(&DEC) {
-                let oper = UnaryOperation::from_token_type(&self.buffer.pop().unwrap().t_type).unwrap();
+                let oper = UnaryOperation::from_token_type(&token.t_type).unwrap();
                 left = Expression::Unary(Box::from(UnaryExpressionNode::new(left, oper)))
             }
-            self.buffer.pop();
-            break
+            if !self.r#match(&AS) {
+                break
+            }
+            // cast, e.g. x as int, x as? byte, x as saturating ubyte or x as wrapping ubyte
+            let mode = if self.r#match(&QUESTION) {
+                CastMode::Checked
+            } else if self.get_cur().t_type == IDENT && self.get_cur().t_value == "saturating" {
+                self.pos += 1;
+                CastMode::Saturating
+            } else if self.get_cur().t_type == IDENT && self.get_cur().t_value == "wrapping" {
+                self.pos += 1;
+                CastMode::Wrapping
+            } else {
+                CastMode::Exact
+            };
+            let target = self.base_type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, CHARARRKW, BOOLKW]);
+            left = Expression::Cast(Box::from(CastExpressionNode::new(left, target, mode)))
+        }
+        left
+    }
+
+    fn access(&mut self) -> Expression {
+        let mut left = self.primary();
+        loop {
+            if self.r#match(&NOT) {  // propagation, e.g. parse(s)!
+                left = Expression::Propagate(Box::from(PropagateExpressionNode::new(left)));
+                continue
+            }
+            if !self.r#match(&PERIOD) {
+                break
+            }
+            let name = self.require(&IDENT).t_value.clone();
+            if self.r#match(&LPAREN) {
+                let mut args: Vec<ASTNode> = Vec::new();
+                loop {
+                    if self.r#match(&RPAREN) {
+                        break
+                    }
+                    args.push(ASTNode::Expression(self.expression()));
+                    if self.r#match(&RPAREN) {
+                        break
+                    }
+                    self.require(&COMMA);
+                }
+                left = Expression::MethodCall(Box::from(MethodCallNode::new(left, name, args)))
+            } else {
+                left = Expression::FieldAccess(Box::from(FieldAccessExpressionNode::new(left, name)))
+            }
         }
         left
     }
@@ -421,12 +933,33 @@ impl Parser {
             let buffered = &self.buffer.pop().unwrap().t_value;
             return Expression::Literal(Box::from(LiteralExpression::Chararr(CharArrNode::new(buffered.clone()))))
         }
+        if self.r#match(&NULLKW) {
+            self.buffer.pop();
+            return Expression::Literal(Box::from(LiteralExpression::Null))
+        }
+        if self.r#match(&OK) || self.r#match(&ERR) {
+            let is_ok = self.buffer.pop().unwrap().t_type == OK;
+            self.require(&LPAREN);
+            let value = self.expression();
+            self.require(&RPAREN);
+            return Expression::ResultValue(Box::from(ResultExpressionNode::new(is_ok, value)))
+        }
+        if self.r#match(&SIZEOF) || self.r#match(&ALIGNOF) {  // sizeof(Point), alignof(int[4])
+            let query = if self.buffer.pop().unwrap().t_type == SIZEOF { LayoutQuery::Size } else { LayoutQuery::Align };
+            self.require(&LPAREN);
+            let target = self.r#type(vec![BYTEKW, UBYTEKW, INTKW, UINTKW, LONGKW, ULONGKW, DECIMALKW, OMNIKW, CHARKW, CHARARRKW, BOOLKW]);
+            self.require(&RPAREN);
+            return Expression::LayoutQuery(Box::from(LayoutQueryExpressionNode::new(query, target)))
+        }
         // if self.r#match(&BOOL) {                                                                                                         //not yet implemented
         //     let buffered = &self.buffer.pop().unwrap().t_value;
         //     return Expression::Literal(Box::from(LiteralExpression::(LongNode::new(buffered.parse::<i64>().unwrap()))))
         // }
         if self.r#match(&IDENT) {
             let buffered = self.buffer.pop().unwrap();
+            if self.r#match(&ACCESS) {
+                return self.variant_construction(buffered.t_value.clone())
+            }
             if self.r#match(&LPAREN) {
                 let name = buffered.t_value.clone();
                 if self.r#match(&RPAREN) {
@@ -449,8 +982,30 @@ impl Parser {
             }
             return Expression::VariableAccess(Box::from(VariableAccessExpressionNode::new(buffered.t_value.clone())));
         }
+        if self.r#match(&IF) {
+            self.buffer.pop();
+            let cond = self.expression();
+            let then = self.statement();
+            self.require(&ELSE);
+            let r#else = self.statement();
+            return Expression::If(Box::from(IfStatementNode::new(cond, then, Some(r#else))))
+        }
         if self.r#match(&LPAREN) {
+            if self.is_lambda_start() {
+                return Expression::Lambda(Box::from(self.lambda()))
+            }
             let value = self.expression();
+            if self.r#match(&COMMA) {  // tuple, e.g. (q, r)
+                let mut values = vec![value];
+                loop {
+                    values.push(self.expression());
+                    if self.r#match(&RPAREN) {
+                        break
+                    }
+                    self.require(&COMMA);
+                }
+                return Expression::Tuple(Box::from(TupleExpressionNode::new(values)))
+            }
             self.require(&RPAREN);
             value
         }
@@ -530,7 +1085,6 @@ impl Parser {
                 if x == &cur.t_type {
                     self.pos += 1;
                     result = true;
-                    return;
                 }
             });
             if !result {
@@ -564,7 +1118,6 @@ impl Parser {
                     self.pos += 1;
                     token = Some(cur.clone());
                     result = true;
-                    return;
                 }
             });
             if !result || token.is_none() {
@@ -579,7 +1132,9 @@ impl Parser {
         self.pos < self.len
     }
     fn log_error(&self, msg: String) {
-        eprintln!("OmniaParser::{{ {} }}", msg)
+        if self.trace {
+            eprintln!("OmniaParser::{{ {} }}", msg)
+        }
     }
 }
 #[derive(Debug)]
@@ -0,0 +1,22 @@
+mod common;
+
+use common::{check, rejects};
+
+#[test]
+fn every_error_of_the_program_is_reported() {
+    let output = check("func f() -> int {\n    mk _a = y;\n    1\n}\nfunc main() {\n    g();\n    if 1 > 0 {\n        mk _b = \"a\" * 2;\n    }\n}\n");
+    assert!(!output.succeeded());
+    for error in ["Variable 'y' is not defined", "Function 'g' is not defined", "Cannot apply * to char[] and int"] {
+        assert!(output.reports(error), "expected '{}' in:\n{}", error, output.stderr);
+    }
+}
+
+#[test]
+fn nested_bodies_are_analyzed() {
+    rejects("func main() {\n    if 1 > 0 {\n        if 2 > 1 {\n            mk _x = z;\n        }\n    }\n}\n", "Variable 'z' is not defined");
+}
+
+#[test]
+fn locals_do_not_outlive_their_block() {
+    rejects("func main() {\n    if 1 > 0 {\n        mk x = 1;\n    }\n    mk _y = x;\n}\n", "Variable 'x' is not defined");
+}
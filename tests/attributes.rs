@@ -0,0 +1,62 @@
+mod common;
+
+use common::{accepts, check, rejects};
+
+#[test]
+fn calling_a_deprecated_function_warns_with_its_note() {
+    let output = accepts("@deprecated(\"use g\")\nfunc f() {}\nfunc main() {\n    f();\n}\n");
+    assert!(output.reports("Warning: 'f' is deprecated: use g [deprecated]\n  at 4:5: f();"), "{}", output.stderr);
+}
+
+#[test]
+fn deprecated_function_declared_after_its_caller_warns() {
+    let output = accepts("func main() {\n    f();\n}\n@deprecated\nfunc f() {}\n");
+    assert!(output.reports("Warning: 'f' is deprecated [deprecated]\n  at 2:5"), "{}", output.stderr);
+}
+
+#[test]
+fn every_use_of_a_deprecated_function_warns() {
+    let output = accepts("@deprecated\nfunc f() -> int { 1 }\nfunc main() {\n    mk a = f();\n    mk _b = a + f();\n}\n");
+    assert_eq!(output.stderr.matches("'f' is deprecated").count(), 2, "{}", output.stderr);
+}
+
+#[test]
+fn deprecated_function_used_as_a_value_warns() {
+    let output = accepts("@deprecated\nfunc f(x: int) -> int { x }\nfunc apply(g: (int) -> int) -> int { g(1) }\nfunc main() {\n    apply(f);\n}\n");
+    assert!(output.reports("'f' is deprecated [deprecated]"), "{}", output.stderr);
+}
+
+#[test]
+fn deprecated_struct_in_a_parameter_type_warns() {
+    let output = accepts("@deprecated(\"use Vec2\")\nstruct Point { x: int }\nfunc show(p: Point?) {}\nfunc main() {\n    show(null);\n}\n");
+    assert!(output.reports("'Point' is deprecated: use Vec2 [deprecated]"), "{}", output.stderr);
+}
+
+#[test]
+fn functions_that_are_not_deprecated_do_not_warn() {
+    let output = accepts("func f() {}\nfunc main() {\n    f();\n}\n");
+    assert!(!output.reports("deprecated"), "{}", output.stderr);
+}
+
+#[test]
+fn allow_attribute_silences_deprecation() {
+    let output = accepts("@deprecated\nfunc f() {}\n@allow(\"deprecated\")\nfunc main() {\n    f();\n}\n");
+    assert!(!output.reports("deprecated"), "{}", output.stderr);
+}
+
+#[test]
+fn deny_attribute_makes_deprecation_an_error() {
+    let output = check("@deprecated\nfunc f() {}\n@deny(\"deprecated\")\nfunc main() {\n    f();\n}\n");
+    assert!(!output.succeeded());
+    assert!(output.reports("Lint error: 'f' is deprecated [deprecated]"), "{}", output.stderr);
+}
+
+#[test]
+fn test_must_take_no_parameters() {
+    rejects("@test\nfunc t(x: int) -> bool { x > 0 }\nfunc main() {}\n", "Test 't' must take no parameters and return bool or nothing");
+}
+
+#[test]
+fn test_must_return_bool_or_nothing() {
+    rejects("@test\nfunc t() -> int { 1 }\nfunc main() {}\n", "Test 't' must take no parameters and return bool or nothing");
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn widening_cast_is_accepted() {
+    accepts("func f(x: int) -> long { x as long }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn narrowing_cast_is_accepted() {
+    accepts("func f(x: long) -> int { x as int }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn invalid_cast_is_rejected() {
+    rejects("func f(x: char[]) -> int { x as int }\nfunc main() {\n    f(\"a\");\n}\n", "Cannot cast char[] to int");
+}
+
+#[test]
+fn checked_cast_is_nullable() {
+    rejects("func f(x: long) -> int { x as? int }\nfunc main() {\n    f(1);\n}\n", "Cannot return a value of type int? from a function returning int");
+}
+
+#[test]
+fn checked_cast_fits_a_nullable_target() {
+    accepts("func f(x: long) -> int? { x as? int }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn exact_cast_of_a_constant_that_does_not_fit_is_rejected() {
+    rejects("func main() {\n    mk _b = 300 as byte;\n}\n", "Constant 300 doesn't fit byte in `as` cast");
+}
+
+#[test]
+fn saturating_cast_of_a_constant_is_folded() {
+    accepts("static byte B = 300 as saturating byte;\nfunc main() {}\n");
+}
+
+#[test]
+fn wrapping_cast_of_a_constant_is_folded() {
+    accepts("static byte B = 300 as wrapping byte;\nfunc main() {}\n");
+}
+
+#[test]
+fn checked_cast_that_does_not_fit_is_not_constant() {
+    rejects("static byte B = 300 as? byte;\nfunc main() {}\n", "doesn't fit byte");
+}
+
+#[test]
+fn lossy_conversion_suggests_a_cast() {
+    rejects("func f(x: long) -> int { x }\nfunc main() {\n    f(1);\n}\n", "the conversion may lose information, write it as a cast `as int`");
+}
+
+#[test]
+fn cast_of_a_nullable_value_is_rejected() {
+    rejects("func f(x: long?) -> int { x as int }\nfunc main() {\n    f(null);\n}\n", "may be null");
+}
+
+#[test]
+fn constant_casts_fold_in_their_mode() {
+    accepts("static byte SATURATED = 300 as saturating byte;\nstatic byte WRAPPED = 300 as wrapping byte;\nstatic byte MAX = WRAPPED + (83 as byte);\nfunc main() {}\n");
+    rejects("static byte SATURATED = 300 as saturating byte;\nstatic byte NEXT = SATURATED + (1 as byte);\nfunc main() {}\n", "Constant 128 overflows byte");
+    rejects("static byte CHECKED = 300 as byte;\nfunc main() {}\n", "Constant 300 doesn't fit byte in `as` cast");
+}
@@ -0,0 +1,79 @@
+//! Runs the compiler on programs written to a fresh directory, so every test sees only its own files
+#![allow(dead_code)]
+
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Output {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String
+}
+impl Output {
+    pub fn succeeded(&self) -> bool {
+        self.code == Some(0)
+    }
+    /// Whether the compiler reported `text` among its errors and warnings
+    pub fn reports(&self, text: &str) -> bool {
+        self.stderr.contains(text)
+    }
+}
+
+/// Directory with the files `(name, content)` for a single run
+pub fn workspace(files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omnia-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::SeqCst)));
+    create_dir_all(&dir).unwrap();
+    for (name, content) in files {
+        write(dir.join(name), content).unwrap();
+    }
+    dir
+}
+
+/// Runs the compiler in `dir` with `args`
+pub fn omnia(dir: &PathBuf, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_Omnia")).args(args).current_dir(dir).output().unwrap();
+    Output {
+        code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string()
+    }
+}
+
+/// Compiles a single file program
+pub fn check(source: &str) -> Output {
+    let dir = workspace(&[("main.oa", source)]);
+    omnia(&dir, &["main.oa"])
+}
+
+/// Compiles a program made of several modules, named `m0.oa`, `m1.oa`, ...
+pub fn check_modules(sources: &[&str]) -> Output {
+    let names: Vec<String> = (0..sources.len()).map(|i| format!("m{}.oa", i)).collect();
+    let files: Vec<(&str, &str)> = names.iter().map(|x| x.as_str()).zip(sources.iter().copied()).collect();
+    let dir = workspace(&files);
+    let args: Vec<&str> = names.iter().map(|x| x.as_str()).collect();
+    omnia(&dir, &args)
+}
+
+/// Asserts that a program compiles without errors
+pub fn accepts(source: &str) -> Output {
+    let output = check(source);
+    assert!(output.succeeded(), "expected the program to compile, got:\n{}", output.stderr);
+    output
+}
+
+/// Asserts that a program fails with an error containing `error`
+pub fn rejects(source: &str, error: &str) -> Output {
+    let output = check(source);
+    assert!(!output.succeeded(), "expected the program to be rejected with '{}'", error);
+    assert!(output.reports(error), "expected an error containing '{}', got:\n{}", error, output.stderr);
+    output
+}
+
+/// Reads a file the compiler wrote into `dir`
+pub fn read(dir: &Path, name: &str) -> String {
+    read_to_string(dir.join(name)).unwrap()
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn strict_equality_of_the_same_type_is_accepted() {
+    accepts("func f(a: int, b: int) -> bool { a =! b }\nfunc main() {\n    f(1, 2);\n}\n");
+}
+
+#[test]
+fn strict_equality_does_not_promote() {
+    rejects("func f(a: int, b: long) -> bool { a =! b }\nfunc main() {\n    f(1, 2);\n}\n", "`=!` compares without promotion, but operands are int and long");
+}
+
+#[test]
+fn equality_promotes() {
+    accepts("func f(a: int, b: long) -> bool { a == b }\nfunc main() {\n    f(1, 2);\n}\n");
+}
+
+#[test]
+fn incomparable_types_are_rejected() {
+    rejects("func f(a: int, b: char[]) -> bool { a == b }\nfunc main() {\n    f(1, \"a\");\n}\n", "Cannot compare int with char[]");
+}
+
+#[test]
+fn strict_equality_folds_in_constants() {
+    accepts("static bool SAME = 2 =! 2;\nfunc main() {}\n");
+    rejects("static long L = 2;\nstatic bool S = L =! 2;\nfunc main() {}\n", "`=!` compares without promotion, but operands are long and int");
+}
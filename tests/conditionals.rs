@@ -0,0 +1,53 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn ternary_takes_the_common_type_of_its_branches() {
+    accepts("func f(c: bool) -> decimal { c ? 1 : 2.5 }\nfunc main() {\n    f(1 > 0);\n}\n");
+}
+
+#[test]
+fn ternary_condition_must_be_bool() {
+    rejects("func main() {\n    mk x = 1 ? 2 : 3;\n}\n", "Condition must be bool, got int");
+}
+
+#[test]
+fn if_expression_takes_the_common_type_of_its_branches() {
+    rejects("func f(c: bool) -> int { if c { 1 } else { 2.5 } }\nfunc main() {\n    f(1 > 0);\n}\n", "Cannot return a value of type decimal from a function returning int");
+}
+
+#[test]
+fn if_expression_branches_must_be_compatible() {
+    rejects("func main() {\n    mk x = if 1 > 0 { \"a\" } else { 3 };\n}\n", "Branches have incompatible types char[] and int");
+}
+
+#[test]
+fn if_expression_branch_has_its_own_scope() {
+    accepts("func f(c: bool, y: int) -> int {\n    mk x = if c { mk t = y; t } else { y };\n    x\n}\nfunc main() {\n    f(1 > 0, 2);\n}\n");
+}
+
+#[test]
+fn if_expression_branch_locals_do_not_leak() {
+    rejects("func f(c: bool, y: int) -> int {\n    mk x = if c { mk t = y; t } else { y };\n    t\n}\nfunc main() {\n    f(1 > 0, 2);\n}\n", "Variable 't' is not defined");
+}
+
+#[test]
+fn if_expression_branch_statements_are_analyzed() {
+    rejects("func f(c: bool, y: int) -> int {\n    mk x = if c { undefinedfn(); y } else { y };\n    x\n}\nfunc main() {\n    f(1 > 0, 2);\n}\n", "Function 'undefinedfn' is not defined");
+}
+
+#[test]
+fn if_expression_branch_value_is_not_returned() {
+    accepts("func f(c: bool) -> int {\n    mk s = if c { 0.5 } else { 1.5 };\n    1\n}\nfunc main() {\n    f(1 > 0);\n}\n");
+}
+
+#[test]
+fn if_expression_branch_may_return_from_the_function() {
+    accepts("func f(c: bool) -> int {\n    mk x = if c { return 4; } else { 3 };\n    x\n}\nfunc main() {\n    f(1 > 0);\n}\n");
+}
+
+#[test]
+fn if_expression_narrows_nullable_conditions() {
+    accepts("func f(n: int?) -> int { if n != null { n + 1 } else { 0 } }\nfunc main() {\n    f(null);\n}\n");
+}
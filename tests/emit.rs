@@ -0,0 +1,39 @@
+mod common;
+
+use common::{omnia, read, workspace};
+
+const SOURCE: &str = "func main() {\n    mk x = 1;\n}\n";
+
+#[test]
+fn tokens_are_emitted_with_their_spans() {
+    let dir = workspace(&[("main.oa", SOURCE)]);
+    assert!(omnia(&dir, &["--emit=tokens", "main.oa"]).succeeded());
+    let tokens = read(&dir, "main.oa.tokens.json");
+    assert!(tokens.starts_with("{\"version\":"), "{}", tokens);
+    assert!(tokens.contains("{\"type\":\"MK\",\"value\":\"mk\",\"span\":{\"start\":18,\"end\":20,\"line\":2,\"column\":5}}"), "{}", tokens);
+}
+
+#[test]
+fn ast_is_emitted_with_statement_spans() {
+    let dir = workspace(&[("main.oa", SOURCE)]);
+    assert!(omnia(&dir, &["--emit=tokens,ast", "main.oa"]).succeeded());
+    let ast = read(&dir, "main.oa.ast.json");
+    assert!(ast.contains("{\"kind\":\"VariableCreation\",\"name\":\"x\",\"mutable\":false,\"type\":null,\"value\":{\"kind\":\"Literal\",\"type\":\"int\",\"value\":\"1\"},\"span\":{\"start\":18,\"end\":27,\"line\":2,\"column\":5}}"), "{}", ast);
+    assert!(dir.join("main.oa.tokens.json").exists());
+}
+
+#[test]
+fn unknown_emit_kind_is_rejected() {
+    let dir = workspace(&[("main.oa", SOURCE)]);
+    let output = omnia(&dir, &["--emit=bogus", "main.oa"]);
+    assert!(!output.succeeded());
+    assert!(output.reports("Unknown emit kind 'bogus', expected tokens, ast, types or layouts"), "{}", output.stderr);
+}
+
+#[test]
+fn compiling_prints_nothing() {
+    let dir = workspace(&[("main.oa", SOURCE)]);
+    let output = omnia(&dir, &["main.oa"]);
+    assert!(output.succeeded(), "{}", output.stderr);
+    assert_eq!(output.stdout, "");
+}
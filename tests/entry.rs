@@ -0,0 +1,95 @@
+mod common;
+
+use common::{accepts, check_modules, omnia, read, rejects, workspace};
+
+#[test]
+fn main_without_parameters_is_accepted() {
+    accepts("func main() {}\n");
+}
+
+#[test]
+fn main_returning_an_exit_code_is_accepted() {
+    accepts("func main() -> int { 0 }\n");
+}
+
+#[test]
+fn main_taking_the_arguments_is_accepted() {
+    accepts("func main(args: &char[][]) {}\n");
+}
+
+#[test]
+fn main_taking_the_arguments_as_strings_is_accepted() {
+    accepts("func main(args: &string[]) -> int { 0 }\n");
+}
+
+#[test]
+fn main_with_the_type_first_is_accepted() {
+    accepts("func main(&char[][] args) {}\n");
+}
+
+#[test]
+fn static_main_is_accepted() {
+    accepts("static func main(&string[] args) {}\n");
+}
+
+#[test]
+fn static_main_returning_an_exit_code_is_accepted() {
+    accepts("static func main() -> int { 0 }\n");
+}
+
+#[test]
+fn main_with_other_parameters_is_rejected() {
+    rejects("func main(string args) {}\n", "must be declared as `func main()` or `func main(args: &char[][])`, also written `static func main(&string[] args)`, optionally returning an `int` exit code, got main(char[])");
+}
+
+#[test]
+fn main_with_another_return_type_is_rejected() {
+    rejects("func main() -> decimal { 0.5 }\n", "got main() -> decimal");
+}
+
+#[test]
+fn main_is_not_a_test() {
+    rejects("@test\nfunc main() {}\n", "can't be marked '@test'");
+}
+
+#[test]
+fn program_needs_an_entry_point() {
+    rejects("func f() {}\n", "The program has no entry point, declare `func main()` in one of its modules");
+}
+
+#[test]
+fn program_has_one_entry_point() {
+    let output = check_modules(&["func main() {}\n", "static func main(&string[] args) {}\n"]);
+    assert!(!output.succeeded());
+    assert!(output.reports("The program has 2 entry points, but it must have exactly one: module 'm0' at 1:1: func main() {}; module 'm1' at 1:1: static func main(&string[] args) {}"), "{}", output.stderr);
+}
+
+#[test]
+fn modules_do_not_define_a_symbol_twice() {
+    let output = check_modules(&["func main() {}\nvisible func area() -> int { 1 }\n", "visible func area() -> int { 2 }\n"]);
+    assert!(!output.succeeded());
+    assert!(output.reports("Function 'area' of module 'm1' at 1:1: visible func area() -> int { 2 } is already defined by module 'm0' at 2:1"), "{}", output.stderr);
+}
+
+#[test]
+fn modules_may_overload_a_function() {
+    let output = check_modules(&["func main() {}\nvisible func area(x: int) -> int { x }\n", "visible func area(x: decimal) -> decimal { x }\n"]);
+    assert!(output.succeeded(), "{}", output.stderr);
+}
+
+#[test]
+fn static_function_is_declared_at_the_top_level() {
+    rejects("func main() {\n    static func f() {}\n}\n", "`static` function 'f' must be declared at the top level");
+}
+
+#[test]
+fn string_is_an_alias_of_char_array() {
+    accepts("visible func greet(string name, int times) -> int { times }\nfunc main() {\n    string s = \"a\";\n    greet(s, 2);\n}\n");
+}
+
+#[test]
+fn static_main_keeps_its_modifier_when_formatted() {
+    let dir = workspace(&[("main.oa", "static func main(&string[] args) {}\n")]);
+    omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(read(&dir, "main.oa"), "static func main(args: &char[][]) {}\n");
+}
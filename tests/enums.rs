@@ -0,0 +1,75 @@
+mod common;
+
+use common::{accepts, rejects};
+
+const SHAPE: &str = "enum Shape { Circle(decimal), Rect { w: int, h: int }, Empty }\n";
+
+fn program(body: &str) -> String {
+    format!("{}{}", SHAPE, body)
+}
+
+#[test]
+fn variants_are_constructed_with_their_payloads() {
+    accepts(&program("func main() {\n    mk c = Shape::Circle(1.5);\n    mk r = Shape::Rect { w: 1, h: 2 };\n    mk e = Shape::Empty;\n}\n"));
+}
+
+#[test]
+fn variant_payload_types_are_checked() {
+    rejects(&program("func main() {\n    mk c = Shape::Circle(\"a\");\n}\n"), "Field '0' of Shape::Circle expects decimal, got char[]");
+}
+
+#[test]
+fn variant_payload_size_is_checked() {
+    rejects(&program("func main() {\n    mk c = Shape::Circle(1.5, 2.5);\n}\n"), "Variant Shape::Circle holds 1 values, got 2");
+}
+
+#[test]
+fn variant_payload_kind_is_checked() {
+    rejects(&program("func main() {\n    mk c = Shape::Rect(1, 2);\n}\n"), "Wrong payload kind for variant Shape::Rect");
+}
+
+#[test]
+fn unknown_variant_is_rejected() {
+    rejects(&program("func main() {\n    mk c = Shape::Square(1);\n}\n"), "Enum 'Shape' has no variant 'Square'");
+}
+
+#[test]
+fn switch_destructures_the_variants() {
+    accepts(&program("visible func area(s: Shape) -> decimal {\n    switch s {\n        Shape::Circle(r) -> { return r; }\n        Shape::Rect { w: w, h: h } -> { return (w * h) as decimal; }\n        Shape::Empty -> { return 0.0; }\n    }\n}\nfunc main() {}\n"));
+}
+
+#[test]
+fn switch_pattern_binds_every_value() {
+    rejects(&program("visible func f(s: Shape) {\n    switch s {\n        Shape::Circle(r, q) -> {}\n        _ -> {}\n    }\n}\nfunc main() {}\n"), "Variant Shape::Circle holds 1 values, pattern binds 2");
+}
+
+#[test]
+fn struct_path_calls_an_extension_method_with_an_explicit_receiver() {
+    accepts("struct Point { x: int }\next func scale(k: int) for Point -> int { k }\nvisible func f(p: Point) -> int { Point::scale(p, 2) }\nfunc main() {}\n");
+}
+
+#[test]
+fn struct_path_call_checks_the_receiver() {
+    rejects("struct Point { x: int }\next func scale(k: int) for Point -> int { k }\nvisible func f(p: Point) -> int { Point::scale(1, 2) }\nfunc main() {}\n", "Receiver of 'Point::scale' must be Point, got int");
+}
+
+#[test]
+fn struct_path_call_checks_the_arguments() {
+    rejects("struct Point { x: int }\next func scale(k: int) for Point -> int { k }\nvisible func f(p: Point) -> int { Point::scale(p, \"a\") }\nfunc main() {}\n", "Argument of 'scale' expects int, got char[]");
+}
+
+#[test]
+fn struct_path_call_needs_the_method() {
+    rejects("struct Point { x: int }\nvisible func f(p: Point) -> int { Point::scale(p, 2) }\nfunc main() {}\n", "Point has no method 'scale'");
+}
+
+#[test]
+fn plugged_item_path_calls_into_the_library() {
+    let output = accepts("plug std::console.println|reader;\nfunc main() {\n    mk input = reader::new(std::stdin);\n    input.next_int();\n    println(\"a\");\n}\n");
+    assert!(!output.reports("never used"), "{}", output.stderr);
+}
+
+#[test]
+fn path_with_an_unknown_root_is_rejected() {
+    rejects("func main() {\n    mk input = writer::new(1);\n}\n", "'writer::new' is not defined, 'writer' is neither an enum, a struct nor a plugged item");
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::{omnia, read, workspace};
+
+const MESSY: &str = "plug std::console.println;\nplug std::console.print;\n// adds\nfunc add(a: int, b: int) -> int { a + b }\nfunc pick(c: int) -> int {\n    if c > 0 { return 1; }\n    return add(c, 2);   // fallback\n}\nfunc main() {\n    mk x = pick(1);\n        println(\"x\");\n\n\n    mk f = (y: int) -> int >> { y * 2 };\n    f(x);\n}\n";
+
+const FORMATTED: &str = "plug std::console.print;\nplug std::console.println;\n// adds\nfunc add(a: int, b: int) -> int {\n    a + b\n}\nfunc pick(c: int) -> int {\n    if c > 0 {\n        return 1;\n    }\n    return add(c, 2); // fallback\n}\nfunc main() {\n    mk x = pick(1);\n    println(\"x\");\n\n    mk f = (y: int) -> int >> {\n        y * 2\n    };\n    f(x);\n}\n";
+
+/// The AST of a file as emitted by `--emit=ast`, without the spans which differ between layouts of the same code
+fn ast(source: &str) -> String {
+    let dir = workspace(&[("main.oa", source)]);
+    omnia(&dir, &["--emit=ast", "main.oa"]);
+    let mut json = read(&dir, "main.oa.ast.json");
+    while let Some(start) = json.find(",\"span\":{") {
+        let end = start + json[start..].find('}').unwrap() + 1;
+        json.replace_range(start..end, "");
+    }
+    json
+}
+
+#[test]
+fn formats_into_the_canonical_style() {
+    let dir = workspace(&[("main.oa", MESSY)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(read(&dir, "main.oa"), FORMATTED);
+}
+
+#[test]
+fn formatting_is_idempotent() {
+    let dir = workspace(&[("main.oa", FORMATTED)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(read(&dir, "main.oa"), FORMATTED);
+}
+
+#[test]
+fn check_mode_is_quiet_on_formatted_files() {
+    let dir = workspace(&[("main.oa", FORMATTED)]);
+    let output = omnia(&dir, &["fmt", "--check", "main.oa"]);
+    assert_eq!(output.code, Some(0));
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.stderr, "");
+}
+
+#[test]
+fn check_mode_reports_without_writing() {
+    let dir = workspace(&[("main.oa", MESSY)]);
+    let output = omnia(&dir, &["fmt", "--check", "main.oa"]);
+    assert_eq!(output.code, Some(1));
+    assert_eq!(output.stderr, "main.oa: not formatted\n");
+    assert_eq!(read(&dir, "main.oa"), MESSY);
+}
+
+#[test]
+fn returns_and_tail_values_round_trip() {
+    let source = "func f(x: int) -> int {\n    if x > 0 {\n        return x;\n    }\n    g(x)\n}\nfunc g(x: int) -> int {\n    0\n}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(read(&dir, "main.oa"), source);
+}
+
+#[test]
+fn formatting_keeps_the_ast() {
+    let source = MESSY.replace("plug std::console.print;\n", "");
+    let dir = workspace(&[("main.oa", &source)]);
+    omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(ast(&read(&dir, "main.oa")), ast(&source));
+}
+
+#[test]
+fn comments_stay_next_to_their_tokens() {
+    let source = "struct P {\n  // the x\n  x: int, // right\n  y: int /* down */\n}\nfunc add(a: int, b: int) -> int { a + b }\nfunc main() {\n    mk _s = add(1, /* two */ 2);\n}\nfunc todo() { // later\n}\n";
+    let formatted = "struct P {\n    // the x\n    x: int, // right\n    y: int /* down */\n}\nfunc add(a: int, b: int) -> int {\n    a + b\n}\nfunc main() {\n    mk _s = add(1, /* two */ 2);\n}\nfunc todo() { // later\n}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(read(&dir, "main.oa"), formatted);
+}
+
+#[test]
+fn comment_that_would_move_leaves_the_file_as_is() {
+    let source = "plug std::console.println;\n// for the prompt\nplug std::console.print;\nfunc main() {}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(2));
+    assert_eq!(output.stderr, "main.oa: the comment `// for the prompt` on line 2 would move, the file was left as is\n");
+    assert_eq!(read(&dir, "main.oa"), source);
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    let source = "func main() {\n    mk x = (1 + 2;\n}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(2));
+    assert!(output.stderr.starts_with("main.oa: cannot be parsed: "), "{}", output.stderr);
+    assert!(!output.stderr.contains("panicked"), "{}", output.stderr);
+    assert_eq!(read(&dir, "main.oa"), source);
+}
+
+#[test]
+fn keeps_mutable_lambda_bindings_and_increments() {
+    let source = "func main() {\n    mk mut f = (y: int) -> int >> {\n        y\n    };\n    mk mut x = 1;\n    x++;\n    f(x);\n}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(read(&dir, "main.oa"), source);
+}
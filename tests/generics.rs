@@ -0,0 +1,35 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn generic_function_accepts_each_call_its_own_type() {
+    accepts("func max<T>(a: T, b: T) -> T { a }\nfunc main() {\n    mk _x = max(1, 2);\n    mk _y = max(1.5, 2.5);\n}\n");
+}
+
+#[test]
+fn generic_arguments_must_agree() {
+    rejects("func max<T>(a: T, b: T) -> T { a }\nfunc main() {\n    mk _x = max(1, \"a\");\n}\n", "Argument 'b' of 'max' expects int, got char[]");
+}
+
+#[test]
+fn generic_arguments_are_bound_to_their_common_type() {
+    accepts("func max<T>(a: T, b: T) -> T { a }\nfunc main() {\n    mk _x = max(1, 2.5);\n    mk _y = max(2.5, 1);\n}\n");
+    rejects("func max<T>(a: T, b: T) -> T { a }\nfunc main() {\n    int _x = max(1, 2.5);\n}\n", "Cannot initialize '_x' of type int with a value of type decimal");
+}
+
+#[test]
+fn call_takes_the_instantiated_return_type() {
+    rejects("func id<T>(a: T) -> T { a }\nfunc main() {\n    mk _x = id(1) + 1;\n    mk _y = id(\"a\") + 1;\n}\n", "Cannot apply + to char[] and int");
+}
+
+#[test]
+fn generic_body_is_checked_once_without_its_instances() {
+    rejects("func id<T>(a: T) -> T { a + 1 }\nfunc main() {\n    id(1);\n}\n", "Cannot apply + to T and int");
+}
+
+#[test]
+fn generic_struct_fields_take_the_type_arguments() {
+    accepts("struct Pair<A, B> { first: A, second: B }\nvisible func f(p: Pair<int, char[]>) -> int { p.first }\nfunc main() {}\n");
+    rejects("struct Pair<A, B> { first: A, second: B }\nvisible func f(p: Pair<int, char[]>) -> int { p.second }\nfunc main() {}\n", "Cannot return a value of type char[] from a function returning int");
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::{accepts, omnia, read, rejects, workspace};
+
+/// The first Omnia example of a readme
+fn example(readme: &str) -> String {
+    let text = std::fs::read_to_string(format!("{}/{}", env!("CARGO_MANIFEST_DIR"), readme)).unwrap();
+    let start = text.find("```Omnia\n").unwrap() + "```Omnia\n".len();
+    let end = start + text[start..].find("```").unwrap();
+    text[start..end].to_string()
+}
+
+/// The `mk` bindings of `source` as written by `--emit=types`
+fn types(source: &str) -> String {
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["--emit=types", "main.oa"]);
+    assert!(output.succeeded(), "{}", output.stderr);
+    read(&dir, "main.oa.types.json")
+}
+
+#[test]
+fn readme_example_compiles() {
+    accepts(&example("README.md"));
+}
+
+#[test]
+fn translated_readme_example_compiles() {
+    assert_eq!(example("README_RU.md"), example("README.md"));
+}
+
+#[test]
+fn binding_takes_the_type_of_its_initializer() {
+    let json = types("func main() {\n    mk x = 1;\n    mk s = \"a\";\n    mk d = 0.5;\n}\n");
+    assert!(json.contains("{\"name\":\"x\",\"type\":\"int\",\"span\":{\"start\":18,\"end\":27,\"line\":2,\"column\":5}}"), "{}", json);
+    assert!(json.contains("{\"name\":\"s\",\"type\":\"char[]\""), "{}", json);
+    assert!(json.contains("{\"name\":\"d\",\"type\":\"decimal\""), "{}", json);
+}
+
+#[test]
+fn binding_of_a_library_value_takes_the_plugged_type() {
+    let json = types("plug std::console.reader;\nfunc main() {\n    mk input = reader::new(std::stdin);\n    input.close();\n}\n");
+    assert!(json.contains("{\"name\":\"input\",\"type\":\"reader\""), "{}", json);
+}
+
+#[test]
+fn results_of_library_methods_are_opaque_values() {
+    let json = types("plug std::console.println|reader;\nfunc main() {\n    mk input = reader::new(std::stdin);\n    mk line = input.next_line();\n    println(line.trim());\n}\n");
+    assert!(json.contains("{\"name\":\"line\",\"type\":\"std::next_line\""), "{}", json);
+}
+
+#[test]
+fn results_of_plugged_functions_are_opaque_values() {
+    let json = types("plug std::console.println|readln;\nfunc main() {\n    mk line = readln();\n    println(line);\n}\n");
+    assert!(json.contains("{\"name\":\"line\",\"type\":\"std::readln\""), "{}", json);
+}
+
+#[test]
+fn opaque_value_is_not_a_declared_type() {
+    rejects("plug std::console.readln;\nfunc twice(x: int) -> int { x * 2 }\nfunc main() {\n    twice(readln());\n}\n", "Argument 'x' of 'twice' expects int, got std::readln");
+}
+
+#[test]
+fn declaration_without_a_value_is_filled_through_a_reference() {
+    accepts("plug std::console.println|reader;\nfunc main() {\n    int a, b;\n    mk input = reader::new(std::stdin);\n    input.next_int(&a);\n    input.next_int(&b);\n    println(a / b)\n}\n");
+}
+
+#[test]
+fn declaration_without_a_value_is_immutable() {
+    rejects("func main() {\n    int a;\n    a = 1;\n}\n", "Cannot assign to immutable 'a'");
+}
+
+#[test]
+fn only_variables_have_an_address() {
+    rejects("plug std::console.reader;\nfunc main() {\n    mk input = reader::new(std::stdin);\n    input.next_int(&1);\n}\n", "Only variables have an address that `&` can take");
+}
+
+#[test]
+fn nested_and_destructured_bindings_are_emitted() {
+    let json = types("func pair() -> (int, decimal) { (1, 2.5) }\nfunc main() {\n    mk (a, _) = pair();\n    if a > 0 {\n        mk f = (x: int) -> int >> { mk y = x; y };\n        f(a);\n    }\n}\n");
+    assert!(json.contains("{\"name\":\"a\",\"type\":\"int\""), "{}", json);
+    assert!(!json.contains("\"name\":\"_\""), "{}", json);
+    assert!(json.contains("{\"name\":\"y\",\"type\":\"int\""), "{}", json);
+}
+
+#[test]
+fn declared_bindings_are_not_emitted() {
+    let json = types("func main() {\n    int x = 1;\n}\n");
+    assert!(!json.contains("\"name\":\"x\""), "{}", json);
+}
+
+#[test]
+fn inferred_type_is_used_by_later_checks() {
+    rejects("func main() {\n    mk s = \"a\";\n    int n = s;\n}\n", "Cannot initialize 'n' of type int with a value of type char[] ('s' is inferred as char[])");
+}
+
+#[test]
+fn binding_of_null_needs_a_declared_type() {
+    rejects("func main() {\n    mk x = null;\n}\n", "Cannot infer the type of 'x' from `null`, declare it with an explicit type");
+}
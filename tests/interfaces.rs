@@ -0,0 +1,64 @@
+mod common;
+
+use common::{accepts, rejects};
+
+const SHAPE: &str = "interface Shape { func area() -> decimal; }\nstruct Point { x: int }\n";
+
+fn program(body: &str) -> String {
+    format!("{}{}", SHAPE, body)
+}
+
+#[test]
+fn implementation_provides_every_method() {
+    accepts(&program("ext Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc main() {}\n"));
+}
+
+#[test]
+fn missing_method_is_reported() {
+    rejects("interface Shape { func area() -> decimal; func name() -> int; }\nstruct Point { x: int }\next Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc main() {}\n", "Point does not implement name() -> int of interface 'Shape'");
+}
+
+#[test]
+fn implementation_must_be_marked_override() {
+    rejects(&program("ext Shape for Point {\n    func area() -> decimal { 1.5 }\n}\nfunc main() {}\n"), "Method 'area' implements interface 'Shape' and must be marked `override`");
+}
+
+#[test]
+fn override_is_checked_against_the_interface_signature() {
+    rejects(&program("ext Shape for Point {\n    override func area() -> int { 1 }\n}\nfunc main() {}\n"), "Method 'area() -> int' of Point must have signature area() -> decimal from interface 'Shape'");
+}
+
+#[test]
+fn struct_is_passed_as_an_interface_it_implements() {
+    accepts(&program("ext Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc total(s: Shape) -> decimal { s.area() }\nvisible func f(p: Point) -> decimal { total(p) }\nfunc main() {}\n"));
+}
+
+#[test]
+fn struct_is_passed_as_a_nullable_interface() {
+    accepts(&program("ext Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc total(s: Shape?) -> decimal { 0.5 }\nvisible func f(p: Point) -> decimal { total(p) }\nfunc main() {}\n"));
+}
+
+#[test]
+fn struct_is_returned_as_an_interface_it_implements() {
+    accepts(&program("ext Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nvisible func f(p: Point) -> Shape { return p; }\nfunc main() {}\n"));
+}
+
+#[test]
+fn struct_without_an_implementation_is_not_an_interface() {
+    rejects(&program("ext func area() for Point -> decimal { 1.5 }\nfunc total(s: Shape) -> decimal { s.area() }\nvisible func f(p: Point) -> decimal { total(p) }\nfunc main() {}\n"), "Argument 's' of 'total' expects Shape, got Point");
+}
+
+#[test]
+fn generic_bound_accepts_an_implementation() {
+    accepts(&program("ext Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc total<T: Shape>(s: T) -> decimal { s.area() }\nvisible func f(p: Point) -> decimal { total(p) }\nfunc main() {}\n"));
+}
+
+#[test]
+fn generic_bound_rejects_a_type_without_an_implementation() {
+    rejects(&program("func total<T: Shape>(s: T) -> decimal { s.area() }\nvisible func f(p: Point) -> decimal { total(p) }\nfunc main() {}\n"), "Point does not implement interface 'Shape' required by 'total'");
+}
+
+#[test]
+fn unknown_interface_is_rejected() {
+    rejects("struct Point { x: int }\next Drawable for Point {\n    override func draw() -> int { 1 }\n}\nfunc main() {}\n", "Interface 'Drawable' is not defined");
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn lambda_is_called_through_its_binding() {
+    accepts("func main() {\n    mk double = (x: int) -> int >> { x * 2 };\n    double(4);\n}\n");
+}
+
+#[test]
+fn lambda_is_passed_to_a_function_type_parameter() {
+    accepts("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    mk k = 3;\n    apply((x: int) -> int >> { x + k }, 2);\n}\n");
+}
+
+#[test]
+fn inline_lambda_body_is_analyzed() {
+    rejects("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    apply((x: int) -> int >> { zzz }, 2);\n}\n", "Variable 'zzz' is not defined");
+}
+
+#[test]
+fn inline_lambda_return_type_is_checked() {
+    rejects("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    apply((x: int) -> int >> { 0.5 }, 2);\n}\n", "Cannot return a value of type decimal from a function returning int");
+}
+
+#[test]
+fn lambda_does_not_match_another_function_type() {
+    rejects("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    apply((x: decimal) -> int >> { 1 }, 2);\n}\n", "Argument 'f' of 'apply' expects (int) -> int, got (decimal) -> int");
+}
+
+#[test]
+fn call_through_a_variable_checks_the_arity() {
+    rejects("func main() {\n    mk g = (x: int) -> int >> { x };\n    g(1 == 1, 5, 7);\n}\n", "'g' of type (int) -> int takes 1 arguments, got 3");
+}
+
+#[test]
+fn call_through_a_variable_checks_the_argument_types() {
+    rejects("func main() {\n    mk g = (x: int) -> int >> { x };\n    g(1 == 1);\n}\n", "Argument of 'g' expects int, got bool");
+}
+
+#[test]
+fn only_functions_are_callable() {
+    rejects("func main() {\n    mk g = 1;\n    g(1);\n}\n", "'g' of type int is not callable");
+}
+
+#[test]
+fn named_function_is_a_value_of_function_type() {
+    accepts("func inc(x: int) -> int { x + 1 }\nfunc apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    apply(inc, 1);\n}\n");
+}
+
+#[test]
+fn mutable_lambda_binding_can_be_reassigned() {
+    accepts("func main() {\n    mk mut f = (x: int) -> int >> { x };\n    f = (x: int) -> int >> { x * 2 };\n    f(1);\n}\n");
+}
+
+#[test]
+fn immutable_lambda_binding_is_not_reassigned() {
+    rejects("func main() {\n    mk f = (x: int) -> int >> { x };\n    f = (x: int) -> int >> { x * 2 };\n    f(1);\n}\n", "Cannot assign to immutable 'f'");
+}
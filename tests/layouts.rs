@@ -0,0 +1,84 @@
+mod common;
+
+use common::{accepts, omnia, read, rejects, workspace};
+
+/// The struct and enum layouts of `source` as written by `--emit=layouts`
+fn layouts(source: &str) -> String {
+    let dir = workspace(&[("main.oa", source)]);
+    let output = omnia(&dir, &["--emit=layouts", "main.oa"]);
+    assert!(output.succeeded(), "{}", output.stderr);
+    read(&dir, "main.oa.layouts.json")
+}
+
+#[test]
+fn scalars_are_aligned_to_their_size() {
+    let json = layouts("@layout(\"C\")\nstruct S { a: byte, b: int, c: byte, d: long }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"S","size":24,"align":8,"fields":[{"name":"a","offset":0},{"name":"b","offset":4},{"name":"c","offset":8},{"name":"d","offset":16}]}"#), "{}", json);
+}
+
+#[test]
+fn default_layout_reorders_fields_by_alignment() {
+    let json = layouts("struct S { a: byte, b: long, c: byte }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"S","size":16,"align":8,"fields":[{"name":"a","offset":8},{"name":"b","offset":0},{"name":"c","offset":9}]}"#), "{}", json);
+}
+
+#[test]
+fn c_layout_keeps_the_declaration_order() {
+    let json = layouts("@layout(\"C\")\nstruct S { a: byte, b: long, c: byte }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"S","size":24,"align":8,"fields":[{"name":"a","offset":0},{"name":"b","offset":8},{"name":"c","offset":16}]}"#), "{}", json);
+}
+
+#[test]
+fn packed_layout_has_no_padding() {
+    let json = layouts("@layout(\"packed\")\nstruct S { a: byte, b: long, c: byte }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"S","size":10,"align":1,"fields":[{"name":"a","offset":0},{"name":"b","offset":1},{"name":"c","offset":9}]}"#), "{}", json);
+}
+
+#[test]
+fn tuples_of_structs_use_the_struct_layout() {
+    let json = layouts("struct Big { a: long, b: long, c: long }\nstruct T { t: (byte, Big) }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"T","size":32,"align":8,"#), "{}", json);
+}
+
+#[test]
+fn tuples_of_enums_use_the_enum_layout() {
+    let json = layouts("enum E { A(long), B }\nstruct T { t: (E, byte) }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"T","size":24,"align":8,"#), "{}", json);
+}
+
+#[test]
+fn enum_is_a_tag_followed_by_its_largest_payload() {
+    let json = layouts("enum E { A(int), B(long, long), C }\nfunc main() {}\n");
+    assert!(json.contains(r#""name":"E","size":24,"align":8,"tag_size":1"#), "{}", json);
+}
+
+#[test]
+fn fixed_arrays_repeat_their_item() {
+    let json = layouts("struct P { x: int, y: int }\nstruct A { ps: P[4] }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"A","size":32,"align":4,"#), "{}", json);
+}
+
+#[test]
+fn sizeof_is_constant() {
+    accepts("struct P { x: int, y: int }\nstatic ulong SIZE = sizeof(P);\nstatic ulong REST = SIZE - (8 as ulong);\nfunc main() {}\n");
+    rejects("struct P { x: int, y: int }\nstatic ulong SIZE = sizeof(P);\nstatic ulong REST = SIZE - (9 as ulong);\nfunc main() {}\n", "Constant -1 overflows ulong");
+}
+
+#[test]
+fn unknown_layout_is_rejected() {
+    rejects("@layout(\"rust\")\nstruct S { a: int }\nfunc main() {}\n", "Unknown layout \"rust\" of 'S', expected \"C\" or \"packed\"");
+}
+
+#[test]
+fn struct_containing_itself_is_rejected() {
+    rejects("struct Node { next: Node? }\nfunc main() {}\n", "contains itself through Node -> Node and has no finite size");
+}
+
+#[test]
+fn layouts_are_emitted() {
+    let json = layouts("@layout(\"C\")\nstruct S { a: byte, b: int }\nenum E { A(byte), B }\nstruct G<T> { v: T }\nfunc main() {}\n");
+    assert!(json.contains(r#"{"name":"S","size":8,"align":4,"fields":[{"name":"a","offset":0},{"name":"b","offset":4}]}"#), "{}", json);
+    assert!(json.contains(r#""name":"E","size":2,"align":1,"tag_size":1"#), "{}", json);
+    assert!(json.contains(r#"{"name":"A","tag":0,"fields":[{"name":"0","offset":1}]}"#), "{}", json);
+    assert!(!json.contains(r#""name":"G""#), "{}", json);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{accepts, check, omnia, workspace};
+
+#[test]
+fn unused_variable_is_reported_at_its_declaration() {
+    let output = accepts("func main() {\n    mk h = 4;\n}\n");
+    assert!(output.reports("Warning: Variable 'h' is never used [unused_variable]\n  at 2:5: mk h = 4;"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_variable_keeps_its_span_after_a_lambda_body() {
+    let output = accepts("func apply(f: (int) -> int, v: int) -> int { f(v) }\nfunc main() {\n    mk k = 3;\n    mk h = apply((x: int) -> int >> { x + k }, 2);\n}\n");
+    assert!(output.reports("Variable 'h' is never used [unused_variable]\n  at 4:5: mk h"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_variable_keeps_its_span_after_an_if_expression() {
+    let output = accepts("func main() {\n    mk c = if 1 > 0 { mk t = 1; t } else { 2 };\n}\n");
+    assert!(output.reports("Variable 'c' is never used [unused_variable]\n  at 2:5: mk c"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_parameter_is_reported() {
+    let output = accepts("func f(x: int) -> int { 1 }\nfunc main() {\n    f(1);\n}\n");
+    assert!(output.reports("Parameter 'x' is never used [unused_variable]"), "{}", output.stderr);
+}
+
+#[test]
+fn underscore_names_are_exempt() {
+    let output = accepts("func main() {\n    mk _h = 4;\n}\n");
+    assert!(!output.reports("never used"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_plug_is_reported() {
+    let output = accepts("plug std::console.println;\nfunc main() {}\n");
+    assert!(output.reports("Plugged item 'println' is never used [unused_plug]\n  at 1:1"), "{}", output.stderr);
+}
+
+#[test]
+fn shadowing_is_reported() {
+    let output = accepts("func main() {\n    mk x = 1;\n    if x > 0 {\n        mk x = 2;\n        mk _y = x;\n    }\n}\n");
+    assert!(output.reports("'x' shadows a variable of an enclosing scope [shadowing]\n  at 4:9"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_function_is_reported() {
+    let output = accepts("func helper() {}\nfunc main() {}\n");
+    assert!(output.reports("Function 'helper' is never used and is not `visible` [unused_function]"), "{}", output.stderr);
+}
+
+#[test]
+fn allow_attribute_silences_a_lint() {
+    let output = accepts("@allow(\"unused_variable\")\nfunc main() {\n    mk h = 4;\n}\n");
+    assert!(!output.reports("never used"), "{}", output.stderr);
+}
+
+#[test]
+fn deny_attribute_fails_the_compilation() {
+    let output = check("@deny(\"unused_variable\")\nfunc main() {\n    mk h = 4;\n}\n");
+    assert!(!output.succeeded());
+    assert!(output.reports("Lint error: Variable 'h' is never used [unused_variable]"), "{}", output.stderr);
+}
+
+#[test]
+fn unknown_lint_in_an_attribute_is_an_error() {
+    let output = check("@allow(\"typo\")\nfunc main() {}\n");
+    assert!(!output.succeeded());
+    assert!(output.reports("Unknown lint 'typo'"), "{}", output.stderr);
+}
+
+#[test]
+fn project_config_sets_the_levels() {
+    let dir = workspace(&[("main.oa", "func main() {\n    mk h = 4;\n}\n"), ("omnia.lints", "# project lints\nunused_variable = deny\n")]);
+    let output = omnia(&dir, &["main.oa"]);
+    assert!(!output.succeeded());
+    assert!(output.reports("Lint error: Variable 'h' is never used"), "{}", output.stderr);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{accepts, check, rejects};
+
+#[test]
+fn mutable_variable_can_be_assigned() {
+    accepts("func main() {\n    mk mut x = 1;\n    x = 2;\n    mk _y = x;\n}\n");
+}
+
+#[test]
+fn assignment_to_an_immutable_variable_is_rejected() {
+    rejects("func main() {\n    mk x = 1;\n    x = 2;\n}\n", "Cannot assign to immutable 'x'; declare it as `mut x` to allow changes");
+}
+
+#[test]
+fn compound_assignment_to_an_immutable_variable_is_rejected() {
+    rejects("func main() {\n    mk x = 1;\n    x += 2;\n}\n", "Cannot assign to immutable 'x'");
+}
+
+#[test]
+fn immutability_error_points_at_the_declaration() {
+    let output = check("func main() {\n    mk x = 1;\n    x = 2;\n}\n");
+    assert!(!output.succeeded());
+    assert!(output.reports("  at 2:5: mk x = 1;"), "{}", output.stderr);
+}
+
+#[test]
+fn parameters_are_immutable() {
+    rejects("func f(x: int) {\n    x = 2;\n}\nfunc main() {\n    f(1);\n}\n", "Cannot assign to immutable 'x'");
+}
+
+#[test]
+fn assignment_to_a_function_is_rejected() {
+    rejects("func f() {}\nfunc main() {\n    f = 2;\n}\n", "'f' is not a variable and can't be assigned to");
+}
+
+#[test]
+fn nullable_value_is_rejected_where_a_value_is_required() {
+    rejects("func f(x: int?) -> int { x + 1 }\nfunc main() {\n    f(null);\n}\n", "may be null");
+}
+
+#[test]
+fn compound_assignment_to_a_nullable_variable_is_rejected() {
+    rejects("func main() {\n    int? mut x = null;\n    x += 1;\n}\n", "'x' of type int? may be null, check it with `if x != null` before using it");
+}
+
+#[test]
+fn null_check_narrows_the_rest_of_the_block() {
+    accepts("func f(x: int?) -> int {\n    if x == null {\n        return 0;\n    }\n    x + 1\n}\nfunc main() {\n    f(null);\n}\n");
+}
+
+#[test]
+fn variable_inferred_from_null_is_rejected() {
+    rejects("func main() {\n    mk x = null;\n}\n", "Cannot infer the type of 'x' from `null`, declare it with an explicit type");
+}
+
+#[test]
+fn increment_statements_change_a_mutable_variable() {
+    accepts("func main() {\n    mk mut x = 1;\n    x++;\n    x--;\n    mk _y = x;\n}\n");
+}
+
+#[test]
+fn increment_of_an_immutable_variable_is_rejected() {
+    rejects("func main() {\n    mk x = 1;\n    x++;\n}\n", "Cannot apply ++ to immutable 'x'");
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn plain_type_rejects_null() {
+    rejects("func f(x: int) -> int { x }\nfunc main() {\n    f(null);\n}\n", "Argument 'x' of 'f' expects int, got null");
+}
+
+#[test]
+fn nullable_type_takes_null_and_values() {
+    accepts("func f(x: int?) -> int? { x }\nfunc main() {\n    f(null);\n    f(1);\n}\n");
+}
+
+#[test]
+fn field_of_a_nullable_value_is_rejected() {
+    rejects("struct P { x: int }\nvisible func f(p: P?) -> int { p.x }\nfunc main() {}\n", "'p' of type P? may be null, check it with `if p != null` before using it");
+}
+
+#[test]
+fn null_check_narrows_the_branch() {
+    accepts("struct P { x: int }\nvisible func f(p: P?) -> int { if p != null { p.x } else { 0 } }\nfunc main() {}\n");
+}
+
+#[test]
+fn nullable_value_is_suggested_a_null_check_instead_of_a_cast() {
+    rejects("func f(x: int?) -> int { x }\nfunc main() {\n    f(1);\n}\n", "Cannot return a value of type int? from a function returning int; the value may be null, compare it with `null` before using it");
+    rejects("func f(x: long?) -> int { x }\nfunc main() {\n    f(1);\n}\n", "compare it with `null` before using it, then write it as a cast `as int`");
+}
+
+#[test]
+fn narrowed_variable_can_be_assigned_null() {
+    accepts("func main() {\n    int? mut x = 1;\n    if x != null {\n        mk _y = x + 1;\n        x = null;\n    }\n}\n");
+}
+
+#[test]
+fn narrowed_variable_assigned_null_needs_a_new_check() {
+    rejects("func main() {\n    int? mut x = 1;\n    if x != null {\n        x = null;\n        mk _y = x + 1;\n    }\n}\n", "'x' of type int? may be null");
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn overloads_are_resolved_by_the_argument_types() {
+    accepts("func show(x: int) -> int { x }\nfunc show(x: decimal) -> decimal { x }\nfunc main() {\n    mk a = show(1);\n    mk b = show(0.5);\n    int c = a;\n    decimal d = b;\n}\n");
+}
+
+#[test]
+fn overloads_with_the_same_parameter_types_are_a_redefinition() {
+    rejects("func show(x: int) -> int { x }\nfunc show(y: int) -> decimal { 0.5 }\nfunc main() {}\n", "Function 'show(int)' is already defined in this scope with the same parameter types");
+}
+
+#[test]
+fn call_matching_no_overload_lists_the_candidates() {
+    rejects("func show(x: int) -> int { x }\nfunc show(x: decimal) -> decimal { x }\nfunc main() {\n    show(\"a\");\n}\n", "No overload of 'show' accepts (char[]), candidates are show(int), show(decimal)");
+}
+
+#[test]
+fn ambiguous_call_is_rejected() {
+    rejects("func pick(x: long, y: int) -> int { 1 }\nfunc pick(x: int, y: long) -> int { 2 }\nfunc main() {\n    pick(1, 2);\n}\n", "Call of 'pick' with (int, int) is ambiguous between");
+}
+
+#[test]
+fn extension_methods_are_overloaded() {
+    accepts("struct Point { x: int }\next func scale(k: int) for Point -> int { k }\next func scale(k: decimal) for Point -> decimal { k }\nvisible func f(p: Point) -> decimal { p.scale(0.5) }\nfunc main() {}\n");
+}
+
+#[test]
+fn extension_method_with_the_same_parameter_types_is_a_redefinition() {
+    rejects("struct Point { x: int }\next func scale(k: int) for Point -> int { k }\next func scale(j: int) for Point -> int { j }\nfunc main() {}\n", "Method 'scale(int)' is already defined for Point with the same parameter types");
+}
+
+#[test]
+fn interface_method_is_not_overloaded_after_its_implementation() {
+    rejects("interface Shape { func area() -> decimal; }\nstruct Point { x: int }\next Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\next func area(x: int) for Point -> decimal { 0.5 }\nfunc main() {}\n", "Method 'area' of Point implements interface 'Shape' and can't be overloaded, found area() and area(int)");
+}
+
+#[test]
+fn interface_method_is_not_overloaded_before_its_implementation() {
+    rejects("interface Shape { func area() -> decimal; }\nstruct Point { x: int }\next func area(x: int) for Point -> decimal { 0.5 }\next Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\nfunc main() {}\n", "Method 'area' of Point implements interface 'Shape' and can't be overloaded, found area(int) and area()");
+}
+
+#[test]
+fn other_methods_of_an_implementing_type_are_overloaded() {
+    accepts("interface Shape { func area() -> decimal; }\nstruct Point { x: int }\next Shape for Point {\n    override func area() -> decimal { 1.5 }\n}\next func scale(k: int) for Point -> int { k }\next func scale(k: decimal) for Point -> decimal { k }\nfunc main() {}\n");
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{accepts, omnia, read, rejects, workspace};
+
+#[test]
+fn plug_brings_the_last_segment_into_scope() {
+    accepts("plug std::console.println;\nfunc main() {\n    println(\"a\");\n}\n");
+}
+
+#[test]
+fn plug_list_brings_every_item_into_scope() {
+    let output = accepts("plug std::console.println|print;\nfunc main() {\n    println(\"a\");\n    print(\"b\");\n}\n");
+    assert!(!output.reports("never used"), "{}", output.stderr);
+}
+
+#[test]
+fn unused_item_of_a_plug_list_is_reported_by_name() {
+    let output = accepts("plug std::console.println|reader;\nfunc main() {\n    println(\"a\");\n}\n");
+    assert!(output.reports("Plugged item 'reader' is never used [unused_plug]"), "{}", output.stderr);
+    assert!(!output.reports("'println' is never used"), "{}", output.stderr);
+}
+
+#[test]
+fn unplugged_function_is_not_defined() {
+    rejects("plug std::console.println;\nfunc main() {\n    print(\"a\");\n}\n", "Function 'print' is not defined");
+}
+
+#[test]
+fn item_plugged_twice_is_a_redefinition() {
+    rejects("plug std::console.println;\nplug std::console.print|println;\nfunc main() {\n    println(\"a\");\n}\n", "Symbol 'println' already defined");
+}
+
+#[test]
+fn plug_list_is_kept_by_the_formatter() {
+    let source = "plug std::console.println|reader;\nfunc main() {\n    println(\"a\");\n}\n";
+    let dir = workspace(&[("main.oa", source)]);
+    omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(read(&dir, "main.oa"), source);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{accepts, rejects};
+
+#[test]
+fn smaller_integers_widen() {
+    accepts("func f(x: byte) -> long { x }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn integers_widen_to_decimal() {
+    accepts("func f(x: long) -> decimal { x }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn unsigned_widens_to_the_next_signed_size() {
+    accepts("func f(x: uint) -> long { x }\nfunc main() {\n    f(1);\n}\n");
+    rejects("func f(x: uint) -> int { x }\nfunc main() {\n    f(1);\n}\n", "Cannot return a value of type uint from a function returning int; the conversion may lose information, write it as a cast `as int`");
+}
+
+#[test]
+fn signed_never_becomes_unsigned_implicitly() {
+    rejects("func f(x: int) -> uint { x }\nfunc main() {\n    f(1);\n}\n", "write it as a cast `as uint`");
+}
+
+#[test]
+fn decimal_does_not_narrow_to_an_integer() {
+    rejects("func f(x: decimal) -> long { x }\nfunc main() {\n    f(1.5);\n}\n", "write it as a cast `as long`");
+}
+
+#[test]
+fn arguments_are_checked_against_the_parameters() {
+    accepts("func add(a: decimal, b: decimal) -> decimal { a + b }\nfunc main() {\n    add(1, 2);\n}\n");
+    rejects("func add(a: decimal, b: decimal) -> decimal { a + b }\nfunc main() {\n    add(\"a\", 1.5);\n}\n", "Argument 'a' of 'add' expects decimal, got char[]");
+}
+
+#[test]
+fn if_condition_must_be_bool() {
+    rejects("func main() {\n    if 1 {}\n}\n", "Condition must be bool, got int");
+}
+
+#[test]
+fn binary_operators_chain_to_the_left() {
+    accepts("static int X = 10 - 3 - 2;\nfunc main() {}\n");
+    rejects("static ubyte X = (4 as ubyte) - (3 as ubyte) - (2 as ubyte);\nfunc main() {}\n", "Constant -1 overflows ubyte");
+    rejects("func f(a: int, b: int, c: bool) -> int { a * b * c }\nfunc main() {\n    f(1, 2, true);\n}\n", "Cannot apply * to int and bool");
+}
+
+#[test]
+fn arithmetic_takes_numbers() {
+    rejects("func f(a: bool, b: bool) -> bool { a + b }\nfunc main() {\n    f(true, false);\n}\n", "Cannot apply + to bool and bool");
+    rejects("func f(a: char[], b: char[]) -> int { a - b }\nfunc main() {\n    f(\"a\", \"b\");\n}\n", "Cannot apply - to char[] and char[]");
+}
+
+#[test]
+fn ordering_takes_numbers_or_chars() {
+    accepts("visible func f(a: char, b: char) -> bool { a < b }\nfunc g(a: byte, b: decimal) -> bool { a >= b }\nfunc main() {\n    g(1, 2.5);\n}\n");
+    rejects("func f(a: bool, b: bool) -> bool { a > b }\nfunc main() {\n    f(true, false);\n}\n", "Cannot apply > to bool and bool, only numbers and chars are ordered");
+    rejects("func f(a: char[], b: char[]) -> bool { a <= b }\nfunc main() {\n    f(\"a\", \"b\");\n}\n", "Cannot apply <= to char[] and char[]");
+}
+
+#[test]
+fn type_parameters_are_neither_added_nor_ordered() {
+    rejects("func f<T>(a: T, b: T) -> T { a + b }\nfunc main() {\n    f(1, 2);\n}\n", "Cannot apply + to T and T");
+    rejects("func f<T>(a: T, b: T) -> bool { a > b }\nfunc main() {\n    f(1, 2);\n}\n", "Cannot apply > to T and T");
+}
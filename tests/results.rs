@@ -0,0 +1,34 @@
+mod common;
+
+use common::{accepts, rejects};
+
+const ERROR: &str = "enum ParseError { Negative }\nfunc parse(x: int) -> int ! ParseError { if x > 0 { ok(x) } else { err(ParseError::Negative) } }\n";
+
+fn program(body: &str) -> String {
+    format!("{}{}", ERROR, body)
+}
+
+#[test]
+fn result_is_propagated_and_matched() {
+    accepts(&program("func twice(x: int) -> int ! ParseError {\n    mk v = parse(x)!;\n    ok(v * 2)\n}\nfunc main() {\n    switch twice(2) {\n        ok(v) -> { mk _w = v; }\n        err(_) -> {}\n    }\n}\n"));
+}
+
+#[test]
+fn discarded_result_is_rejected() {
+    rejects(&program("func main() {\n    parse(1);\n}\n"), "Result of 'parse' of type int ! ParseError is discarded, handle it with `switch`, propagate it with `!` or bind it with `mk`");
+}
+
+#[test]
+fn propagation_needs_a_function_returning_a_result() {
+    rejects(&program("func twice(x: int) -> int {\n    mk v = parse(x)!;\n    v\n}\nfunc main() {\n    twice(1);\n}\n"), "`!` can only be used in functions returning a result, this one returns int");
+}
+
+#[test]
+fn propagated_error_must_fit_the_error_type() {
+    rejects(&program("func twice(x: int) -> int ! int {\n    mk v = parse(x)!;\n    ok(v)\n}\nfunc main() {\n    mk _r = twice(1);\n}\n"), "`!` would return an error of type ParseError from a function whose error type is int");
+}
+
+#[test]
+fn only_results_are_propagated() {
+    rejects("func f(x: int) -> int {\n    mk _v = x!;\n    x\n}\nfunc main() {\n    f(1);\n}\n", "`!` propagates errors of results, got a value of type int");
+}
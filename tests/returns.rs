@@ -0,0 +1,71 @@
+mod common;
+
+use common::{accepts, check, rejects};
+
+#[test]
+fn return_statement_takes_its_semicolon() {
+    accepts("func f(x: int) -> int {\n    return x;\n}\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn return_without_semicolon_ends_a_block() {
+    accepts("func f(x: int) -> int { return x }\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn tail_call_is_the_value_of_the_body() {
+    accepts("func f(x: int) -> int { return x; }\nfunc g(v: int) -> int { f(v) }\nfunc main() {\n    g(1);\n}\n");
+}
+
+#[test]
+fn tail_call_can_start_a_longer_value() {
+    accepts("func f(x: int) -> int { x }\nfunc g(v: int) -> int { f(v)/f(2) }\nfunc main() {\n    g(1);\n}\n");
+}
+
+#[test]
+fn tail_method_call_is_the_value_of_the_body() {
+    accepts("struct Point { x: int }\next func area() for Point -> int { 1 }\nvisible func size(s: Point) -> int { s.area() }\nfunc main() {}\n");
+}
+
+#[test]
+fn literal_starts_a_tail_value() {
+    accepts("func one() -> int { 1 }\nfunc half() -> decimal { 0.5 }\nfunc main() {\n    one();\n    half();\n}\n");
+}
+
+#[test]
+fn tail_call_is_checked_against_the_return_type() {
+    rejects("func f() -> decimal { return 1.5; }\nfunc g() -> int { f() }\nfunc main() {\n    g();\n}\n", "Cannot return a value of type decimal from a function returning int");
+}
+
+#[test]
+fn tail_value_of_a_function_without_one_is_dropped() {
+    accepts("func f() -> int { return 1; }\nfunc main() { f() }\n");
+}
+
+#[test]
+fn missing_return_on_a_path_is_reported() {
+    rejects("func f(c: int) -> int {\n    if c > 0 {\n        return 1;\n    }\n}\nfunc main() {\n    f(1);\n}\n", "Missing return: Function 'f' returns int");
+}
+
+#[test]
+fn returns_on_both_branches_are_enough() {
+    accepts("func f(c: int) -> int {\n    if c > 0 {\n        return 1;\n    } else {\n        return 2;\n    }\n}\nfunc main() {\n    f(1);\n}\n");
+}
+
+#[test]
+fn lambda_bodies_must_return_too() {
+    rejects("func main() {\n    mk f = (x: int) -> int >> { mk y = x; };\n    f(1);\n}\n", "Missing return: Lambda returns int");
+}
+
+#[test]
+fn statement_after_return_is_unreachable() {
+    let output = check("func f() -> int {\n    return 1;\n    mk x = 2;\n}\nfunc main() {\n    f();\n}\n");
+    assert!(output.reports("this statement can never run"), "{}", output.stderr);
+    assert!(output.reports("at 3:5"), "{}", output.stderr);
+}
+
+#[test]
+fn tail_call_in_a_branch_does_not_exit_a_function_without_a_value() {
+    let output = accepts("plug std::console.println;\nfunc main() {\n    if 1 > 0 {\n        println(\"one\")\n    }\n    println(\"two\");\n}\n");
+    assert!(!output.reports("can never run"), "{}", output.stderr);
+}
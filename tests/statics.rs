@@ -0,0 +1,70 @@
+mod common;
+
+use common::{accepts, omnia, read, rejects, workspace};
+
+#[test]
+fn constant_is_folded_with_omnia_shifts() {
+    accepts("static int MAX = 1 <| 10;\nstatic int HALF = MAX |> 1;\nfunc main() {}\n");
+}
+
+#[test]
+fn c_style_shifts_are_accepted() {
+    accepts("static int MAX = 1 << 10;\nstatic int HALF = MAX >> 1;\nvisible func f(x: int) -> int { x >> 2 }\nfunc main() {}\n");
+}
+
+#[test]
+fn c_style_shifts_are_formatted_as_omnia_shifts() {
+    let dir = workspace(&[("main.oa", "static int MAX = 1 << 10;\nstatic int HALF = MAX >> 1;\nfunc main() {}\n")]);
+    omnia(&dir, &["fmt", "main.oa"]);
+    assert_eq!(read(&dir, "main.oa"), "static int MAX = 1 <| 10;\nstatic int HALF = MAX |> 1;\nfunc main() {}\n");
+}
+
+#[test]
+fn lambda_body_still_follows_its_return_type() {
+    accepts("func main() {\n    mk f = (x: int) -> int >> { x >> 1 };\n    f(4);\n}\n");
+}
+
+#[test]
+fn shift_out_of_range_is_rejected() {
+    rejects("static int MAX = 1 << 40;\nfunc main() {}\n", "Constant evaluation: Cannot shift a int by 40 bits");
+}
+
+#[test]
+fn constant_must_be_at_the_top_level() {
+    rejects("func main() {\n    static int M = 1;\n}\n", "`static` constant 'M' must be declared at the top level");
+}
+
+#[test]
+fn constant_sizes_a_local_array() {
+    accepts("static int MAX = 1 << 10;\nvisible func f(a: int[1024]) -> int[1024] {\n    int[MAX] arr = a;\n    return arr;\n}\nfunc main() {}\n");
+}
+
+#[test]
+fn local_array_size_is_checked() {
+    rejects("static int MAX = 1 << 10;\nvisible func f(a: int[1023]) {\n    int[MAX] arr = a;\n}\nfunc main() {}\n", "Cannot initialize 'arr' of type int[1024] with a value of type int[1023]");
+}
+
+#[test]
+fn local_array_size_must_be_a_constant() {
+    rejects("visible func f(a: int[4]) {\n    int[N] arr = a;\n}\nfunc main() {}\n", "Array size 'N' is not a static constant");
+}
+
+#[test]
+fn nullable_declarations_are_parsed() {
+    accepts("func main() {\n    int? x = null;\n    decimal[2]? pair = null;\n}\n");
+}
+
+#[test]
+fn omni_constant_is_folded() {
+    accepts("static omni O = 1;\nstatic omni P = O * 2 + 0.5;\nstatic bool BIGGER = P > O;\nstatic int I = P as saturating int;\nfunc main() {}\n");
+}
+
+#[test]
+fn omni_constant_is_not_narrowed_implicitly() {
+    rejects("static omni O = 1;\nstatic int I = O;\nfunc main() {}\n", "Cannot initialize constant 'I' of type int with a value of type omni");
+}
+
+#[test]
+fn bool_constants_are_compared() {
+    accepts("static bool B = 1 == 1;\nstatic bool C = B == B;\nfunc main() {}\n");
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::{accepts, rejects};
+
+const DIVMOD: &str = "func divmod(a: int, b: int) -> (int, int) { (a / b, a % b) }\n";
+
+#[test]
+fn tuple_return_is_destructured() {
+    accepts(&format!("{}func main() {{\n    mk (q, r) = divmod(7, 2);\n    mk _s = q + r;\n}}\n", DIVMOD));
+}
+
+#[test]
+fn destructuring_must_bind_every_element() {
+    rejects(&format!("{}func main() {{\n    mk (q, r, s) = divmod(7, 2);\n}}\n", DIVMOD), "Tuple of type (int, int) has 2 elements, but 3 names are bound");
+}
+
+#[test]
+fn only_tuples_are_destructured() {
+    rejects("func main() {\n    mk (q, r) = 5;\n}\n", "Only tuples can be destructured, got a value of type int");
+}
+
+#[test]
+fn tuple_literal_must_match_the_return_type() {
+    rejects("func pair() -> (int, decimal) { (1, \"a\") }\nfunc main() {\n    mk (_a, _b) = pair();\n}\n", "Cannot return a value of type (int, char[]) from a function returning (int, decimal)");
+}
+
+#[test]
+fn destructured_names_take_the_element_types() {
+    rejects("func main() {\n    mk (_, r) = (1, \"a\");\n    mk _n = r + 1;\n}\n", "Cannot apply + to char[] and int ('r' is inferred as char[])");
+}